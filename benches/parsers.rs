@@ -0,0 +1,81 @@
+//! Parsing cost of the inputs read on every refresh, at sizes far beyond a
+//! typical system: a kernel exporting thousands of extra kstats and a wide pool
+//! listing thousands of disks ahead of its log device.
+
+use async_trait::async_trait;
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::error::Error;
+use zfs_cache_monitor_rs::system::commands::DemoCommandExecutor;
+use zfs_cache_monitor_rs::system::filesystem::DemoFilesystemReader;
+use zfs_cache_monitor_rs::system::{CommandExecutor, FilesystemReader};
+use zfs_cache_monitor_rs::zfs::ZfsStatsCollector;
+
+const EXTRA_ROWS: usize = 20_000;
+
+/// Demo files with `EXTRA_ROWS` made-up kstats appended to arcstats
+struct LargeArcstatsReader(String);
+
+#[async_trait]
+impl FilesystemReader for LargeArcstatsReader {
+    async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        match path {
+            "/proc/spl/kstat/zfs/arcstats" => Ok(self.0.clone()),
+            _ => DemoFilesystemReader.read_to_string(path).await,
+        }
+    }
+}
+
+/// Demo commands with `EXTRA_ROWS` disks listed ahead of the log device in the
+/// `zpool iostat -v` table, and no scripted (`-H -p`) output to use instead
+struct WideIostatExecutor(String);
+
+#[async_trait]
+impl CommandExecutor for WideIostatExecutor {
+    async fn execute(&self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+        match (command, args) {
+            ("zpool", ["iostat", "-v", "-H", "-p", ..]) => Err("invalid option 'H'".into()),
+            ("zpool", ["iostat", "-v", ..]) => Ok(self.0.clone()),
+            _ => DemoCommandExecutor.execute(command, args).await,
+        }
+    }
+}
+
+fn large_arcstats() -> String {
+    let mut content = include_str!("../src/demo/arcstats.txt").to_string();
+    for i in 0..EXTRA_ROWS {
+        content.push_str(&format!("\nextra_kstat_{:<20} 4    {}", i, i));
+    }
+    content
+}
+
+fn wide_iostat() -> String {
+    let mut output = String::from("data        3.45T  2.55T     47     23   234M  12.0M\n");
+    for i in 0..EXTRA_ROWS {
+        output.push_str(&format!(
+            "  disk{:<6}  1.2G   230G      3      1   1.0M  12.0K\n",
+            i
+        ));
+    }
+    output.push_str("logs            -      -      -      -      -      -\n");
+    output.push_str("  mirror-1     0B  1.82T      0     23      0  12.0M\n");
+    output
+}
+
+fn parsers(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    let mut collector =
+        ZfsStatsCollector::new(DemoCommandExecutor, LargeArcstatsReader(large_arcstats()));
+    c.bench_function("arcstats with 20k extra kstats", |b| {
+        b.iter(|| runtime.block_on(collector.collect_arc_stats()).unwrap())
+    });
+
+    let mut collector =
+        ZfsStatsCollector::new(WideIostatExecutor(wide_iostat()), DemoFilesystemReader);
+    c.bench_function("SLOG behind 20k disks in zpool iostat -v", |b| {
+        b.iter(|| runtime.block_on(collector.collect_slog_stats()).unwrap())
+    });
+}
+
+criterion_group!(benches, parsers);
+criterion_main!(benches);
@@ -0,0 +1,717 @@
+//! Command line argument parsing (manual, no clap)
+
+use crate::display::{ColorMode, NumberFormat, Theme, Units};
+use crate::system::commands::DEFAULT_COMMAND_TIMEOUT;
+use crate::zfs::{ArcstatReads, CacheThresholds};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Default refresh interval in seconds
+const DEFAULT_INTERVAL: u32 = 2;
+
+/// Default delay between the silent priming sample and the first frame, in milliseconds
+const DEFAULT_WARMUP_MS: u64 = 500;
+
+/// Output format for each refresh
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OutputFormat {
+    /// Interactive dashboard
+    #[default]
+    Text,
+    /// One JSON document per refresh
+    Json,
+    /// The overview's columns as an aligned plain-text table, also for one pool
+    Table,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "table" => Ok(OutputFormat::Table),
+            _ => Err(format!(
+                "Invalid format '{}': expected text, json or table",
+                s
+            )),
+        }
+    }
+}
+
+/// Parsed command line arguments: `[POOL] [INTERVAL] [OPTIONS]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliArgs {
+    pub pool: Option<String>,
+    pub interval: u32,
+    pub thresholds: CacheThresholds,
+    pub format: OutputFormat,
+    pub list_pools: bool,
+    /// Print the unit and meaning of every `--format json` metric, then exit
+    pub list_metrics: bool,
+    pub demo_static: bool,
+    pub short_names: bool,
+    pub color: ColorMode,
+    /// Fade the bars' filled cells from red to green instead of one color
+    pub gradient_bars: bool,
+    pub units: Units,
+    /// Section labels and colors
+    pub theme: Theme,
+    /// TOML file replacing `theme`, loaded after parsing
+    pub theme_file: Option<PathBuf>,
+    /// Print operation counts as plain integers, for scripts scraping the output
+    pub raw_numbers: bool,
+    /// Show bandwidth in bits per second (Mbps) instead of bytes per second
+    pub bits: bool,
+    /// Whether the arcstat fallback's `read` column is cumulative or per second
+    pub arcstat_reads: ArcstatReads,
+    /// Show rates as averages since boot (counter / uptime) instead of the change
+    /// between refreshes
+    pub since_boot_rates: bool,
+    /// How long `zpool`, `arcstat` and co. may run before a refresh gives up on them
+    pub command_timeout: Duration,
+    pub no_l2arc: bool,
+    pub no_slog: bool,
+    pub output: Option<PathBuf>,
+    pub append: bool,
+    /// File replaced with the latest frame on every refresh
+    pub snapshot_file: Option<PathBuf>,
+    /// Directory to keep the unparsed arcstats and `zpool` output of every sample in
+    pub dump_raw: Option<PathBuf>,
+    pub once: bool,
+    pub doctor: bool,
+    pub overview: bool,
+    /// Pools to leave out of `--overview`; an explicitly named pool is kept
+    pub exclude_pools: Vec<String>,
+    /// Move on to the default pool when the monitored one is no longer imported
+    pub follow_new_pool: bool,
+    /// Render captured `--format json` output instead of collecting live
+    pub replay: Option<PathBuf>,
+    /// Pace `--replay` by the recorded timestamps at this many times real speed,
+    /// 0 steps one frame per Enter; None keeps refreshing every `interval`
+    pub replay_speed: Option<f64>,
+    /// Stop after this many refreshes, 0 runs until interrupted
+    pub count: u64,
+    /// Keep showing a dashboard section until one of its numbers moves by more
+    /// than this many percent
+    pub min_change: Option<f64>,
+    /// Refresh on a fixed cadence instead of sleeping `interval` after each refresh
+    pub high_precision: bool,
+    /// Widen the refresh interval whenever collecting takes too much of it
+    pub adaptive: bool,
+    /// Milliseconds between a silent priming sample and the first frame, so it
+    /// already has rates; 0 skips priming
+    pub warmup_ms: u64,
+    /// Program and arguments every ZFS command is run through (e.g. `nsenter -t 1 -m --`)
+    pub command_prefix: Vec<String>,
+    /// Directory filesystem reads are made below instead of `/`, e.g. the host root
+    pub fs_root: Option<PathBuf>,
+    /// `user@host` to collect from over ssh instead of this machine
+    pub remote: Option<String>,
+    /// Print a line only when the cache status worsens or recovers, not the dashboard
+    pub quiet: bool,
+    /// Dataset whose own reads and writes get a section, e.g. `data/home`
+    pub dataset: Option<String>,
+    /// One plain-text summary of every section with an interpretation, then exit
+    pub report: bool,
+}
+
+impl Default for CliArgs {
+    fn default() -> Self {
+        Self {
+            pool: None,
+            interval: DEFAULT_INTERVAL,
+            thresholds: CacheThresholds::default(),
+            format: OutputFormat::default(),
+            list_pools: false,
+            list_metrics: false,
+            demo_static: false,
+            short_names: false,
+            color: ColorMode::default(),
+            gradient_bars: false,
+            units: Units::default(),
+            theme: Theme::default(),
+            theme_file: None,
+            raw_numbers: false,
+            bits: false,
+            arcstat_reads: ArcstatReads::default(),
+            since_boot_rates: false,
+            command_timeout: DEFAULT_COMMAND_TIMEOUT,
+            no_l2arc: false,
+            no_slog: false,
+            output: None,
+            append: false,
+            snapshot_file: None,
+            dump_raw: None,
+            once: false,
+            doctor: false,
+            count: 0,
+            min_change: None,
+            high_precision: false,
+            adaptive: false,
+            warmup_ms: DEFAULT_WARMUP_MS,
+            command_prefix: Vec::new(),
+            fs_root: None,
+            remote: None,
+            quiet: false,
+            dataset: None,
+            report: false,
+            overview: false,
+            exclude_pools: Vec::new(),
+            follow_new_pool: false,
+            replay: None,
+            replay_speed: None,
+        }
+    }
+}
+
+impl CliArgs {
+    /// Parse arguments, excluding the program name
+    #[cfg(test)]
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        Self::parse_with_defaults(args, Self::default())
+    }
+
+    /// Parse arguments on top of `defaults` (e.g. values from the config file)
+    pub fn parse_with_defaults(args: &[String], defaults: Self) -> Result<Self, String> {
+        let mut parsed = defaults;
+        let mut positional = Vec::new();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if !arg.starts_with("--") {
+                positional.push(arg.as_str());
+                continue;
+            }
+
+            // Support both "--flag value" and "--flag=value"
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| iter.next().cloned())
+                    .ok_or_else(|| format!("Missing value for {}", flag))
+            };
+
+            match flag {
+                "--thresholds" => parsed.thresholds = value()?.parse()?,
+                "--format" => parsed.format = value()?.parse()?,
+                "--interval" => {
+                    let interval = value()?;
+                    parsed.interval = interval
+                        .parse()
+                        .map_err(|_| format!("Invalid interval '{}'", interval))?;
+                }
+                "--list-pools" => parsed.list_pools = true,
+                "--list-metrics" => parsed.list_metrics = true,
+                "--demo-static" => parsed.demo_static = true,
+                "--short-names" => parsed.short_names = true,
+                "--color" => parsed.color = value()?.parse()?,
+                "--gradient-bars" => parsed.gradient_bars = true,
+                "--units" => parsed.units = value()?.parse()?,
+                "--theme" => {
+                    let name = value()?;
+                    parsed.theme = Theme::builtin(&name).ok_or_else(|| {
+                        format!("Invalid theme '{}': expected default or plain", name)
+                    })?;
+                }
+                "--theme-file" => parsed.theme_file = Some(PathBuf::from(value()?)),
+                "--raw-numbers" => parsed.raw_numbers = true,
+                "--bits" => parsed.bits = true,
+                "--arcstat-reads" => parsed.arcstat_reads = value()?.parse()?,
+                "--since-boot-rates" => parsed.since_boot_rates = true,
+                "--instantaneous-rates" => parsed.since_boot_rates = false,
+                "--command-timeout" => {
+                    let timeout = value()?;
+                    let secs = timeout
+                        .parse::<u64>()
+                        .ok()
+                        .filter(|&secs| secs > 0)
+                        .ok_or_else(|| format!("Invalid command timeout '{}'", timeout))?;
+                    parsed.command_timeout = Duration::from_secs(secs);
+                }
+                "--min-change" => {
+                    let percent = value()?;
+                    parsed.min_change = Some(
+                        percent
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|percent| percent.is_finite() && *percent >= 0.0)
+                            .ok_or_else(|| format!("Invalid minimum change '{}'", percent))?,
+                    );
+                }
+                "--no-l2arc" => parsed.no_l2arc = true,
+                "--no-slog" => parsed.no_slog = true,
+                "--output" => parsed.output = Some(PathBuf::from(value()?)),
+                "--append" => parsed.append = true,
+                "--snapshot-file" => parsed.snapshot_file = Some(PathBuf::from(value()?)),
+                "--dump-raw" => parsed.dump_raw = Some(PathBuf::from(value()?)),
+                "--once" => parsed.once = true,
+                "--doctor" => parsed.doctor = true,
+                "--overview" => parsed.overview = true,
+                "--high-precision" => parsed.high_precision = true,
+                "--adaptive" => parsed.adaptive = true,
+                "--quiet" => parsed.quiet = true,
+                "--dataset" => parsed.dataset = Some(value()?),
+                "--report" => parsed.report = true,
+                "--exclude-pool" => parsed.exclude_pools.push(value()?),
+                "--follow-new-pool" => parsed.follow_new_pool = true,
+                "--replay" => parsed.replay = Some(PathBuf::from(value()?)),
+                "--replay-speed" => {
+                    let speed = value()?;
+                    parsed.replay_speed = Some(
+                        speed
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|speed| speed.is_finite() && *speed >= 0.0)
+                            .ok_or_else(|| format!("Invalid replay speed '{}'", speed))?,
+                    );
+                }
+                "--warmup" => {
+                    let warmup = value()?;
+                    parsed.warmup_ms = warmup
+                        .parse()
+                        .map_err(|_| format!("Invalid warmup '{}'", warmup))?;
+                }
+                "--command-prefix" => {
+                    parsed.command_prefix =
+                        value()?.split_whitespace().map(str::to_string).collect();
+                }
+                "--fs-root" => parsed.fs_root = Some(PathBuf::from(value()?)),
+                "--remote" => {
+                    let destination = value()?;
+                    // ssh would take a leading dash for one of its own options
+                    if destination.is_empty() || destination.starts_with('-') {
+                        return Err(format!("Invalid remote host '{}'", destination));
+                    }
+                    parsed.remote = Some(destination);
+                }
+                "--count" => {
+                    let count = value()?;
+                    parsed.count = count
+                        .parse()
+                        .map_err(|_| format!("Invalid count '{}'", count))?;
+                }
+                _ => return Err(format!("Unknown option: {}", flag)),
+            }
+        }
+
+        if let Some(pool) = positional.first() {
+            parsed.pool = Some(pool.to_string());
+        }
+        if let Some(interval) = positional.get(1) {
+            parsed.interval = interval.parse::<u32>().unwrap_or(parsed.interval);
+        }
+
+        Ok(parsed)
+    }
+
+    /// How the dashboard sections render sizes, rates and operation counts
+    pub fn number_format(&self) -> NumberFormat {
+        NumberFormat {
+            units: self.units,
+            raw: self.raw_numbers,
+            bits: self.bits,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let parsed = CliArgs::parse(&[]).unwrap();
+        assert_eq!(parsed, CliArgs::default());
+        assert_eq!(parsed.interval, 2);
+    }
+
+    #[test]
+    fn test_parse_positional() {
+        let parsed = CliArgs::parse(&args(&["tank", "5"])).unwrap();
+        assert_eq!(parsed.pool.as_deref(), Some("tank"));
+        assert_eq!(parsed.interval, 5);
+    }
+
+    #[test]
+    fn test_parse_thresholds() {
+        let parsed = CliArgs::parse(&args(&["tank", "--thresholds", "90,75,60"])).unwrap();
+        assert_eq!(parsed.pool.as_deref(), Some("tank"));
+        assert_eq!(parsed.thresholds.excellent, 90.0);
+        assert_eq!(parsed.thresholds.good, 75.0);
+        assert_eq!(parsed.thresholds.fair, 60.0);
+
+        let parsed = CliArgs::parse(&args(&["--thresholds=95,80,50"])).unwrap();
+        assert_eq!(parsed.thresholds.excellent, 95.0);
+    }
+
+    #[test]
+    fn test_parse_format() {
+        let parsed = CliArgs::parse(&args(&["--format", "json"])).unwrap();
+        assert_eq!(parsed.format, OutputFormat::Json);
+
+        let parsed = CliArgs::parse(&args(&["--format=text"])).unwrap();
+        assert_eq!(parsed.format, OutputFormat::Text);
+
+        let parsed = CliArgs::parse(&args(&["--format", "table"])).unwrap();
+        assert_eq!(parsed.format, OutputFormat::Table);
+
+        assert!(CliArgs::parse(&args(&["--format", "xml"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_list_pools() {
+        assert!(!CliArgs::parse(&[]).unwrap().list_pools);
+        assert!(CliArgs::parse(&args(&["--list-pools"])).unwrap().list_pools);
+    }
+
+    #[test]
+    fn test_parse_list_metrics() {
+        assert!(!CliArgs::parse(&[]).unwrap().list_metrics);
+        assert!(
+            CliArgs::parse(&args(&["--list-metrics"]))
+                .unwrap()
+                .list_metrics
+        );
+    }
+
+    #[test]
+    fn test_parse_demo_static() {
+        assert!(!CliArgs::parse(&[]).unwrap().demo_static);
+        assert!(
+            CliArgs::parse(&args(&["--demo-static"]))
+                .unwrap()
+                .demo_static
+        );
+    }
+
+    #[test]
+    fn test_parse_short_names() {
+        assert!(!CliArgs::parse(&[]).unwrap().short_names);
+        assert!(
+            CliArgs::parse(&args(&["--short-names"]))
+                .unwrap()
+                .short_names
+        );
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(CliArgs::parse(&[]).unwrap().color, ColorMode::Auto);
+        let parsed = CliArgs::parse(&args(&["--color", "always"])).unwrap();
+        assert_eq!(parsed.color, ColorMode::Always);
+        let parsed = CliArgs::parse(&args(&["--color=never"])).unwrap();
+        assert_eq!(parsed.color, ColorMode::Never);
+        assert!(CliArgs::parse(&args(&["--color", "sometimes"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_gradient_bars() {
+        assert!(!CliArgs::parse(&[]).unwrap().gradient_bars);
+        let parsed = CliArgs::parse(&args(&["--gradient-bars"])).unwrap();
+        assert!(parsed.gradient_bars);
+    }
+
+    #[test]
+    fn test_parse_interval_flag() {
+        let parsed = CliArgs::parse(&args(&["--interval", "10"])).unwrap();
+        assert_eq!(parsed.pool, None);
+        assert_eq!(parsed.interval, 10);
+        assert!(CliArgs::parse(&args(&["--interval=soon"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_section_toggles() {
+        let parsed = CliArgs::parse(&[]).unwrap();
+        assert!(!parsed.no_l2arc);
+        assert!(!parsed.no_slog);
+
+        let parsed = CliArgs::parse(&args(&["--no-l2arc", "--no-slog"])).unwrap();
+        assert!(parsed.no_l2arc);
+        assert!(parsed.no_slog);
+    }
+
+    #[test]
+    fn test_parse_output() {
+        let parsed = CliArgs::parse(&args(&[])).unwrap();
+        assert_eq!(parsed.output, None);
+        assert!(!parsed.append);
+        assert!(!parsed.once);
+
+        let parsed =
+            CliArgs::parse(&args(&["--output", "/tmp/zfs.log", "--append", "--once"])).unwrap();
+        assert_eq!(parsed.output, Some(PathBuf::from("/tmp/zfs.log")));
+        assert!(parsed.append);
+        assert!(parsed.once);
+
+        assert!(CliArgs::parse(&args(&["--output"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_doctor() {
+        assert!(!CliArgs::parse(&[]).unwrap().doctor);
+        assert!(CliArgs::parse(&args(&["--doctor"])).unwrap().doctor);
+    }
+
+    #[test]
+    fn test_parse_exclude_pool() {
+        assert!(CliArgs::parse(&[]).unwrap().exclude_pools.is_empty());
+        let parsed = CliArgs::parse(&args(&[
+            "--exclude-pool",
+            "boot-pool",
+            "--exclude-pool=usb-backup",
+        ]))
+        .unwrap();
+        assert_eq!(parsed.exclude_pools, vec!["boot-pool", "usb-backup"]);
+        assert!(CliArgs::parse(&args(&["--exclude-pool"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_high_precision() {
+        assert!(!CliArgs::parse(&[]).unwrap().high_precision);
+        assert!(
+            CliArgs::parse(&args(&["--high-precision"]))
+                .unwrap()
+                .high_precision
+        );
+    }
+
+    #[test]
+    fn test_parse_snapshot_file() {
+        assert_eq!(CliArgs::parse(&[]).unwrap().snapshot_file, None);
+        assert_eq!(
+            CliArgs::parse(&args(&["--snapshot-file", "/run/zfs-cache.txt"]))
+                .unwrap()
+                .snapshot_file,
+            Some(PathBuf::from("/run/zfs-cache.txt"))
+        );
+    }
+
+    #[test]
+    fn test_parse_dump_raw() {
+        assert_eq!(CliArgs::parse(&[]).unwrap().dump_raw, None);
+        assert_eq!(
+            CliArgs::parse(&args(&["--dump-raw", "/tmp/zfs-raw"]))
+                .unwrap()
+                .dump_raw,
+            Some(PathBuf::from("/tmp/zfs-raw"))
+        );
+    }
+
+    #[test]
+    fn test_parse_warmup() {
+        assert_eq!(CliArgs::parse(&[]).unwrap().warmup_ms, 500);
+        assert_eq!(
+            CliArgs::parse(&args(&["--warmup", "0"])).unwrap().warmup_ms,
+            0
+        );
+        assert_eq!(
+            CliArgs::parse(&args(&["--warmup=250"])).unwrap().warmup_ms,
+            250
+        );
+        assert!(CliArgs::parse(&args(&["--warmup", "soon"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_container_options() {
+        let parsed = CliArgs::parse(&args(&[
+            "--command-prefix",
+            "nsenter -t 1 -m --",
+            "--fs-root=/host",
+        ]))
+        .unwrap();
+        assert_eq!(parsed.command_prefix, ["nsenter", "-t", "1", "-m", "--"]);
+        assert_eq!(parsed.fs_root, Some(PathBuf::from("/host")));
+
+        let defaults = CliArgs::parse(&[]).unwrap();
+        assert!(defaults.command_prefix.is_empty());
+        assert_eq!(defaults.fs_root, None);
+    }
+
+    #[test]
+    fn test_parse_remote() {
+        let parsed = CliArgs::parse(&args(&["tank", "--remote", "admin@nas"])).unwrap();
+        assert_eq!(parsed.remote.as_deref(), Some("admin@nas"));
+        assert_eq!(CliArgs::parse(&[]).unwrap().remote, None);
+        assert!(CliArgs::parse(&args(&["--remote", "-oProxyCommand=x"])).is_err());
+        assert!(CliArgs::parse(&args(&["--remote="])).is_err());
+    }
+
+    #[test]
+    fn test_parse_theme() {
+        assert_eq!(CliArgs::parse(&[]).unwrap().theme, Theme::default());
+        assert_eq!(
+            CliArgs::parse(&args(&["--theme", "plain"])).unwrap().theme,
+            Theme::builtin("plain").unwrap()
+        );
+        assert!(CliArgs::parse(&args(&["--theme", "neon"])).is_err());
+        assert_eq!(
+            CliArgs::parse(&args(&["--theme-file", "theme.toml"]))
+                .unwrap()
+                .theme_file,
+            Some(PathBuf::from("theme.toml"))
+        );
+    }
+
+    #[test]
+    fn test_parse_adaptive() {
+        assert!(!CliArgs::parse(&[]).unwrap().adaptive);
+        assert!(CliArgs::parse(&args(&["--adaptive"])).unwrap().adaptive);
+    }
+
+    #[test]
+    fn test_parse_dataset() {
+        assert_eq!(CliArgs::parse(&[]).unwrap().dataset, None);
+        assert_eq!(
+            CliArgs::parse(&args(&["--dataset", "data/home"]))
+                .unwrap()
+                .dataset,
+            Some("data/home".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_report() {
+        assert!(!CliArgs::parse(&[]).unwrap().report);
+        assert!(CliArgs::parse(&args(&["--report"])).unwrap().report);
+    }
+
+    #[test]
+    fn test_parse_quiet() {
+        assert!(!CliArgs::parse(&[]).unwrap().quiet);
+        assert!(CliArgs::parse(&args(&["--quiet"])).unwrap().quiet);
+    }
+
+    #[test]
+    fn test_parse_overview() {
+        assert!(!CliArgs::parse(&[]).unwrap().overview);
+        assert!(CliArgs::parse(&args(&["--overview"])).unwrap().overview);
+    }
+
+    #[test]
+    fn test_parse_follow_new_pool() {
+        assert!(!CliArgs::parse(&[]).unwrap().follow_new_pool);
+        assert!(
+            CliArgs::parse(&args(&["--follow-new-pool"]))
+                .unwrap()
+                .follow_new_pool
+        );
+    }
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!(CliArgs::parse(&[]).unwrap().units, Units::Short);
+        let parsed = CliArgs::parse(&args(&["--units", "binary"])).unwrap();
+        assert_eq!(parsed.units, Units::Binary);
+        let parsed = CliArgs::parse(&args(&["--units=si"])).unwrap();
+        assert_eq!(parsed.units, Units::Decimal);
+        // The default can be named too, e.g. to override a config file
+        let parsed = CliArgs::parse(&args(&["--units", "short"])).unwrap();
+        assert_eq!(parsed.units, Units::Short);
+        assert!(CliArgs::parse(&args(&["--units", "metric"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_raw_numbers() {
+        assert!(!CliArgs::parse(&[]).unwrap().raw_numbers);
+        assert!(
+            CliArgs::parse(&args(&["--raw-numbers"]))
+                .unwrap()
+                .raw_numbers
+        );
+    }
+
+    #[test]
+    fn test_parse_arcstat_reads() {
+        assert_eq!(
+            CliArgs::parse(&[]).unwrap().arcstat_reads,
+            ArcstatReads::Cumulative
+        );
+        let parsed = CliArgs::parse(&args(&["--arcstat-reads", "rate"])).unwrap();
+        assert_eq!(parsed.arcstat_reads, ArcstatReads::PerSecond);
+        assert!(CliArgs::parse(&args(&["--arcstat-reads", "instant"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_rate_mode() {
+        assert!(!CliArgs::parse(&[]).unwrap().since_boot_rates);
+        let parse = |flags: &[&str]| CliArgs::parse(&args(flags)).unwrap().since_boot_rates;
+        assert!(parse(&["--since-boot-rates"]));
+        // The last one given wins, e.g. to override an alias
+        assert!(!parse(&["--since-boot-rates", "--instantaneous-rates"]));
+        assert!(parse(&["--instantaneous-rates", "--since-boot-rates"]));
+    }
+
+    #[test]
+    fn test_parse_min_change() {
+        assert_eq!(CliArgs::parse(&[]).unwrap().min_change, None);
+        let parsed = CliArgs::parse(&args(&["--min-change", "2.5"])).unwrap();
+        assert_eq!(parsed.min_change, Some(2.5));
+        assert!(CliArgs::parse(&args(&["--min-change", "-1"])).is_err());
+        assert!(CliArgs::parse(&args(&["--min-change", "NaN"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_command_timeout() {
+        assert_eq!(
+            CliArgs::parse(&[]).unwrap().command_timeout,
+            Duration::from_secs(5)
+        );
+        let parsed = CliArgs::parse(&args(&["--command-timeout", "30"])).unwrap();
+        assert_eq!(parsed.command_timeout, Duration::from_secs(30));
+        assert!(CliArgs::parse(&args(&["--command-timeout", "0"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_bits() {
+        assert!(!CliArgs::parse(&[]).unwrap().bits);
+        assert!(CliArgs::parse(&args(&["--bits"])).unwrap().bits);
+    }
+
+    #[test]
+    fn test_parse_replay() {
+        assert_eq!(CliArgs::parse(&[]).unwrap().replay, None);
+        assert_eq!(
+            CliArgs::parse(&args(&["--replay", "capture.jsonl"]))
+                .unwrap()
+                .replay,
+            Some(PathBuf::from("capture.jsonl"))
+        );
+        assert!(CliArgs::parse(&args(&["--replay"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_replay_speed() {
+        assert_eq!(CliArgs::parse(&[]).unwrap().replay_speed, None);
+        let parse = |speed| CliArgs::parse(&args(&["--replay-speed", speed]));
+        assert_eq!(parse("2.0").unwrap().replay_speed, Some(2.0));
+        assert_eq!(parse("0").unwrap().replay_speed, Some(0.0));
+        assert!(parse("-1").is_err());
+        assert!(parse("inf").is_err());
+        assert!(parse("fast").is_err());
+    }
+
+    #[test]
+    fn test_parse_count() {
+        assert_eq!(CliArgs::parse(&[]).unwrap().count, 0);
+        assert_eq!(CliArgs::parse(&args(&["--count", "5"])).unwrap().count, 5);
+        assert!(CliArgs::parse(&args(&["--count=-1"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(CliArgs::parse(&args(&["--thresholds"])).is_err());
+        assert!(CliArgs::parse(&args(&["--thresholds", "1,2"])).is_err());
+        assert!(CliArgs::parse(&args(&["--bogus"])).is_err());
+    }
+}
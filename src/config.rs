@@ -0,0 +1,203 @@
+//! Optional config file, `~/.config/zpool-rw-meter/config.toml` by default
+//!
+//! Precedence, highest first: command line flags, config file, built-in defaults.
+//! Only flat `key = value` TOML is understood, which is all the settings need.
+
+use crate::cli::{CliArgs, OutputFormat};
+use crate::display::{ColorMode, Units};
+use crate::zfs::CacheThresholds;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Environment variable overriding the config file location
+pub const CONFIG_PATH_ENV: &str = "ZPOOL_RW_METER_CONFIG";
+
+/// Settings read from the config file; unset keys keep the built-in defaults
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub pool: Option<String>,
+    pub interval: Option<u32>,
+    pub format: Option<OutputFormat>,
+    pub thresholds: Option<CacheThresholds>,
+    pub color: Option<ColorMode>,
+    pub units: Option<Units>,
+}
+
+impl Config {
+    /// Config file location: `$ZPOOL_RW_METER_CONFIG`, else under
+    /// `$XDG_CONFIG_HOME` or `$HOME/.config`
+    pub fn default_path() -> Option<PathBuf> {
+        if let Ok(path) = env::var(CONFIG_PATH_ENV) {
+            return Some(PathBuf::from(path));
+        }
+
+        let config_dir = env::var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|_| env::var("HOME").map(|home| Path::new(&home).join(".config")))
+            .ok()?;
+        Some(config_dir.join("zpool-rw-meter").join("config.toml"))
+    }
+
+    /// Load the config file, treating a missing file as "no config"
+    pub async fn load(path: &Path) -> Result<Option<Self>, String> {
+        match tokio::fs::read_to_string(path).await {
+            Ok(content) => content
+                .parse()
+                .map(Some)
+                .map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    /// Use file values in place of the defaults in `args`
+    pub fn apply(&self, mut args: CliArgs) -> CliArgs {
+        if let Some(pool) = &self.pool {
+            args.pool = Some(pool.clone());
+        }
+        if let Some(interval) = self.interval {
+            args.interval = interval;
+        }
+        if let Some(format) = self.format {
+            args.format = format;
+        }
+        if let Some(thresholds) = self.thresholds {
+            args.thresholds = thresholds;
+        }
+        if let Some(color) = self.color {
+            args.color = color;
+        }
+        if let Some(units) = self.units {
+            args.units = units;
+        }
+        args
+    }
+}
+
+impl FromStr for Config {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = Config::default();
+
+        for (index, line) in s.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let err = |msg: String| format!("line {}: {}", index + 1, msg);
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| err(format!("expected key = value, got '{}'", line)))?;
+            let value = value.trim();
+
+            match key.trim() {
+                "pool" => config.pool = Some(parse_string(value).map_err(err)?),
+                "interval" => {
+                    config.interval = Some(
+                        value
+                            .parse()
+                            .map_err(|_| err(format!("Invalid interval '{}'", value)))?,
+                    )
+                }
+                "format" => config.format = Some(parse_string(value).map_err(err)?.parse()?),
+                "thresholds" => {
+                    // Accept both "90,75,60" and [90, 75, 60]
+                    let list = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                        Some(items) => items.to_string(),
+                        None => parse_string(value).map_err(err)?,
+                    };
+                    config.thresholds = Some(list.parse().map_err(err)?);
+                }
+                "color" => config.color = Some(parse_string(value).map_err(err)?.parse()?),
+                "units" => config.units = Some(parse_string(value).map_err(err)?.parse()?),
+                other => return Err(err(format!("Unknown key '{}'", other))),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Drop a trailing `# comment`, ignoring `#` inside quoted strings
+pub(crate) fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+pub(crate) fn parse_string(value: &str) -> Result<String, String> {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .map(str::to_string)
+        .ok_or_else(|| format!("Expected a quoted string, got '{}'", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+# zpool-rw-meter settings
+pool = "tank"
+interval = 5          # seconds
+format = "text"
+thresholds = [90, 75, 60]
+color = "never"
+units = "decimal"
+"#;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config: Config = SAMPLE.parse().unwrap();
+
+        assert_eq!(config.pool.as_deref(), Some("tank"));
+        assert_eq!(config.interval, Some(5));
+        assert_eq!(config.format, Some(OutputFormat::Text));
+        assert_eq!(config.thresholds.unwrap().excellent, 90.0);
+        assert_eq!(config.color, Some(ColorMode::Never));
+        assert_eq!(config.units, Some(Units::Decimal));
+
+        let config: Config = "thresholds = \"95,80,50\"".parse().unwrap();
+        assert_eq!(config.thresholds.unwrap().good, 80.0);
+    }
+
+    #[test]
+    fn test_cli_overrides_config() {
+        let config: Config = SAMPLE.parse().unwrap();
+        let base = config.apply(CliArgs::default());
+
+        let merged = CliArgs::parse_with_defaults(&args(&["--interval", "10"]), base).unwrap();
+
+        assert_eq!(merged.interval, 10);
+        assert_eq!(merged.pool.as_deref(), Some("tank"));
+        assert_eq!(merged.color, ColorMode::Never);
+    }
+
+    #[test]
+    fn test_parse_config_errors() {
+        assert!("bogus = 1".parse::<Config>().is_err());
+        assert!("interval = fast".parse::<Config>().is_err());
+        assert!("pool = tank".parse::<Config>().is_err());
+        assert!("no equals sign".parse::<Config>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_missing_file() {
+        let config = Config::load(Path::new("/nonexistent/config.toml")).await;
+        assert_eq!(config, Ok(None));
+    }
+}
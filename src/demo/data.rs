@@ -1,6 +1,5 @@
-
-
-
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Demo zpool status output
 #[allow(dead_code)]
@@ -25,4 +24,280 @@ config:
 errors: No known data errors
 "#;
 
+/// Seed used for the demo dashboard so runs are reproducible
+pub const DEMO_SEED: u64 = 0x5EED_2FC5;
+
+/// Generates plausibly varying demo data around the base fixtures.
+/// Cumulative counters advance with the elapsed time at jittered nominal rates,
+/// so the `RateCalculator` yields realistic non-zero rates.
+#[derive(Debug)]
+pub struct DemoDataGenerator {
+    rng_state: u64,
+    elapsed: Duration,
+    arc_size: u64,
+    arc_hits: f64,
+    arc_misses: f64,
+    arc_read_ops: f64,
+    l2_hits: f64,
+    l2_misses: f64,
+    l2_read_bytes: f64,
+    l2_write_bytes: f64,
+    slog_write_ops: f64,
+    slog_write_bw: f64,
+    slog_write_ops_rate: f64,
+    slog_write_bw_rate: f64,
+}
+
+impl DemoDataGenerator {
+    const ARC_SIZE: u64 = 49_720_066_048;
+    const ARC_MAX: u64 = 49_910_562_816;
+    const L2_SIZE: u64 = 594_542_387_200;
+    /// Seconds the pool was imported before the demo started, as `zpool iostat`
+    /// averages over the time since import
+    const IMPORTED_FOR: f64 = 86_400.0;
+    const SLOG_WRITE_OPS: f64 = 156.0;
+    const SLOG_WRITE_BW: f64 = 12_582_912.0;
+
+    pub fn new(seed: u64) -> Self {
+        Self {
+            // xorshift state must never be zero
+            rng_state: seed.max(1),
+            elapsed: Duration::ZERO,
+            arc_size: Self::ARC_SIZE,
+            arc_hits: 970_000.0,
+            arc_misses: 30_000.0,
+            arc_read_ops: 1_000_000.0,
+            l2_hits: 655_000.0,
+            l2_misses: 237_000.0,
+            l2_read_bytes: 245_760_000.0,
+            l2_write_bytes: 10_000_000.0,
+            // 23 ops/s and 12 MiB/s on average since import
+            slog_write_ops: 23.0 * Self::IMPORTED_FOR,
+            slog_write_bw: Self::SLOG_WRITE_BW * Self::IMPORTED_FOR,
+            slog_write_ops_rate: Self::SLOG_WRITE_OPS,
+            slog_write_bw_rate: Self::SLOG_WRITE_BW,
+        }
+    }
+
+    /// Next pseudo-random number in [0, 1) (xorshift64*)
+    fn next_f64(&mut self) -> f64 {
+        self.rng_state ^= self.rng_state >> 12;
+        self.rng_state ^= self.rng_state << 25;
+        self.rng_state ^= self.rng_state >> 27;
+        let value = self.rng_state.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        (value >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Random factor in [1 - amplitude, 1 + amplitude)
+    fn jitter(&mut self, amplitude: f64) -> f64 {
+        1.0 + (self.next_f64() * 2.0 - 1.0) * amplitude
+    }
+
+    /// Advance all counters to the given time since the demo started
+    fn advance(&mut self, elapsed: Duration) {
+        let dt = elapsed.saturating_sub(self.elapsed).as_secs_f64();
+        if dt <= 0.0 {
+            return;
+        }
+        self.elapsed = elapsed;
+
+        let arc_accesses = 5_000.0 * dt * self.jitter(0.3);
+        let arc_hit_ratio = (0.97 * self.jitter(0.03)).min(1.0);
+        self.arc_hits += arc_accesses * arc_hit_ratio;
+        self.arc_misses += arc_accesses * (1.0 - arc_hit_ratio);
+        self.arc_read_ops += 1_247.0 * dt * self.jitter(0.3);
+        self.arc_size = ((Self::ARC_SIZE as f64 * self.jitter(0.003)) as u64).min(Self::ARC_MAX);
+
+        let l2_accesses = 900.0 * dt * self.jitter(0.3);
+        let l2_hit_ratio = 0.734 * self.jitter(0.1);
+        self.l2_hits += l2_accesses * l2_hit_ratio;
+        self.l2_misses += l2_accesses * (1.0 - l2_hit_ratio);
+        self.l2_read_bytes += 240_000_000.0 * dt * self.jitter(0.4);
+        self.l2_write_bytes += 8_000_000.0 * dt * self.jitter(0.5);
+
+        self.slog_write_ops_rate = Self::SLOG_WRITE_OPS * self.jitter(0.4);
+        self.slog_write_bw_rate = Self::SLOG_WRITE_BW * self.jitter(0.4);
+        self.slog_write_ops += self.slog_write_ops_rate * dt;
+        self.slog_write_bw += self.slog_write_bw_rate * dt;
+    }
+
+    /// Render `/proc/spl/kstat/zfs/arcstats` content as of `elapsed`
+    pub fn arcstats(&mut self, elapsed: Duration) -> String {
+        self.advance(elapsed);
+
+        // Split hits by access type with fixed shares, keeping their sum exact
+        let hits = self.arc_hits as u64;
+        let demand_data_hits = (hits as f64 * 0.76) as u64;
+        let demand_metadata_hits = (hits as f64 * 0.22) as u64;
+        let prefetch_data_hits = (hits as f64 * 0.015) as u64;
+        let prefetch_metadata_hits =
+            hits - demand_data_hits - demand_metadata_hits - prefetch_data_hits;
+
+        let mut content = String::new();
+        for line in include_str!("arcstats.txt").lines() {
+            let value = match line.split_whitespace().next() {
+                Some("hits") => Some(self.arc_hits as u64),
+                Some("demand_data_hits") => Some(demand_data_hits),
+                Some("demand_metadata_hits") => Some(demand_metadata_hits),
+                Some("prefetch_data_hits") => Some(prefetch_data_hits),
+                Some("prefetch_metadata_hits") => Some(prefetch_metadata_hits),
+                Some("misses") => Some(self.arc_misses as u64),
+                Some("read_ops") => Some(self.arc_read_ops as u64),
+                Some("size") => Some(self.arc_size),
+                Some("l2_hits") => Some(self.l2_hits as u64),
+                Some("l2_misses") => Some(self.l2_misses as u64),
+                Some("l2_read_bytes") => Some(self.l2_read_bytes as u64),
+                Some("l2_write_bytes") => Some(self.l2_write_bytes as u64),
+                Some("l2_size") => Some(Self::L2_SIZE + self.l2_write_bytes as u64),
+                _ => None,
+            };
+
+            match (value, line.rfind(char::is_whitespace)) {
+                (Some(value), Some(split)) => {
+                    content.push_str(&format!("{}{}", &line[..=split], value))
+                }
+                _ => content.push_str(line),
+            }
+            content.push('\n');
+        }
+        content
+    }
+
+    /// Render `zpool iostat -v -H -p` output as of `elapsed`: the averages since
+    /// import, followed by a report of the current rates when `sampled` (with an
+    /// interval and count of 2)
+    pub fn zpool_iostat_parseable(&mut self, elapsed: Duration, sampled: bool) -> String {
+        self.advance(elapsed);
+
+        let imported_for = Self::IMPORTED_FOR + self.elapsed.as_secs_f64();
+        let mut output = Self::iostat_report(
+            (self.slog_write_ops / imported_for) as u64,
+            (self.slog_write_bw / imported_for) as u64,
+        );
+        if sampled {
+            output.push_str(&Self::iostat_report(
+                self.slog_write_ops_rate as u64,
+                self.slog_write_bw_rate as u64,
+            ));
+        }
+        output
+    }
+
+    /// One `zpool iostat -v -H -p` report with the given SLOG write rates
+    fn iostat_report(write_ops: u64, write_bw: u64) -> String {
+        format!(
+            "data\t3793315160064\t2803752878080\t47\t{write_ops}\t245366784\t{write_bw}\n\
+             logs\t-\t-\t-\t-\t-\t-\n\
+             mirror-1\t0\t2001111162880\t0\t{write_ops}\t0\t{write_bw}\n"
+        )
+    }
+}
+
+/// Demo data generator shared between the demo command executor and filesystem reader
+#[derive(Debug, Clone)]
+pub struct SharedDemoData {
+    generator: Arc<Mutex<DemoDataGenerator>>,
+    started: Instant,
+}
+
+impl SharedDemoData {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            generator: Arc::new(Mutex::new(DemoDataGenerator::new(seed))),
+            started: Instant::now(),
+        }
+    }
+
+    fn with_generator<T>(&self, f: impl FnOnce(&mut DemoDataGenerator, Duration) -> T) -> T {
+        // A poisoned lock only means another reader panicked mid-update; the data is still usable
+        let mut generator = self.generator.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut generator, self.started.elapsed())
+    }
+
+    pub fn arcstats(&self) -> String {
+        self.with_generator(|generator, elapsed| generator.arcstats(elapsed))
+    }
+
+    pub fn zpool_iostat_parseable(&self, sampled: bool) -> String {
+        self.with_generator(|generator, elapsed| generator.zpool_iostat_parseable(elapsed, sampled))
+    }
+
+    /// /proc/uptime of a machine booted a day before the demo started
+    pub fn uptime(&self) -> String {
+        let uptime = 86_400.0 + self.started.elapsed().as_secs_f64();
+        format!("{:.2} {:.2}\n", uptime, uptime * 14.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kstat_value(content: &str, key: &str) -> u64 {
+        content
+            .lines()
+            .find(|line| line.split_whitespace().next() == Some(key))
+            .and_then(|line| line.split_whitespace().nth(2))
+            .and_then(|value| value.parse().ok())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_generator_is_reproducible() {
+        let mut first = DemoDataGenerator::new(DEMO_SEED);
+        let mut second = DemoDataGenerator::new(DEMO_SEED);
+
+        for secs in [0, 2, 4] {
+            let elapsed = Duration::from_secs(secs);
+            assert_eq!(first.arcstats(elapsed), second.arcstats(elapsed));
+        }
+    }
+
+    #[test]
+    fn test_generator_values_vary() {
+        let mut generator = DemoDataGenerator::new(DEMO_SEED);
+        let t0 = generator.arcstats(Duration::ZERO);
+        let t1 = generator.arcstats(Duration::from_secs(2));
+        let t2 = generator.arcstats(Duration::from_secs(4));
+
+        // Cumulative counters advance at roughly their nominal rate
+        let read_ops_delta = kstat_value(&t1, "read_ops") - kstat_value(&t0, "read_ops");
+        assert!((1_700..=3_300).contains(&read_ops_delta));
+        assert!(kstat_value(&t2, "l2_read_bytes") > kstat_value(&t1, "l2_read_bytes"));
+        assert!(kstat_value(&t2, "hits") > kstat_value(&t1, "hits"));
+
+        // Sizes jitter around the base but stay within the ARC maximum
+        assert_ne!(kstat_value(&t1, "size"), kstat_value(&t2, "size"));
+        assert!(kstat_value(&t1, "size") <= kstat_value(&t1, "c_max"));
+    }
+
+    #[test]
+    fn test_generator_same_instant_is_stable() {
+        let mut generator = DemoDataGenerator::new(DEMO_SEED);
+        let first = generator.arcstats(Duration::from_secs(1));
+        let second = generator.arcstats(Duration::from_secs(1));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_generator_iostat_sample() {
+        let mut generator = DemoDataGenerator::new(DEMO_SEED);
+        let write_ops = |output: &str| -> Vec<u64> {
+            output
+                .lines()
+                .filter(|l| l.starts_with("mirror-1"))
+                .map(|row| row.split('\t').nth(4).unwrap().parse().unwrap())
+                .collect()
+        };
 
+        // Averages since import only, then followed by the current rates
+        assert_eq!(
+            write_ops(&generator.zpool_iostat_parseable(Duration::ZERO, false)),
+            vec![23]
+        );
+        let sampled = write_ops(&generator.zpool_iostat_parseable(Duration::from_secs(10), true));
+        assert_eq!(sampled.len(), 2);
+        assert_eq!(sampled[0], 23);
+        assert!((93..=219).contains(&sampled[1]));
+    }
+}
@@ -3,3 +3,4 @@
 pub mod data;
 
 // Re-export demo data
+pub use data::{DEMO_SEED, SharedDemoData};
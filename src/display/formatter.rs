@@ -1,34 +1,168 @@
-/// Human-readable byte formatting (B/K/M/G/T/P)
-pub fn format_bytes(bytes: u64) -> String {
-    const UNITS: &[&str] = &["B", "K", "M", "G", "T", "P"];
-    let mut size = bytes as f64;
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How byte sizes are scaled and labeled
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Units {
+    /// 1024-based with short labels (K/M/G), as `zpool` prints them
+    #[default]
+    Short,
+    /// 1024-based with IEC labels (KiB/MiB/GiB)
+    Binary,
+    /// 1000-based with SI labels (kB/MB/GB)
+    Decimal,
+}
+
+impl Units {
+    fn base(self) -> f64 {
+        match self {
+            Units::Short | Units::Binary => 1024.0,
+            Units::Decimal => 1000.0,
+        }
+    }
+
+    fn labels(self) -> &'static [&'static str] {
+        match self {
+            Units::Short => &["B", "K", "M", "G", "T", "P", "E"],
+            Units::Binary => &["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"],
+            Units::Decimal => &["B", "kB", "MB", "GB", "TB", "PB", "EB"],
+        }
+    }
+
+    fn bit_labels(self) -> &'static [&'static str] {
+        match self {
+            Units::Short => &["bps", "Kbps", "Mbps", "Gbps", "Tbps", "Pbps", "Ebps"],
+            Units::Binary => &["bps", "Kibps", "Mibps", "Gibps", "Tibps", "Pibps", "Eibps"],
+            Units::Decimal => &["bps", "kbps", "Mbps", "Gbps", "Tbps", "Pbps", "Ebps"],
+        }
+    }
+}
+
+impl FromStr for Units {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "short" => Ok(Units::Short),
+            "binary" => Ok(Units::Binary),
+            // SI prefixes are the decimal ones
+            "decimal" | "si" => Ok(Units::Decimal),
+            _ => Err(format!(
+                "Invalid units '{}': expected short, binary, decimal or si",
+                s
+            )),
+        }
+    }
+}
+
+/// How the dashboard renders sizes, rates and operation counts
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NumberFormat {
+    pub units: Units,
+    /// Plain integers for operation counts (`--raw-numbers`)
+    pub raw: bool,
+    /// Bandwidth in bits rather than bytes per second (`--bits`)
+    pub bits: bool,
+}
+
+// The `*_into` variants append to a caller's buffer, so a display path that
+// reuses one doesn't allocate a String per figure; the others delegate to them.
+// Writing into a String can't fail, hence the ignored `fmt::Result`s.
+
+/// Human-readable byte formatting (e.g. "1.5K", "1.5KiB" or "1.5kB")
+pub fn format_bytes(bytes: u64, units: Units) -> String {
+    let mut out = String::new();
+    format_bytes_into(&mut out, bytes, units);
+    out
+}
+
+/// Like [`format_bytes`], appended to `out`
+pub fn format_bytes_into(out: &mut String, bytes: u64, units: Units) {
+    format_scaled_into(out, bytes as f64, units, units.labels());
+}
+
+/// Scale `value` by the units' base and append it with the matching label
+fn format_scaled_into(out: &mut String, value: f64, units: Units, labels: &[&str]) {
+    let mut size = value;
     let mut unit_index = 0;
 
-    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
-        size /= 1024.0;
+    while size >= units.base() && unit_index < labels.len() - 1 {
+        size /= units.base();
         unit_index += 1;
     }
 
-    if unit_index == 0 {
-        format!("{} {}", bytes, UNITS[unit_index])
-    } else {
-        format!("{:.1}{}", size, UNITS[unit_index])
+    // {:.1} would round e.g. 1023.95K up to "1024.0K", which belongs to the next unit
+    if unit_index > 0
+        && (size * 10.0).round() / 10.0 >= units.base()
+        && unit_index < labels.len() - 1
+    {
+        size /= units.base();
+        unit_index += 1;
     }
+
+    let _ = if unit_index == 0 {
+        write!(out, "{} {}", value as u64, labels[unit_index])
+    } else {
+        write!(out, "{:.1}{}", size, labels[unit_index])
+    };
 }
 
 /// Format bytes with both current and total (e.g., "46.3G/46.5G")
-pub fn format_bytes_ratio(current: u64, total: u64) -> String {
-    format!("{}/{}", format_bytes(current), format_bytes(total))
+pub fn format_bytes_ratio(current: u64, total: u64, units: Units) -> String {
+    let mut out = String::new();
+    format_bytes_into(&mut out, current, units);
+    out.push('/');
+    format_bytes_into(&mut out, total, units);
+    out
 }
 
-/// Format rate (bytes per second)
-pub fn format_rate(bytes_per_second: u64) -> String {
-    format!("{}/s", format_bytes(bytes_per_second))
+/// Format rate (bytes per second), or in bits per second when `bits` is set
+/// (e.g. "1.0M/s" or "8.0Mbps")
+pub fn format_rate(bytes_per_second: u64, units: Units, bits: bool) -> String {
+    let mut out = String::new();
+    format_rate_into(&mut out, bytes_per_second, units, bits);
+    out
 }
 
-/// Format operations per second
-pub fn format_ops_per_second(ops: u64) -> String {
-    format!("{}/s", ops)
+/// Like [`format_rate`], appended to `out`
+pub fn format_rate_into(out: &mut String, bytes_per_second: u64, units: Units, bits: bool) {
+    if bits {
+        format_scaled_into(
+            out,
+            bytes_per_second as f64 * 8.0,
+            units,
+            units.bit_labels(),
+        );
+    } else {
+        format_bytes_into(out, bytes_per_second, units);
+        out.push_str("/s");
+    }
+}
+
+/// Format operations per second, with K/M/G (base 1000) suffixes from 1000 up
+/// unless `raw` asks for the plain integer (e.g. "1.45M/s" or "1452318/s")
+pub fn format_ops_per_second(ops: u64, raw: bool) -> String {
+    let mut out = String::new();
+    format_ops_per_second_into(&mut out, ops, raw);
+    out
+}
+
+/// Like [`format_ops_per_second`], appended to `out`
+pub fn format_ops_per_second_into(out: &mut String, ops: u64, raw: bool) {
+    const SUFFIXES: &[&str] = &["K", "M", "G"];
+    if raw || ops < 1000 {
+        let _ = write!(out, "{}/s", ops);
+        return;
+    }
+
+    let mut value = ops as f64 / 1000.0;
+    let mut suffix_index = 0;
+    while value >= 1000.0 && suffix_index < SUFFIXES.len() - 1 {
+        value /= 1000.0;
+        suffix_index += 1;
+    }
+    let _ = write!(out, "{:.2}{}/s", value, SUFFIXES[suffix_index]);
 }
 
 /// Format latency in milliseconds
@@ -36,40 +170,158 @@ pub fn format_latency_ms(latency: f64) -> String {
     format!("{:.1}ms", latency)
 }
 
+/// Format a duration compactly in its two largest units (e.g., "45s", "1m30s",
+/// "2h15m", "3d4h"), with "<1s" for anything shorter than a second but zero
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0 if !duration.is_zero() => "<1s".to_string(),
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m{}s", secs / 60, secs % 60),
+        3600..86400 => format!("{}h{}m", secs / 3600, secs % 3600 / 60),
+        _ => format!("{}d{}h", secs / 86400, secs % 86400 / 3600),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_format_bytes() {
-        assert_eq!(format_bytes(0), "0 B");
-        assert_eq!(format_bytes(512), "512 B");
-        assert_eq!(format_bytes(1024), "1.0K");
-        assert_eq!(format_bytes(1536), "1.5K");
-        assert_eq!(format_bytes(1024 * 1024), "1.0M");
-        assert_eq!(format_bytes(1024 * 1024 * 1024), "1.0G");
-        assert_eq!(format_bytes(1024 * 1024 * 1024 * 1024), "1.0T");
+        let units = Units::default();
+        assert_eq!(format_bytes(0, units), "0 B");
+        assert_eq!(format_bytes(512, units), "512 B");
+        assert_eq!(format_bytes(1024, units), "1.0K");
+        assert_eq!(format_bytes(1536, units), "1.5K");
+        assert_eq!(format_bytes(1024 * 1024, units), "1.0M");
+        assert_eq!(format_bytes(1024 * 1024 * 1024, units), "1.0G");
+        assert_eq!(format_bytes(1024 * 1024 * 1024 * 1024, units), "1.0T");
+        assert_eq!(format_bytes(3 << 49, units), "1.5P");
+        assert_eq!(format_bytes(1 << 60, units), "1.0E");
+        assert_eq!(format_bytes(u64::MAX, Units::Binary), "16.0EiB");
+    }
+
+    #[test]
+    fn test_format_bytes_just_under_unit_boundary() {
+        let units = Units::default();
+        assert_eq!(format_bytes(1023, units), "1023 B");
+        assert_eq!(format_bytes(1_048_575, units), "1.0M");
+        assert_eq!(format_bytes(1_073_741_823, units), "1.0G");
+        assert_eq!(format_bytes(1_048_575, Units::Binary), "1.0MiB");
+        assert_eq!(format_bytes(999_999, Units::Decimal), "1.0MB");
+        assert_eq!(format_bytes_ratio(1_048_575, 1_048_576, units), "1.0M/1.0M");
+    }
+
+    #[test]
+    fn test_format_bytes_binary() {
+        assert_eq!(format_bytes(512, Units::Binary), "512 B");
+        assert_eq!(format_bytes(1536, Units::Binary), "1.5KiB");
+        assert_eq!(format_bytes(1024 * 1024, Units::Binary), "1.0MiB");
+        assert_eq!(format_bytes(49_910_562_816, Units::Binary), "46.5GiB");
+    }
+
+    #[test]
+    fn test_format_bytes_decimal() {
+        assert_eq!(format_bytes(999, Units::Decimal), "999 B");
+        assert_eq!(format_bytes(1000, Units::Decimal), "1.0kB");
+        assert_eq!(format_bytes(1024, Units::Decimal), "1.0kB");
+        assert_eq!(format_bytes(1_500_000, Units::Decimal), "1.5MB");
+        assert_eq!(format_bytes(49_910_562_816, Units::Decimal), "49.9GB");
+    }
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!("binary".parse::<Units>().unwrap(), Units::Binary);
+        assert_eq!("decimal".parse::<Units>().unwrap(), Units::Decimal);
+        assert_eq!("si".parse::<Units>().unwrap(), Units::Decimal);
+        assert!("iec".parse::<Units>().is_err());
     }
 
     #[test]
     fn test_format_bytes_ratio() {
-        assert_eq!(format_bytes_ratio(1024, 2048), "1.0K/2.0K");
+        let units = Units::default();
+        assert_eq!(format_bytes_ratio(1024, 2048, units), "1.0K/2.0K");
         assert_eq!(
-            format_bytes_ratio(46_301_224_960, 49_910_562_816),
+            format_bytes_ratio(46_301_224_960, 49_910_562_816, units),
             "43.1G/46.5G"
         );
     }
 
     #[test]
     fn test_format_rate() {
-        assert_eq!(format_rate(1024), "1.0K/s");
-        assert_eq!(format_rate(1024 * 1024), "1.0M/s");
+        assert_eq!(format_rate(1024, Units::default(), false), "1.0K/s");
+        assert_eq!(format_rate(1024 * 1024, Units::default(), false), "1.0M/s");
+        assert_eq!(format_rate(1024 * 1024, Units::Decimal, false), "1.0MB/s");
+    }
+
+    #[test]
+    fn test_format_rate_bits() {
+        assert_eq!(format_rate(1024 * 1024, Units::default(), true), "8.0Mbps");
+        assert_eq!(format_rate(1024 * 1024, Units::Binary, true), "8.0Mibps");
+        assert_eq!(format_rate(1_000_000, Units::Decimal, true), "8.0Mbps");
+        assert_eq!(format_rate(100, Units::default(), true), "800 bps");
     }
 
     #[test]
     fn test_format_ops_per_second() {
-        assert_eq!(format_ops_per_second(1000), "1000/s");
-        assert_eq!(format_ops_per_second(50), "50/s");
+        assert_eq!(format_ops_per_second(50, false), "50/s");
+        assert_eq!(format_ops_per_second(999, false), "999/s");
+        assert_eq!(format_ops_per_second(1000, false), "1.00K/s");
+        assert_eq!(format_ops_per_second(23_450, false), "23.45K/s");
+        assert_eq!(format_ops_per_second(1_452_318, false), "1.45M/s");
+        assert_eq!(format_ops_per_second(7_200_000_000, false), "7.20G/s");
+        assert_eq!(
+            format_ops_per_second(7_200_000_000_000, false),
+            "7200.00G/s"
+        );
+    }
+
+    #[test]
+    fn test_format_ops_per_second_raw() {
+        assert_eq!(format_ops_per_second(1000, true), "1000/s");
+        assert_eq!(format_ops_per_second(1_452_318, true), "1452318/s");
+        assert_eq!(format_ops_per_second(50, true), "50/s");
+    }
+
+    #[test]
+    fn test_in_place_variants_match() {
+        let mut values = vec![0, 1, 999, 1000, 1023, 1024, 1536, 1_048_575, 1_073_741_823];
+        values.extend((0..64).map(|shift| 3u64 << shift >> 1));
+        values.push(u64::MAX);
+
+        // One buffer for every call, with something already in it to append to
+        let mut out = String::with_capacity(64);
+        let mut check = |write: &dyn Fn(&mut String), expected: String| {
+            out.clear();
+            out.push_str("> ");
+            write(&mut out);
+            assert_eq!(out, format!("> {}", expected));
+        };
+        for _ in 0..100 {
+            for &value in &values {
+                for units in [Units::Short, Units::Binary, Units::Decimal] {
+                    check(
+                        &|out| format_bytes_into(out, value, units),
+                        format_bytes(value, units),
+                    );
+                    for bits in [false, true] {
+                        check(
+                            &|out| format_rate_into(out, value, units, bits),
+                            format_rate(value, units, bits),
+                        );
+                    }
+                }
+                for raw in [false, true] {
+                    check(
+                        &|out| format_ops_per_second_into(out, value, raw),
+                        format_ops_per_second(value, raw),
+                    );
+                }
+            }
+        }
+        // Every figure fit the buffer it was given, without growing it
+        assert_eq!(out.capacity(), 64);
     }
 
     #[test]
@@ -77,4 +329,26 @@ mod tests {
         assert_eq!(format_latency_ms(2.1), "2.1ms");
         assert_eq!(format_latency_ms(0.5), "0.5ms");
     }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::ZERO), "0s");
+        assert_eq!(format_duration(Duration::from_millis(500)), "<1s");
+        assert_eq!(format_duration(Duration::from_secs(2)), "2s");
+        assert_eq!(format_duration(Duration::from_secs(59)), "59s");
+        assert_eq!(format_duration(Duration::from_secs(60)), "1m0s");
+        assert_eq!(format_duration(Duration::from_secs(90)), "1m30s");
+        assert_eq!(format_duration(Duration::from_secs(3599)), "59m59s");
+        assert_eq!(format_duration(Duration::from_secs(3600)), "1h0m");
+        assert_eq!(format_duration(Duration::from_secs(3700)), "1h1m");
+        assert_eq!(format_duration(Duration::from_secs(7500)), "2h5m");
+        assert_eq!(format_duration(Duration::from_secs(86399)), "23h59m");
+        assert_eq!(format_duration(Duration::from_secs(86400)), "1d0h");
+        assert_eq!(format_duration(Duration::from_secs(300_000)), "3d11h");
+        // Years of uptime still fit the two-unit form
+        assert_eq!(
+            format_duration(Duration::from_secs(u64::MAX)),
+            "213503982334601d7h"
+        );
+    }
 }
@@ -0,0 +1,1047 @@
+use crate::zfs::error::ZfsError;
+use crate::zfs::{
+    ArcEviction, ArcHitBreakdown, ArcMetadata, ArcStats, CacheStatus, CollectedAt, L2ArcStats,
+    LatencyPercentiles, PoolIoStats, SlogStats, Trend,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Deserialize;
+use serde::de::IgnoredAny;
+use std::fmt;
+use std::time::Duration;
+
+/// Version of the JSON output envelope.
+/// Bump whenever an existing field is removed, renamed or changes meaning.
+pub const SCHEMA_VERSION: u64 = 2;
+
+/// Minimal JSON value used for machine-readable output
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    UInt(u64),
+    Float(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Build an object from key/value pairs, preserving their order
+    pub fn object<const N: usize>(fields: [(&str, JsonValue); N]) -> Self {
+        JsonValue::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    /// Look up a field of an object by key
+    #[cfg(test)]
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for JsonValue {
+    fn from(value: &str) -> Self {
+        JsonValue::String(value.to_string())
+    }
+}
+
+impl<T: Into<JsonValue>> From<Option<T>> for JsonValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(JsonValue::Null, Into::into)
+    }
+}
+
+fn write_json_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl fmt::Display for JsonValue {
+    /// Serialize as compact JSON
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Bool(b) => write!(f, "{}", b),
+            JsonValue::UInt(n) => write!(f, "{}", n),
+            // JSON has no representation for NaN/Infinity
+            JsonValue::Float(n) if !n.is_finite() => write!(f, "null"),
+            JsonValue::Float(n) => write!(f, "{}", n),
+            JsonValue::String(s) => write_json_string(f, s),
+            JsonValue::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            JsonValue::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_json_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl From<&ArcStats> for JsonValue {
+    fn from(arc: &ArcStats) -> Self {
+        JsonValue::object([
+            ("hit_rate", JsonValue::Float(arc.hit_rate)),
+            ("size", JsonValue::UInt(arc.size)),
+            ("target", JsonValue::UInt(arc.target)),
+            ("target_trend", arc.target_trend.map(JsonValue::from).into()),
+            ("max", JsonValue::UInt(arc.max)),
+            ("read_ops", JsonValue::UInt(arc.read_ops)),
+            ("hits_total", JsonValue::UInt(arc.hits_total)),
+            ("misses_total", JsonValue::UInt(arc.misses_total)),
+            ("hit_breakdown", arc.hit_breakdown.as_ref().into()),
+            ("eviction", arc.eviction.as_ref().into()),
+            ("metadata", arc.metadata.as_ref().into()),
+            ("no_grow", arc.no_grow.map(JsonValue::Bool).into()),
+            ("prunes", arc.prunes.map(JsonValue::UInt).into()),
+            ("memory_total", arc.memory_total.map(JsonValue::UInt).into()),
+        ])
+    }
+}
+
+impl From<&ArcEviction> for JsonValue {
+    fn from(eviction: &ArcEviction) -> Self {
+        JsonValue::object([
+            ("evict_skip", JsonValue::UInt(eviction.evict_skip)),
+            (
+                "evict_not_enough",
+                JsonValue::UInt(eviction.evict_not_enough),
+            ),
+            ("mutex_miss", JsonValue::UInt(eviction.mutex_miss)),
+            // Derived, for consumers that don't want to repeat the cutoffs
+            ("pressure", eviction.pressure().to_string().as_str().into()),
+        ])
+    }
+}
+
+impl From<&ArcMetadata> for JsonValue {
+    fn from(metadata: &ArcMetadata) -> Self {
+        JsonValue::object([
+            ("size", JsonValue::UInt(metadata.size)),
+            ("limit", metadata.limit.map(JsonValue::UInt).into()),
+        ])
+    }
+}
+
+impl From<&ArcHitBreakdown> for JsonValue {
+    fn from(breakdown: &ArcHitBreakdown) -> Self {
+        JsonValue::object([
+            ("demand_data", JsonValue::UInt(breakdown.demand_data)),
+            (
+                "demand_metadata",
+                JsonValue::UInt(breakdown.demand_metadata),
+            ),
+            ("prefetch", JsonValue::UInt(breakdown.prefetch)),
+        ])
+    }
+}
+
+impl From<&L2ArcStats> for JsonValue {
+    fn from(l2arc: &L2ArcStats) -> Self {
+        JsonValue::object([
+            ("hit_rate", JsonValue::Float(l2arc.hit_rate)),
+            ("size", JsonValue::UInt(l2arc.size)),
+            ("read_bytes", JsonValue::UInt(l2arc.read_bytes)),
+            ("read_bytes_peak", JsonValue::UInt(l2arc.read_bytes_peak)),
+            ("write_bytes", JsonValue::UInt(l2arc.write_bytes)),
+            ("total_ops", JsonValue::UInt(l2arc.total_ops)),
+            ("read_bytes_total", JsonValue::UInt(l2arc.read_bytes_total)),
+            (
+                "write_bytes_total",
+                JsonValue::UInt(l2arc.write_bytes_total),
+            ),
+            ("cksum_bad", JsonValue::UInt(l2arc.cksum_bad)),
+            ("io_errors", JsonValue::UInt(l2arc.io_errors)),
+            ("hits_total", JsonValue::UInt(l2arc.hits_total)),
+            ("misses_total", JsonValue::UInt(l2arc.misses_total)),
+            ("warming", JsonValue::Bool(l2arc.warming)),
+        ])
+    }
+}
+
+impl From<&SlogStats> for JsonValue {
+    fn from(slog: &SlogStats) -> Self {
+        JsonValue::object([
+            ("device", slog.device.as_str().into()),
+            ("display_name", slog.display_name.as_str().into()),
+            ("write_ops", JsonValue::UInt(slog.write_ops)),
+            ("write_bw", JsonValue::UInt(slog.write_bw)),
+            ("write_bw_peak", JsonValue::UInt(slog.write_bw_peak)),
+            ("write_ops_avg", JsonValue::UInt(slog.write_ops_avg)),
+            ("write_bw_avg", JsonValue::UInt(slog.write_bw_avg)),
+            ("utilization", JsonValue::Float(slog.utilization)),
+            ("latency", JsonValue::Float(slog.latency)),
+            (
+                "latency_percentiles",
+                slog.latency_percentiles.as_ref().into(),
+            ),
+            (
+                "cache_age_seconds",
+                slog.cache_age
+                    .map(|age| JsonValue::Float(age.as_secs_f64()))
+                    .into(),
+            ),
+            ("fault", slog.fault.as_deref().into()),
+        ])
+    }
+}
+
+impl From<&LatencyPercentiles> for JsonValue {
+    fn from(percentiles: &LatencyPercentiles) -> Self {
+        JsonValue::object([
+            ("p50", JsonValue::Float(percentiles.p50)),
+            ("p95", JsonValue::Float(percentiles.p95)),
+            ("p99", JsonValue::Float(percentiles.p99)),
+        ])
+    }
+}
+
+impl From<&ZfsError> for JsonValue {
+    fn from(error: &ZfsError) -> Self {
+        let mut fields = vec![("kind".to_string(), error.kind().into())];
+        let mut field = |key: &str, value: JsonValue| fields.push((key.to_string(), value));
+        match error {
+            ZfsError::CommandError {
+                command,
+                args,
+                source,
+            } => {
+                field("command", command.as_str().into());
+                field(
+                    "args",
+                    JsonValue::Array(args.iter().map(|arg| arg.as_str().into()).collect()),
+                );
+                field("source", JsonValue::String(source.to_string()));
+            }
+            ZfsError::TimeoutError {
+                command,
+                args,
+                timeout,
+            } => {
+                field("command", command.as_str().into());
+                field(
+                    "args",
+                    JsonValue::Array(args.iter().map(|arg| arg.as_str().into()).collect()),
+                );
+                field("timeout_secs", JsonValue::Float(timeout.as_secs_f64()));
+            }
+            ZfsError::FilesystemError {
+                path,
+                operation,
+                source,
+            } => {
+                field("path", path.as_str().into());
+                field("operation", operation.as_str().into());
+                field("source", JsonValue::String(source.to_string()));
+            }
+            ZfsError::ParseError {
+                data_source,
+                reason,
+            } => {
+                field("data_source", data_source.as_str().into());
+                field("reason", reason.as_str().into());
+            }
+            ZfsError::InvalidFormat {
+                expected,
+                received,
+                context,
+            } => {
+                field("expected", expected.as_str().into());
+                field("received", received.as_str().into());
+                field("context", context.as_str().into());
+            }
+            ZfsError::SubsystemUnavailable { subsystem, reason } => {
+                field("subsystem", subsystem.as_str().into());
+                field("reason", reason.as_str().into());
+            }
+        }
+        // The same text the dashboard shows
+        field("message", JsonValue::String(error.to_string()));
+        JsonValue::Object(fields)
+    }
+}
+
+impl From<&PoolIoStats> for JsonValue {
+    fn from(pool_io: &PoolIoStats) -> Self {
+        JsonValue::object([
+            ("pool", pool_io.pool.as_str().into()),
+            ("read_ops", JsonValue::UInt(pool_io.read_ops)),
+            ("write_ops", JsonValue::UInt(pool_io.write_ops)),
+            ("read_bw", JsonValue::UInt(pool_io.read_bw)),
+            ("write_bw", JsonValue::UInt(pool_io.write_bw)),
+            ("alloc", JsonValue::UInt(pool_io.alloc)),
+            ("free", JsonValue::UInt(pool_io.free)),
+        ])
+    }
+}
+
+/// Wrap one refresh worth of metrics in the versioned output envelope
+pub fn metrics_envelope(
+    pool: &str,
+    timestamp: DateTime<Utc>,
+    arc: &ArcStats,
+    l2arc: Option<&L2ArcStats>,
+    slog: Option<&SlogStats>,
+    pool_io: Option<&PoolIoStats>,
+) -> JsonValue {
+    JsonValue::object([
+        ("schema_version", JsonValue::UInt(SCHEMA_VERSION)),
+        (
+            "timestamp",
+            JsonValue::String(timestamp.to_rfc3339_opts(SecondsFormat::Secs, true)),
+        ),
+        ("pool", pool.into()),
+        (
+            "metrics",
+            JsonValue::object([
+                ("arc", arc.into()),
+                ("l2arc", l2arc.into()),
+                ("slog", slog.into()),
+                ("pool_io", pool_io.into()),
+            ]),
+        ),
+    ])
+}
+
+/// Envelope reporting a failed collection in place of `metrics`, so the stream
+/// stays one JSON document per refresh
+pub fn error_envelope(pool: &str, timestamp: DateTime<Utc>, error: &ZfsError) -> JsonValue {
+    JsonValue::object([
+        ("schema_version", JsonValue::UInt(SCHEMA_VERSION)),
+        (
+            "timestamp",
+            JsonValue::String(timestamp.to_rfc3339_opts(SecondsFormat::Secs, true)),
+        ),
+        ("pool", pool.into()),
+        ("error", error.into()),
+    ])
+}
+
+/// Two envelopes taken `interval` apart plus the per-second rates between them
+pub fn batch_envelope(
+    pool: &str,
+    interval: Duration,
+    samples: Vec<JsonValue>,
+    rates: Vec<(String, f64)>,
+) -> JsonValue {
+    JsonValue::object([
+        ("schema_version", JsonValue::UInt(SCHEMA_VERSION)),
+        ("pool", pool.into()),
+        ("interval_seconds", JsonValue::Float(interval.as_secs_f64())),
+        ("samples", JsonValue::Array(samples)),
+        (
+            "rates",
+            JsonValue::Object(
+                rates
+                    .into_iter()
+                    .map(|(key, rate)| (key, JsonValue::Float(rate)))
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+/// Unit and meaning of one field of the `metrics` object
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricMetadata {
+    /// Dotted path below `metrics`, e.g. "arc.hit_rate"
+    pub name: &'static str,
+    pub unit: &'static str,
+    pub description: &'static str,
+}
+
+impl From<&MetricMetadata> for JsonValue {
+    fn from(metric: &MetricMetadata) -> Self {
+        JsonValue::object([
+            ("name", metric.name.into()),
+            ("unit", metric.unit.into()),
+            ("description", metric.description.into()),
+        ])
+    }
+}
+
+const fn metric(
+    name: &'static str,
+    unit: &'static str,
+    description: &'static str,
+) -> MetricMetadata {
+    MetricMetadata {
+        name,
+        unit,
+        description,
+    }
+}
+
+/// Every field the `metrics` object can hold, in output order. Rates are per
+/// second over the last refresh, totals are cumulative since boot.
+const METRICS: &[MetricMetadata] = &[
+    metric("arc.hit_rate", "percent", "ARC hit rate"),
+    metric("arc.size", "bytes", "Current ARC size"),
+    metric("arc.target", "bytes", "ARC target size (c)"),
+    metric(
+        "arc.target_trend",
+        "rising|falling|steady",
+        "Where the ARC target went since the previous sample",
+    ),
+    metric("arc.max", "bytes", "Hard ARC ceiling (c_max)"),
+    metric("arc.read_ops", "ops/s", "ARC read operations"),
+    metric("arc.hits_total", "count", "ARC hits"),
+    metric("arc.misses_total", "count", "ARC misses"),
+    metric(
+        "arc.hit_breakdown.demand_data",
+        "count",
+        "ARC hits on demand data",
+    ),
+    metric(
+        "arc.hit_breakdown.demand_metadata",
+        "count",
+        "ARC hits on demand metadata",
+    ),
+    metric(
+        "arc.hit_breakdown.prefetch",
+        "count",
+        "ARC hits on prefetched data and metadata",
+    ),
+    metric(
+        "arc.eviction.evict_skip",
+        "1/s",
+        "Buffers skipped by eviction because they were in use",
+    ),
+    metric(
+        "arc.eviction.evict_not_enough",
+        "1/s",
+        "Evictions that could not free as much as asked",
+    ),
+    metric(
+        "arc.eviction.mutex_miss",
+        "1/s",
+        "Buffers skipped by eviction because their hash lock was held",
+    ),
+    metric(
+        "arc.eviction.pressure",
+        "none|low|medium|high",
+        "Eviction pressure judged by the skip rate",
+    ),
+    metric("arc.metadata.size", "bytes", "Metadata held in the ARC"),
+    metric(
+        "arc.metadata.limit",
+        "bytes",
+        "ARC metadata limit (arc_meta_limit)",
+    ),
+    metric(
+        "arc.no_grow",
+        "bool",
+        "ARC held back from growing by memory pressure",
+    ),
+    metric("arc.prunes", "1/s", "arc_prune calls"),
+    metric("arc.memory_total", "bytes", "Physical RAM"),
+    metric("l2arc.hit_rate", "percent", "L2ARC hit rate"),
+    metric("l2arc.size", "bytes", "L2ARC size"),
+    metric(
+        "l2arc.read_bytes",
+        "bytes/s",
+        "Bytes read from the cache device",
+    ),
+    metric(
+        "l2arc.read_bytes_peak",
+        "bytes/s",
+        "Highest L2ARC read rate this session",
+    ),
+    metric(
+        "l2arc.write_bytes",
+        "bytes/s",
+        "Bytes written (filled) to the cache device",
+    ),
+    metric("l2arc.total_ops", "ops/s", "L2ARC operations"),
+    metric(
+        "l2arc.read_bytes_total",
+        "bytes",
+        "Bytes read from the cache device",
+    ),
+    metric(
+        "l2arc.write_bytes_total",
+        "bytes",
+        "Bytes written (filled) to the cache device",
+    ),
+    metric(
+        "l2arc.cksum_bad",
+        "count",
+        "Checksum failures reading from the cache device",
+    ),
+    metric("l2arc.io_errors", "count", "I/O errors on the cache device"),
+    metric("l2arc.hits_total", "count", "L2ARC hits"),
+    metric("l2arc.misses_total", "count", "L2ARC misses"),
+    metric("l2arc.warming", "bool", "L2ARC still filling up"),
+    metric("slog.device", "name", "SLOG device identifier"),
+    metric("slog.display_name", "name", "SLOG device name as displayed"),
+    metric("slog.write_ops", "ops/s", "SLOG write operations"),
+    metric("slog.write_bw", "bytes/s", "SLOG write bandwidth"),
+    metric(
+        "slog.write_bw_peak",
+        "bytes/s",
+        "Highest SLOG write bandwidth this session",
+    ),
+    metric(
+        "slog.write_ops_avg",
+        "ops/s",
+        "SLOG write operations averaged since pool import",
+    ),
+    metric(
+        "slog.write_bw_avg",
+        "bytes/s",
+        "SLOG write bandwidth averaged since pool import",
+    ),
+    metric("slog.utilization", "percent", "SLOG device utilization"),
+    metric("slog.latency", "ms", "Average SLOG latency"),
+    metric(
+        "slog.latency_percentiles.p50",
+        "ms",
+        "Median SLOG write latency",
+    ),
+    metric(
+        "slog.latency_percentiles.p95",
+        "ms",
+        "95th percentile SLOG write latency",
+    ),
+    metric(
+        "slog.latency_percentiles.p99",
+        "ms",
+        "99th percentile SLOG write latency",
+    ),
+    metric(
+        "slog.cache_age_seconds",
+        "seconds",
+        "Age of the zpool output the SLOG stats come from, when cached",
+    ),
+    metric(
+        "slog.fault",
+        "state",
+        "State of a SLOG device that can't take writes, e.g. FAULTED",
+    ),
+    metric("pool_io.pool", "name", "Monitored pool"),
+    metric("pool_io.read_ops", "ops/s", "Pool read operations"),
+    metric("pool_io.write_ops", "ops/s", "Pool write operations"),
+    metric("pool_io.read_bw", "bytes/s", "Pool read bandwidth"),
+    metric("pool_io.write_bw", "bytes/s", "Pool write bandwidth"),
+    metric("pool_io.alloc", "bytes", "Allocated pool space"),
+    metric("pool_io.free", "bytes", "Free pool space"),
+];
+
+/// Unit and meaning of every field of the `metrics` object, for consumers of
+/// `--format json` that don't want to hard-code them
+pub fn metrics_metadata() -> &'static [MetricMetadata] {
+    METRICS
+}
+
+impl From<CacheStatus> for JsonValue {
+    fn from(status: CacheStatus) -> Self {
+        JsonValue::String(status.to_string())
+    }
+}
+
+impl From<Trend> for JsonValue {
+    fn from(trend: Trend) -> Self {
+        JsonValue::String(trend.to_string())
+    }
+}
+
+/// One refresh read back from a metrics envelope
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub pool: String,
+    pub timestamp: DateTime<Utc>,
+    pub arc: ArcStats,
+    pub l2arc: Option<L2ArcStats>,
+    pub slog: Option<SlogStats>,
+    pub pool_io: Option<PoolIoStats>,
+}
+
+/// Fields every envelope starts with, checked before reading the rest
+#[derive(Deserialize)]
+struct Header {
+    schema_version: u64,
+}
+
+/// A metrics or error envelope; only the fields a replay needs
+#[derive(Deserialize)]
+struct Envelope {
+    timestamp: DateTime<Utc>,
+    pool: String,
+    metrics: Option<Metrics>,
+    error: Option<IgnoredAny>,
+}
+
+#[derive(Deserialize)]
+struct Metrics {
+    arc: ArcStats,
+    l2arc: Option<L2ArcStats>,
+    slog: Option<SlogStats>,
+    pool_io: Option<PoolIoStats>,
+}
+
+impl Snapshot {
+    /// Read one line of `--format json` output back; None for an error envelope,
+    /// written while ZFS was gone, as there are no metrics to show
+    pub fn from_json(line: &str) -> Result<Option<Self>, String> {
+        let header: Header = serde_json::from_str(line).map_err(|e| e.to_string())?;
+        // Older envelopes only lack fields that are optional here
+        if header.schema_version == 0 || header.schema_version > SCHEMA_VERSION {
+            return Err(format!(
+                "Unsupported schema_version {} (expected at most {})",
+                header.schema_version, SCHEMA_VERSION
+            ));
+        }
+
+        let envelope: Envelope = serde_json::from_str(line).map_err(|e| e.to_string())?;
+        let metrics = match (envelope.metrics, envelope.error) {
+            (Some(metrics), _) => metrics,
+            (None, Some(_)) => return Ok(None),
+            (None, None) => return Err("missing field `metrics`".to_string()),
+        };
+        let mut snapshot = Snapshot {
+            pool: envelope.pool,
+            timestamp: envelope.timestamp,
+            arc: metrics.arc,
+            l2arc: metrics.l2arc,
+            slog: metrics.slog,
+            pool_io: metrics.pool_io,
+        };
+
+        // Sections aren't timestamped on their own; they were collected with the envelope
+        let collected_at = CollectedAt(envelope.timestamp.into());
+        snapshot.arc.collected_at = collected_at;
+        if let Some(l2arc) = &mut snapshot.l2arc {
+            l2arc.collected_at = collected_at;
+        }
+        if let Some(slog) = &mut snapshot.slog {
+            slog.collected_at = collected_at;
+        }
+        Ok(Some(snapshot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_arc() -> ArcStats {
+        ArcStats {
+            hit_rate: 95.5,
+            size: 49_720_066_048,
+            target: 49_720_066_048,
+            target_trend: None,
+            max: 49_910_562_816,
+            read_ops: 1247,
+            hits_total: 1_000_000,
+            misses_total: 2_500,
+            hit_breakdown: None,
+            eviction: None,
+            metadata: None,
+            no_grow: None,
+            prunes: None,
+            memory_total: None,
+            collected_at: CollectedAt::default(),
+        }
+    }
+
+    #[test]
+    fn test_json_serialization() {
+        let value = JsonValue::object([
+            ("null", JsonValue::Null),
+            ("uint", JsonValue::UInt(42)),
+            ("float", JsonValue::Float(1.5)),
+            ("nan", JsonValue::Float(f64::NAN)),
+            ("string", "a\"b\\c\n".into()),
+            (
+                "array",
+                JsonValue::Array(vec![JsonValue::UInt(1), JsonValue::Null]),
+            ),
+        ]);
+
+        assert_eq!(
+            value.to_string(),
+            r#"{"null":null,"uint":42,"float":1.5,"nan":null,"string":"a\"b\\c\n","array":[1,null]}"#
+        );
+    }
+
+    #[test]
+    fn test_metrics_envelope() {
+        let timestamp = DateTime::parse_from_rfc3339("2025-09-14T17:10:08Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let envelope = metrics_envelope("data", timestamp, &sample_arc(), None, None, None);
+
+        assert_eq!(
+            envelope.get("schema_version"),
+            Some(&JsonValue::UInt(SCHEMA_VERSION))
+        );
+        assert_eq!(envelope.get("pool"), Some(&"data".into()));
+
+        let Some(JsonValue::String(ts)) = envelope.get("timestamp") else {
+            panic!("Expected timestamp string");
+        };
+        assert!(DateTime::parse_from_rfc3339(ts).is_ok());
+        assert_eq!(ts, "2025-09-14T17:10:08Z");
+
+        let metrics = envelope.get("metrics").unwrap();
+        assert_eq!(
+            metrics.get("arc").and_then(|arc| arc.get("read_ops")),
+            Some(&JsonValue::UInt(1247))
+        );
+        assert_eq!(
+            metrics.get("arc").and_then(|arc| arc.get("hit_breakdown")),
+            Some(&JsonValue::Null)
+        );
+        assert_eq!(metrics.get("l2arc"), Some(&JsonValue::Null));
+        assert_eq!(metrics.get("slog"), Some(&JsonValue::Null));
+        assert_eq!(metrics.get("pool_io"), Some(&JsonValue::Null));
+
+        let serialized = envelope.to_string();
+        assert!(
+            serialized.starts_with(r#"{"schema_version":2,"timestamp":"2025-09-14T17:10:08Z""#)
+        );
+    }
+
+    /// Serialize to text and parse it back, as a capture and `--replay` would
+    fn round_trip<T>(value: &T) -> T
+    where
+        for<'a> &'a T: Into<JsonValue>,
+        T: for<'de> Deserialize<'de>,
+    {
+        let text = value.into().to_string();
+        serde_json::from_str(&text).unwrap()
+    }
+
+    fn sample_l2arc() -> L2ArcStats {
+        L2ArcStats {
+            hit_rate: 42.5,
+            size: 553_700_000_000,
+            read_bytes: 1_048_576,
+            write_bytes: 524_288,
+            read_bytes_peak: 4_194_304,
+            total_ops: 310,
+            read_bytes_total: 9_000_000_000,
+            write_bytes_total: 12_000_000_000,
+            cksum_bad: 2,
+            io_errors: 0,
+            hits_total: 2_000,
+            misses_total: 500,
+            warming: true,
+            collected_at: CollectedAt::default(),
+        }
+    }
+
+    fn sample_slog() -> SlogStats {
+        SlogStats {
+            device: "mirror-1".to_string(),
+            display_name: "sdc".to_string(),
+            write_ops: 23,
+            write_bw: 12_582_912,
+            write_bw_peak: 25_165_824,
+            write_ops_avg: 120_000,
+            write_bw_avg: 64_000_000_000,
+            utilization: 12.5,
+            latency: 2.1,
+            cache_age: Some(Duration::from_secs(12)),
+            collected_at: CollectedAt::default(),
+            latency_percentiles: Some(LatencyPercentiles {
+                p50: 1.0,
+                p95: 2.0,
+                p99: 4.0,
+            }),
+            fault: None,
+        }
+    }
+
+    /// Dotted paths of the scalar fields below `value`
+    fn leaf_paths(prefix: &str, value: &JsonValue, paths: &mut Vec<String>) {
+        match value {
+            JsonValue::Object(fields) => {
+                for (key, value) in fields {
+                    let path = match prefix {
+                        "" => key.clone(),
+                        prefix => format!("{}.{}", prefix, key),
+                    };
+                    leaf_paths(&path, value, paths);
+                }
+            }
+            _ => paths.push(prefix.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_metrics_metadata_covers_output() {
+        let mut arc = sample_arc();
+        arc.target_trend = Some(Trend::Rising);
+        arc.hit_breakdown = Some(ArcHitBreakdown {
+            demand_data: 750_000,
+            demand_metadata: 200_000,
+            prefetch: 50_000,
+        });
+        arc.eviction = Some(ArcEviction {
+            evict_skip: 350,
+            evict_not_enough: 12,
+            mutex_miss: 3,
+        });
+        arc.metadata = Some(ArcMetadata {
+            size: 1_700_000_000,
+            limit: Some(37_432_921_856),
+        });
+        arc.no_grow = Some(false);
+        arc.prunes = Some(0);
+        arc.memory_total = Some(68_719_476_736);
+        let mut slog = sample_slog();
+        slog.fault = Some("FAULTED".to_string());
+        let pool_io = PoolIoStats {
+            pool: "data".to_string(),
+            read_ops: 150,
+            write_ops: 23,
+            read_bw: 8_388_608,
+            write_bw: 12_582_912,
+            alloc: 3_793_315_160_064,
+            free: 2_803_752_878_080,
+        };
+        let envelope = metrics_envelope(
+            "data",
+            Utc::now(),
+            &arc,
+            Some(&sample_l2arc()),
+            Some(&slog),
+            Some(&pool_io),
+        );
+
+        let mut emitted = Vec::new();
+        leaf_paths("", envelope.get("metrics").unwrap(), &mut emitted);
+        let described: Vec<String> = metrics_metadata()
+            .iter()
+            .map(|metric| metric.name.to_string())
+            .collect();
+        // No orphan metrics, and no metadata for fields that are gone
+        assert_eq!(emitted, described);
+        assert!(
+            metrics_metadata()
+                .iter()
+                .all(|metric| !metric.unit.is_empty() && !metric.description.is_empty())
+        );
+    }
+
+    #[test]
+    fn test_zfs_error_json() {
+        let errors = [
+            (
+                ZfsError::command_error("zpool", &["status"], "exit 1"),
+                "CommandError",
+            ),
+            (
+                ZfsError::filesystem_error("/proc/meminfo", "read", "denied"),
+                "FilesystemError",
+            ),
+            (
+                ZfsError::parse_error("arcstat", "Invalid hit rate"),
+                "ParseError",
+            ),
+            (
+                ZfsError::invalid_format("4 columns", "2 columns", "arcstat output"),
+                "InvalidFormat",
+            ),
+            (
+                ZfsError::subsystem_unavailable("SLOG", "needs root"),
+                "SubsystemUnavailable",
+            ),
+        ];
+        for (error, kind) in &errors {
+            let json = JsonValue::from(error);
+            assert_eq!(json.get("kind"), Some(&JsonValue::from(*kind)));
+            assert_eq!(
+                json.get("message"),
+                Some(&JsonValue::String(error.to_string()))
+            );
+        }
+
+        let unavailable = JsonValue::from(&errors[4].0).to_string();
+        assert_eq!(
+            unavailable,
+            r#"{"kind":"SubsystemUnavailable","subsystem":"SLOG","reason":"needs root","message":"SLOG subsystem unavailable: needs root"}"#
+        );
+        let command = JsonValue::from(&errors[0].0);
+        assert_eq!(
+            command.get("args"),
+            Some(&JsonValue::Array(vec!["status".into()]))
+        );
+        assert_eq!(command.get("source"), Some(&"exit 1".into()));
+    }
+
+    #[test]
+    fn test_error_envelope() {
+        let timestamp = DateTime::parse_from_rfc3339("2025-09-14T17:10:08Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let error = ZfsError::parse_error("arcstat", "Invalid hit rate");
+        let envelope = error_envelope("data", timestamp, &error);
+
+        assert_eq!(envelope.get("pool"), Some(&"data".into()));
+        assert_eq!(envelope.get("metrics"), None);
+        assert_eq!(
+            envelope.get("error").and_then(|error| error.get("kind")),
+            Some(&"ParseError".into())
+        );
+    }
+
+    #[test]
+    fn test_read_older_and_non_finite_fields() {
+        // Schema version 1 key names, and a latency that wasn't finite
+        let text = JsonValue::from(&sample_slog())
+            .to_string()
+            .replace("write_ops_avg", "write_ops_total")
+            .replace("write_bw_avg", "write_bw_total")
+            .replace(r#""latency":2.1"#, r#""latency":null"#);
+        let slog: SlogStats = serde_json::from_str(&text).unwrap();
+        assert_eq!(slog.write_ops_avg, sample_slog().write_ops_avg);
+        assert!(slog.latency.is_nan());
+
+        let negative_age = text.replace(r#""cache_age_seconds":12"#, r#""cache_age_seconds":-1"#);
+        assert!(serde_json::from_str::<SlogStats>(&negative_age).is_err());
+    }
+
+    #[test]
+    fn test_stats_round_trip() {
+        let mut arc = sample_arc();
+        assert_eq!(round_trip(&arc), arc);
+        arc.hit_breakdown = Some(ArcHitBreakdown {
+            demand_data: 750_000,
+            demand_metadata: 200_000,
+            prefetch: 50_000,
+        });
+        arc.memory_total = Some(68_719_476_736);
+        arc.no_grow = Some(true);
+        arc.prunes = Some(12);
+        arc.eviction = Some(ArcEviction {
+            evict_skip: 350,
+            evict_not_enough: 12,
+            mutex_miss: 3,
+        });
+        arc.metadata = Some(ArcMetadata {
+            size: 1_700_000_000,
+            limit: Some(37_432_921_856),
+        });
+        for trend in [Trend::Rising, Trend::Falling, Trend::Steady] {
+            arc.target_trend = Some(trend);
+            assert_eq!(round_trip(&arc), arc);
+        }
+        assert_eq!(
+            JsonValue::from(&arc)
+                .get("eviction")
+                .and_then(|eviction| eviction.get("pressure")),
+            Some(&"medium".into())
+        );
+
+        let l2arc = sample_l2arc();
+        assert_eq!(round_trip(&l2arc), l2arc);
+
+        let mut slog = sample_slog();
+        assert_eq!(round_trip(&slog), slog);
+        slog.cache_age = None;
+        slog.latency_percentiles = None;
+        assert_eq!(round_trip(&slog), slog);
+
+        let pool_io = PoolIoStats {
+            pool: "data".to_string(),
+            read_ops: 150,
+            write_ops: 23,
+            read_bw: 8_388_608,
+            write_bw: 12_582_912,
+            alloc: 3_793_315_160_064,
+            free: 2_803_752_878_080,
+        };
+        assert_eq!(round_trip(&pool_io), pool_io);
+
+        for status in [
+            CacheStatus::Excellent,
+            CacheStatus::Good,
+            CacheStatus::Fair,
+            CacheStatus::Poor,
+        ] {
+            let text = JsonValue::from(status).to_string();
+            let parsed: CacheStatus = serde_json::from_str(&text).unwrap();
+            assert_eq!(parsed, status);
+        }
+    }
+
+    #[test]
+    fn test_snapshot_from_envelope() {
+        let timestamp = DateTime::parse_from_rfc3339("2025-09-14T17:10:08Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let envelope = metrics_envelope(
+            "data",
+            timestamp,
+            &sample_arc(),
+            Some(&sample_l2arc()),
+            Some(&sample_slog()),
+            None,
+        );
+
+        let snapshot = Snapshot::from_json(&envelope.to_string()).unwrap().unwrap();
+        assert_eq!(
+            snapshot,
+            Snapshot {
+                pool: "data".to_string(),
+                timestamp,
+                arc: sample_arc(),
+                l2arc: Some(sample_l2arc()),
+                slog: Some(sample_slog()),
+                pool_io: None,
+            }
+        );
+        let collected_at = std::time::SystemTime::from(timestamp);
+        assert_eq!(snapshot.arc.collected_at.0, collected_at);
+        assert_eq!(snapshot.slog.unwrap().collected_at.0, collected_at);
+
+        let future = envelope
+            .to_string()
+            .replace(r#""schema_version":2"#, r#""schema_version":99"#);
+        let result = Snapshot::from_json(&future);
+        assert!(
+            result
+                .unwrap_err()
+                .contains("Unsupported schema_version 99")
+        );
+
+        let older = envelope
+            .to_string()
+            .replace(r#""schema_version":2"#, r#""schema_version":1"#);
+        assert!(Snapshot::from_json(&older).unwrap().is_some());
+    }
+}
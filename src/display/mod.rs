@@ -1,12 +1,19 @@
 //! Display module for terminal output and formatting
 
 pub mod formatter;
+pub mod json;
+pub mod overview;
 pub mod progress;
+pub mod table;
 pub mod terminal;
+pub mod theme;
 
 // Re-export commonly used items
 pub use formatter::{
-    format_bytes, format_bytes_ratio, format_latency_ms, format_ops_per_second, format_rate,
+    NumberFormat, Units, format_bytes, format_bytes_into, format_bytes_ratio, format_duration,
+    format_latency_ms, format_ops_per_second, format_ops_per_second_into, format_rate,
+    format_rate_into,
 };
 pub use progress::ProgressBar;
-pub use terminal::Terminal;
+pub use terminal::{ColorMode, Terminal};
+pub use theme::Theme;
@@ -0,0 +1,186 @@
+//! Compact one-row-per-pool table for `--overview`
+
+use super::table::render_table;
+use super::terminal::Terminal;
+use crate::zfs::{CacheStatus, CacheThresholds};
+use std::io::{self, Write};
+
+/// One pool's figures in the overview table. The ARC is system-wide rather
+/// than per pool, so it is shown once above the table instead of in every row.
+#[derive(Debug, Clone)]
+pub struct OverviewRow {
+    pub pool: String,
+    pub l2arc_hit_rate: Option<f64>,
+    pub slog_write_bw: Option<u64>, // Bytes per second
+    pub slog_latency: Option<f64>,  // Milliseconds
+    pub capacity: Option<f64>,      // Percent of the pool allocated
+}
+
+/// Render the header and one row per pool; missing L2ARC/SLOG/capacity show as `-`
+pub fn render_overview(
+    out: &mut impl Write,
+    terminal: &Terminal,
+    rows: &[OverviewRow],
+    thresholds: &CacheThresholds,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:<16} {:>9} {:>10} {:>9} {:>9}",
+        "POOL", "L2 HIT%", "SLOG MB/s", "LATENCY", "CAPACITY"
+    )?;
+
+    for row in rows {
+        let l2arc = hit_rate_cell(terminal, row.l2arc_hit_rate, thresholds);
+        let slog_bw = row.slog_write_bw.map_or_else(
+            || format!("{:>10}", "-"),
+            |bw| format!("{:>10.1}", bw as f64 / (1024.0 * 1024.0)),
+        );
+        let latency = row.slog_latency.map_or_else(
+            || format!("{:>9}", "-"),
+            |latency| format!("{:>9}", format!("{:.1}ms", latency)),
+        );
+        let capacity = row.capacity.map_or_else(
+            || format!("{:>9}", "-"),
+            |capacity| format!("{:>9}", format!("{:.0}%", capacity)),
+        );
+        writeln!(
+            out,
+            "{:<16} {} {} {} {}",
+            row.pool, l2arc, slog_bw, latency, capacity
+        )?;
+    }
+    Ok(())
+}
+
+/// `--format table`: the system-wide ARC hit rate, then the same columns as an
+/// aligned plain-text table, sized to fit the longest pool name and largest figure
+pub fn overview_table(arc_hit_rate: f64, rows: &[OverviewRow]) -> String {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            vec![
+                row.pool.clone(),
+                row.l2arc_hit_rate
+                    .map_or_else(|| "-".to_string(), |hit_rate| format!("{:.1}", hit_rate)),
+                row.slog_write_bw.map_or_else(
+                    || "-".to_string(),
+                    |bw| format!("{:.1}", bw as f64 / (1024.0 * 1024.0)),
+                ),
+                row.slog_latency
+                    .map_or_else(|| "-".to_string(), |latency| format!("{:.1}ms", latency)),
+                row.capacity
+                    .map_or_else(|| "-".to_string(), |capacity| format!("{:.0}%", capacity)),
+            ]
+        })
+        .collect();
+    format!(
+        "ARC HIT% (system-wide): {:.1}\n{}",
+        arc_hit_rate,
+        render_table(
+            &["POOL", "L2 HIT%", "SLOG MB/s", "LATENCY", "CAPACITY"],
+            &cells,
+        )
+    )
+}
+
+/// Right-aligned hit rate, colored by its `CacheStatus`
+fn hit_rate_cell(
+    terminal: &Terminal,
+    hit_rate: Option<f64>,
+    thresholds: &CacheThresholds,
+) -> String {
+    let Some(hit_rate) = hit_rate else {
+        return format!("{:>9}", "-");
+    };
+
+    // Pad before styling so escape codes don't count towards the width
+    let cell = format!("{:>9.1}", hit_rate);
+    if !terminal.supports_color {
+        return cell;
+    }
+    CacheStatus::from_hit_rate_with_thresholds(hit_rate, thresholds)
+        .color()
+        .force_styling(true)
+        .apply_to(cell)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::ColorMode;
+
+    fn rows() -> Vec<OverviewRow> {
+        vec![
+            OverviewRow {
+                pool: "data".to_string(),
+                l2arc_hit_rate: Some(42.5),
+                slog_write_bw: Some(12 * 1024 * 1024),
+                slog_latency: Some(2.1),
+                capacity: Some(57.5),
+            },
+            OverviewRow {
+                pool: "backup".to_string(),
+                l2arc_hit_rate: None,
+                slog_write_bw: None,
+                slog_latency: None,
+                capacity: None,
+            },
+        ]
+    }
+
+    fn render(terminal: &Terminal) -> String {
+        let mut out = Vec::new();
+        render_overview(&mut out, terminal, &rows(), &CacheThresholds::default()).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_render_overview() {
+        let output = render(&Terminal::with_color_mode(ColorMode::Never));
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "POOL               L2 HIT%  SLOG MB/s   LATENCY  CAPACITY",
+                "data                  42.5       12.0     2.1ms       58%",
+                "backup                   -          -         -         -",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overview_table() {
+        let mut rows = rows();
+        rows[1].pool = "usb-backup-offsite".to_string();
+
+        assert_eq!(
+            overview_table(96.9, &rows),
+            "\
+ARC HIT% (system-wide): 96.9
+POOL                L2 HIT%  SLOG MB/s  LATENCY  CAPACITY
+------------------  -------  ---------  -------  --------
+data                   42.5       12.0    2.1ms       58%
+usb-backup-offsite        -          -        -         -
+"
+        );
+    }
+
+    #[test]
+    fn test_render_overview_colors_by_status() {
+        let output = render(&Terminal::with_color_mode(ColorMode::Always));
+
+        // Poor L2ARC hit rate in red
+        assert!(output.contains("\x1b[31m     42.5\x1b[0m"));
+        // Placeholders stay unstyled
+        assert!(
+            output
+                .lines()
+                .last()
+                .unwrap()
+                .ends_with("         -          -         -         -")
+        );
+        assert!(!output.lines().last().unwrap().contains('\x1b'));
+    }
+}
@@ -1,9 +1,11 @@
 use super::terminal::Terminal;
+use crate::zfs::CacheStatus;
 
 /// ASCII progress bar renderer matching shell script aesthetics
 pub struct ProgressBar {
     width: usize,
     terminal: Terminal,
+    gradient: bool,
 }
 
 impl ProgressBar {
@@ -11,12 +13,45 @@ impl ProgressBar {
         Self {
             width,
             terminal: Terminal::new(),
+            gradient: false,
         }
     }
 
+    /// Override whether the bar is colored
+    pub fn with_color(mut self, supports_color: bool) -> Self {
+        self.terminal.supports_color = supports_color;
+        self
+    }
+
+    /// Color each filled cell by its position along the bar, fading from red
+    /// through yellow to green (256-color palette), instead of in one color
+    pub fn with_gradient(mut self, gradient: bool) -> Self {
+        self.gradient = gradient;
+        self
+    }
+
     /// Render a progress bar with percentage
     /// Returns a string with the progress bar and percentage
     pub fn render(&self, percentage: f64, label: Option<&str>) -> String {
+        self.render_styled(
+            percentage,
+            label,
+            self.terminal.get_performance_style(percentage),
+        )
+    }
+
+    /// Like `render`, for a hit rate already classified as `status`: the bar
+    /// takes the status color rather than judging the percentage again
+    pub fn render_status(
+        &self,
+        percentage: f64,
+        label: Option<&str>,
+        status: CacheStatus,
+    ) -> String {
+        self.render_styled(percentage, label, status.color().force_styling(true))
+    }
+
+    fn render_styled(&self, percentage: f64, label: Option<&str>, style: console::Style) -> String {
         let filled = (percentage / 100.0 * self.width as f64).round() as usize;
         let empty = self.width.saturating_sub(filled);
 
@@ -26,13 +61,12 @@ impl ProgressBar {
         let bar = format!("[{}{}]", filled_chars, empty_chars);
         let percent_text = format!("{:.1}%", percentage);
 
-        let styled_bar = if self.terminal.supports_color {
-            self.terminal
-                .get_performance_style(percentage)
-                .apply_to(&bar)
-                .to_string()
-        } else {
+        let styled_bar = if !self.terminal.supports_color {
             bar
+        } else if self.gradient {
+            self.gradient_bar(filled.min(self.width))
+        } else {
+            style.apply_to(&bar).to_string()
         };
 
         match label {
@@ -41,7 +75,30 @@ impl ProgressBar {
         }
     }
 
+    /// The bar with `filled` cells, each colored by its position along it
+    fn gradient_bar(&self, filled: usize) -> String {
+        let filled_cells: String = (0..filled)
+            .map(|cell| {
+                let position = (cell as f64 + 0.5) / self.width as f64;
+                self.terminal
+                    .get_color_style(gradient_color(position))
+                    .apply_to("#")
+                    .to_string()
+            })
+            .collect();
+        format!("[{}{}]", filled_cells, ".".repeat(self.width - filled))
+    }
+}
 
+/// Red (0.0) through yellow (0.5) to green (1.0) in the 6x6x6 color cube
+fn gradient_color(position: f64) -> console::Color {
+    let position = position.clamp(0.0, 1.0);
+    let (red, green) = if position < 0.5 {
+        (5, (position * 2.0 * 5.0).round() as u8)
+    } else {
+        (((1.0 - position) * 2.0 * 5.0).round() as u8, 5)
+    };
+    console::Color::Color256(16 + 36 * red + 6 * green)
 }
 
 impl Default for ProgressBar {
@@ -78,4 +135,64 @@ mod tests {
         assert!(result.contains("[..........]"));
         assert!(result.contains("0.0%"));
     }
+
+    #[test]
+    fn test_progress_bar_color_override() {
+        let colored = ProgressBar::new(10).with_color(true).render(90.0, None);
+        assert!(colored.contains("\x1b["));
+
+        let plain = ProgressBar::new(10).with_color(false).render(90.0, None);
+        assert_eq!(plain, "[#########.] 90.0%");
+    }
+
+    #[test]
+    fn test_progress_bar_status_color() {
+        // 75% would be yellow by percentage alone; the status decides instead
+        let bar =
+            ProgressBar::new(10)
+                .with_color(true)
+                .render_status(75.0, None, CacheStatus::Excellent);
+        let green = CacheStatus::Excellent
+            .color()
+            .force_styling(true)
+            .apply_to("[########..]")
+            .to_string();
+        assert_eq!(bar, format!("{} 75.0%", green));
+
+        let plain =
+            ProgressBar::new(10)
+                .with_color(false)
+                .render_status(75.0, None, CacheStatus::Poor);
+        assert_eq!(plain, "[########..] 75.0%");
+    }
+
+    #[test]
+    fn test_progress_bar_gradient() {
+        let gradient = ProgressBar::new(10)
+            .with_color(true)
+            .with_gradient(true)
+            .render(100.0, Some("Test"));
+        let colors: std::collections::HashSet<&str> = gradient
+            .split("\x1b[")
+            .skip(1)
+            .map(|code| &code[..code.find('m').unwrap()])
+            .filter(|code| code.starts_with("38;5;"))
+            .collect();
+        assert!(colors.len() > 2, "{:?}", colors);
+        assert!(gradient.ends_with("] 100.0%"));
+
+        // Status colors give way to the gradient too
+        let status = ProgressBar::new(10)
+            .with_color(true)
+            .with_gradient(true)
+            .render_status(100.0, None, CacheStatus::Excellent);
+        assert_eq!(status, gradient.replacen("Test ", "", 1));
+
+        // Without color it is the plain bar
+        let plain = ProgressBar::new(10)
+            .with_color(false)
+            .with_gradient(true)
+            .render(50.0, None);
+        assert_eq!(plain, "[#####.....] 50.0%");
+    }
 }
@@ -0,0 +1,111 @@
+//! Plain-text tables with aligned columns
+
+/// Lay out `rows` under `headers` with every column padded to its widest cell
+/// and a separator row below the header. The first column (names) is
+/// left-aligned, the others (figures) right-aligned; missing cells are blank.
+pub fn render_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers
+        .iter()
+        .map(|header| header.chars().count())
+        .collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    push_row(&mut table, &widths, headers);
+    let separator: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+    push_row(&mut table, &widths, &separator);
+    for row in rows {
+        push_row(&mut table, &widths, row);
+    }
+    table
+}
+
+fn push_row(table: &mut String, widths: &[usize], cells: &[impl AsRef<str>]) {
+    let line: Vec<String> = widths
+        .iter()
+        .enumerate()
+        .map(|(i, &width)| {
+            let cell = cells.get(i).map_or("", AsRef::as_ref);
+            if i == 0 {
+                format!("{:<width$}", cell)
+            } else {
+                format!("{:>width$}", cell)
+            }
+        })
+        .collect();
+    table.push_str(line.join("  ").trim_end());
+    table.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(row: &[&str]) -> Vec<String> {
+        row.iter().map(|cell| cell.to_string()).collect()
+    }
+
+    #[test]
+    fn test_render_table() {
+        let table = render_table(
+            &["POOL", "ARC HIT%", "SLOG MB/s"],
+            &[
+                cells(&["data", "96.9", "12.0"]),
+                cells(&["a-very-long-pool-name", "5.0", "1234567.8"]),
+                cells(&["x", "100.0"]),
+            ],
+        );
+
+        assert_eq!(
+            table,
+            "\
+POOL                   ARC HIT%  SLOG MB/s
+---------------------  --------  ---------
+data                       96.9       12.0
+a-very-long-pool-name       5.0  1234567.8
+x                         100.0
+"
+        );
+    }
+
+    #[test]
+    fn test_render_table_columns_align() {
+        let table = render_table(
+            &["NAME", "N"],
+            &[
+                cells(&["ü", "1"]),
+                cells(&["tank/home/alice", "123456789"]),
+                cells(&["usb", "42"]),
+            ],
+        );
+        let lines: Vec<&str> = table.lines().collect();
+
+        // Every column occupies the same character span on every line: the span
+        // of its dashes in the separator row
+        let separator = lines[1];
+        let mut spans: Vec<(usize, usize)> = Vec::new();
+        for (i, c) in separator.chars().enumerate() {
+            match spans.last_mut() {
+                Some((_, end)) if c == '-' && *end == i => *end = i + 1,
+                _ if c == '-' => spans.push((i, i + 1)),
+                _ => {}
+            }
+        }
+        assert_eq!(spans, vec![(0, 15), (17, 26)]);
+
+        for line in &lines {
+            let chars: Vec<char> = line.chars().collect();
+            for &(start, end) in &spans {
+                let cell: String = chars[start.min(chars.len())..end.min(chars.len())]
+                    .iter()
+                    .collect();
+                assert!(!cell.trim().is_empty(), "empty cell in {:?}", line);
+                assert!(chars.get(end).map_or(true, |c| *c == ' '), "{:?}", line);
+            }
+        }
+    }
+}
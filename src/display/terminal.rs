@@ -1,56 +1,126 @@
 use console;
 use std::io::{self, Write};
+use std::str::FromStr;
+
+/// When to emit ANSI colors
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ColorMode {
+    /// Follow terminal detection
+    #[default]
+    Auto,
+    /// Always color, e.g. for `less -R`
+    Always,
+    /// Never color, e.g. for CI logs
+    Never,
+}
+
+impl FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            _ => Err(format!(
+                "Invalid color mode '{}': expected auto, always or never",
+                s
+            )),
+        }
+    }
+}
 
 /// Terminal control and ANSI color handling
 pub struct Terminal {
     pub supports_color: bool,
+    /// Rows to assume instead of asking the terminal, e.g. in tests
+    pub fixed_height: Option<usize>,
 }
 
 impl Terminal {
     pub fn new() -> Self {
+        Self::with_color_mode(ColorMode::Auto)
+    }
+
+    /// Create a terminal with color detection overridden by `mode`
+    pub fn with_color_mode(mode: ColorMode) -> Self {
+        let supports_color = match mode {
+            ColorMode::Auto => console::colors_enabled(),
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        };
         Self {
-            supports_color: console::colors_enabled(),
+            supports_color,
+            fixed_height: None,
         }
     }
 
+    /// Number of rows on the screen, asked anew on every call so resizes are
+    /// picked up. None when stdout is not a terminal.
+    pub fn height(&self) -> Option<usize> {
+        self.fixed_height.or_else(|| {
+            console::Term::stdout()
+                .size_checked()
+                .map(|(rows, _)| rows as usize)
+        })
+    }
+
     /// Clear the entire screen
-    pub fn clear_screen(&self) -> io::Result<()> {
-        print!("\x1B[2J\x1B[1;1H");
-        io::stdout().flush()
+    pub fn clear_screen(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "\x1B[2J\x1B[1;1H")
     }
 
+    /// Move the cursor to the top-left corner without clearing
+    pub fn move_home(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "\x1B[H")
+    }
 
+    /// Clear from the cursor to the end of the current line
+    pub fn clear_to_eol(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "\x1B[K")
+    }
+
+    /// Move the cursor to the start of the next line, leaving the current one as is
+    pub fn next_line(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "\x1B[E")
+    }
+
+    /// Clear from the cursor to the end of the screen
+    pub fn clear_to_end_of_screen(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "\x1B[J")
+    }
 
     /// Hide cursor during updates to prevent flicker
-    pub fn hide_cursor(&self) -> io::Result<()> {
-        print!("\x1B[?25l");
-        io::stdout().flush()
+    pub fn hide_cursor(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "\x1B[?25l")
     }
 
     /// Show cursor
-    pub fn show_cursor(&self) -> io::Result<()> {
-        print!("\x1B[?25h");
-        io::stdout().flush()
+    pub fn show_cursor(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "\x1B[?25h")
     }
 
     /// Get color style based on performance level
     pub fn get_performance_style(&self, percentage: f64) -> console::Style {
-        let mut style = console::Style::new();
-        if !self.supports_color {
-            return style;
-        }
-
-        if percentage >= 80.0 {
-            style = style.green(); // Excellent
+        let color = if percentage >= 80.0 {
+            console::Color::Green // Excellent
         } else if percentage >= 60.0 {
-            style = style.yellow(); // Good
+            console::Color::Yellow // Good
         } else {
-            style = style.red(); // Poor
-        }
-        style
+            console::Color::Red // Poor
+        };
+        self.get_color_style(color)
     }
 
+    /// Style drawing in `color`, or a plain one without color support
+    pub fn get_color_style(&self, color: console::Color) -> console::Style {
+        if !self.supports_color {
+            return console::Style::new();
+        }
 
+        // Color was already decided here, so bypass console's own tty detection
+        console::Style::new().force_styling(true).fg(color)
+    }
 }
 
 impl Default for Terminal {
@@ -58,3 +128,60 @@ impl Default for Terminal {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clear_sequences() {
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+        let mut out = Vec::new();
+        terminal.clear_to_eol(&mut out).unwrap();
+        terminal.clear_to_end_of_screen(&mut out).unwrap();
+        terminal.next_line(&mut out).unwrap();
+        assert_eq!(out, b"\x1B[K\x1B[J\x1B[E");
+
+        let mut out = Vec::new();
+        terminal.clear_screen(&mut out).unwrap();
+        terminal.move_home(&mut out).unwrap();
+        terminal.hide_cursor(&mut out).unwrap();
+        terminal.show_cursor(&mut out).unwrap();
+        assert_eq!(out, b"\x1B[2J\x1B[1;1H\x1B[H\x1B[?25l\x1B[?25h");
+    }
+
+    #[test]
+    fn test_fixed_height() {
+        let mut terminal = Terminal::with_color_mode(ColorMode::Never);
+        terminal.fixed_height = Some(12);
+        assert_eq!(terminal.height(), Some(12));
+    }
+
+    #[test]
+    fn test_color_mode_from_str() {
+        assert_eq!("auto".parse::<ColorMode>(), Ok(ColorMode::Auto));
+        assert_eq!("always".parse::<ColorMode>(), Ok(ColorMode::Always));
+        assert_eq!("never".parse::<ColorMode>(), Ok(ColorMode::Never));
+        assert!("sometimes".parse::<ColorMode>().is_err());
+    }
+
+    #[test]
+    fn test_performance_style_respects_color_mode() {
+        let colored = Terminal::with_color_mode(ColorMode::Always);
+        assert!(colored.supports_color);
+        let styled = colored
+            .get_performance_style(90.0)
+            .apply_to("90%")
+            .to_string();
+        assert!(styled.contains("\x1b["));
+        assert_ne!(styled, "90%");
+
+        let plain = Terminal::with_color_mode(ColorMode::Never);
+        assert!(!plain.supports_color);
+        let unstyled = plain
+            .get_performance_style(90.0)
+            .apply_to("90%")
+            .to_string();
+        assert_eq!(unstyled, "90%");
+    }
+}
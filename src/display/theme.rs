@@ -0,0 +1,220 @@
+//! Section labels and colors, from a built-in theme or a `--theme-file`
+//!
+//! A theme file is TOML with one table per section, e.g.
+//!
+//! ```toml
+//! [arc]
+//! label = "[CACHE]"
+//! color = "cyan"
+//! ```
+//!
+//! Sections and keys left out keep the `default` theme's values.
+
+use super::terminal::Terminal;
+use crate::config::{parse_string, strip_comment};
+use console::Color;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Header label of one dashboard section and the color it is drawn in
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionStyle {
+    pub label: String,
+    pub color: Option<Color>,
+}
+
+impl SectionStyle {
+    fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            color: None,
+        }
+    }
+
+    /// The label, colored when the terminal allows it
+    pub fn render(&self, terminal: &Terminal) -> String {
+        match self.color {
+            Some(color) => terminal
+                .get_color_style(color)
+                .apply_to(&self.label)
+                .to_string(),
+            None => self.label.clone(),
+        }
+    }
+}
+
+/// Labels and colors of the ARC, L2ARC and SLOG sections
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub arc: SectionStyle,
+    pub l2arc: SectionStyle,
+    pub slog: SectionStyle,
+}
+
+impl Theme {
+    /// A built-in theme by name: `default` (emoji labels) or `plain`
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self {
+                arc: SectionStyle::new("📊 ARC (Primary RAM Cache, system-wide)"),
+                l2arc: SectionStyle::new("💾 L2ARC (Secondary SSD Cache)"),
+                slog: SectionStyle::new("🟡 SLOG (Synchronous Write Log)"),
+            }),
+            "plain" => Some(Self {
+                arc: SectionStyle::new("ARC (Primary RAM Cache, system-wide)"),
+                l2arc: SectionStyle::new("L2ARC (Secondary SSD Cache)"),
+                slog: SectionStyle::new("SLOG (Synchronous Write Log)"),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Load a theme file; unlike the config file it must exist
+    pub async fn load(path: &Path) -> Result<Self, String> {
+        tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|content| content.parse())
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::builtin("default").expect("default theme is built in")
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut theme = Theme::default();
+        let mut section: Option<&mut SectionStyle> = None;
+
+        for (index, line) in s.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let err = |msg: String| format!("line {}: {}", index + 1, msg);
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = Some(match name.trim() {
+                    "arc" => &mut theme.arc,
+                    "l2arc" => &mut theme.l2arc,
+                    "slog" => &mut theme.slog,
+                    other => return Err(err(format!("Unknown section '{}'", other))),
+                });
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| err(format!("expected key = value, got '{}'", line)))?;
+            let style = section
+                .as_deref_mut()
+                .ok_or_else(|| err("expected [arc], [l2arc] or [slog] first".to_string()))?;
+            let value = parse_string(value.trim()).map_err(err)?;
+
+            match key.trim() {
+                "label" => style.label = value,
+                "color" => style.color = Some(parse_color(&value).map_err(err)?),
+                other => return Err(err(format!("Unknown key '{}'", other))),
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+fn parse_color(name: &str) -> Result<Color, String> {
+    match name {
+        "black" => Ok(Color::Black),
+        "red" => Ok(Color::Red),
+        "green" => Ok(Color::Green),
+        "yellow" => Ok(Color::Yellow),
+        "blue" => Ok(Color::Blue),
+        "magenta" => Ok(Color::Magenta),
+        "cyan" => Ok(Color::Cyan),
+        "white" => Ok(Color::White),
+        _ => Err(format!("Unknown color '{}'", name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::ColorMode;
+
+    #[test]
+    fn test_builtin_themes() {
+        assert_eq!(
+            Theme::default().arc.label,
+            "📊 ARC (Primary RAM Cache, system-wide)"
+        );
+        assert_eq!(
+            Theme::builtin("plain").unwrap().slog.label,
+            "SLOG (Synchronous Write Log)"
+        );
+        assert!(Theme::builtin("neon").is_none());
+    }
+
+    #[test]
+    fn test_parse_theme() {
+        let theme: Theme = r#"
+# Terse labels
+[arc]
+label = "[CACHE]"   # shown in cyan
+color = "cyan"
+
+[slog]
+label = "[LOG]"
+"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(theme.arc.label, "[CACHE]");
+        assert_eq!(theme.arc.color, Some(Color::Cyan));
+        assert_eq!(theme.slog.label, "[LOG]");
+        assert_eq!(theme.slog.color, None);
+        // Untouched sections keep the default theme
+        assert_eq!(theme.l2arc, Theme::default().l2arc);
+    }
+
+    #[test]
+    fn test_parse_theme_errors() {
+        assert!(
+            "label = \"x\""
+                .parse::<Theme>()
+                .unwrap_err()
+                .contains("line 1")
+        );
+        assert!(
+            "[zil]"
+                .parse::<Theme>()
+                .unwrap_err()
+                .contains("Unknown section 'zil'")
+        );
+        assert!(
+            "[arc]\ncolor = \"teal\""
+                .parse::<Theme>()
+                .unwrap_err()
+                .contains("line 2: Unknown color 'teal'")
+        );
+        assert!("[arc]\nicon = \"x\"".parse::<Theme>().is_err());
+    }
+
+    #[test]
+    fn test_section_style_render() {
+        let style = SectionStyle {
+            label: "[CACHE]".to_string(),
+            color: Some(Color::Cyan),
+        };
+        let plain = Terminal::with_color_mode(ColorMode::Never);
+        assert_eq!(style.render(&plain), "[CACHE]");
+
+        let colored = style.render(&Terminal::with_color_mode(ColorMode::Always));
+        assert!(colored.contains("\x1b[36m[CACHE]"));
+    }
+}
@@ -0,0 +1,229 @@
+//! Self-test (`--doctor`): check each data source the monitor relies on
+
+use crate::system::{CommandExecutor, FilesystemReader};
+use crate::zfs::{PoolManager, ZfsStatsCollector};
+use std::io::{self, Write};
+use std::time::Duration;
+
+const ARCSTATS_PATH: &str = "/proc/spl/kstat/zfs/arcstats";
+
+/// Outcome of a single check
+#[derive(Debug)]
+pub struct Check {
+    pub name: &'static str,
+    pub passed: bool,
+    pub detail: String,
+    /// What to do about a failure
+    pub hint: &'static str,
+}
+
+impl Check {
+    fn new(name: &'static str, passed: bool, detail: String, hint: &'static str) -> Self {
+        Self {
+            name,
+            passed,
+            detail,
+            hint,
+        }
+    }
+}
+
+/// All checks, in the order they ran
+#[derive(Debug)]
+pub struct Report {
+    pub checks: Vec<Check>,
+    /// Whether any ARC source works; the monitor is useless without it
+    pub arc_available: bool,
+}
+
+impl Report {
+    /// Print one pass/fail line per check, with a hint under each failure
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        for check in &self.checks {
+            let mark = if check.passed { "✅" } else { "❌" };
+            writeln!(out, "{} {}: {}", mark, check.name, check.detail)?;
+            if !check.passed {
+                writeln!(out, "   → {}", check.hint)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run every check against `pool`, giving up on each command after `timeout`
+pub async fn diagnose<E: CommandExecutor, F: FilesystemReader>(
+    executor: E,
+    reader: F,
+    pool: &str,
+    timeout: Duration,
+) -> Report {
+    let mut checks = Vec::new();
+
+    let arcstats_readable = reader.exists(ARCSTATS_PATH).await;
+    checks.push(Check::new(
+        "ARC kstats",
+        arcstats_readable,
+        if arcstats_readable {
+            format!("{} is readable", ARCSTATS_PATH)
+        } else {
+            format!("{} is missing or unreadable", ARCSTATS_PATH)
+        },
+        "Load the ZFS kernel module (`modprobe zfs`) or run with permission to read /proc/spl",
+    ));
+
+    let pools = PoolManager::new(&executor)
+        .with_command_timeout(timeout)
+        .list_pools()
+        .await;
+    checks.push(Check::new(
+        "zpool command",
+        pools.is_ok(),
+        match &pools {
+            Ok(_) => "`zpool list` runs".to_string(),
+            Err(e) => e.to_string(),
+        },
+        "Install the ZFS utilities (e.g. zfsutils-linux) and make sure `zpool` is on PATH",
+    ));
+
+    let pools = pools.unwrap_or_default();
+    checks.push(Check::new(
+        "Pools",
+        !pools.is_empty(),
+        if pools.is_empty() {
+            "no imported pools".to_string()
+        } else {
+            pools.join(", ")
+        },
+        "Import a pool with `zpool import <pool>`",
+    ));
+
+    let arcstat = executor
+        .execute_with_timeout("arcstat", &["1", "1"], timeout)
+        .await;
+    checks.push(Check::new(
+        "arcstat command",
+        arcstat.is_ok(),
+        match &arcstat {
+            Ok(_) => "`arcstat` runs".to_string(),
+            Err(e) => e.to_string(),
+        },
+        "Optional: install `arcstat` (ships with the ZFS utilities) as a fallback ARC source",
+    ));
+
+    let mut collector = ZfsStatsCollector::new(executor, reader)
+        .with_pool(pool)
+        .with_command_timeout(timeout);
+
+    let arc = collector.collect_arc_stats().await;
+    let arc_available = arc.is_ok();
+    checks.push(Check::new(
+        "ARC statistics",
+        arc_available,
+        match &arc {
+            Ok(arc) => format!("{:.1}% hit rate", arc.hit_rate),
+            Err(e) => e.to_string(),
+        },
+        "No ARC source works; fix the ARC kstats or arcstat check above",
+    ));
+
+    let l2arc = collector.collect_l2arc_stats().await;
+    checks.push(Check::new(
+        "L2ARC",
+        matches!(l2arc, Ok(Some(_))),
+        match &l2arc {
+            Ok(Some(_)) => "cache device present".to_string(),
+            Ok(None) => "no cache device".to_string(),
+            Err(e) => e.to_string(),
+        },
+        "Optional: add a cache device with `zpool add <pool> cache <device>`",
+    ));
+
+    let slog = collector.collect_slog_stats().await;
+    checks.push(Check::new(
+        "SLOG",
+        matches!(slog, Ok(Some(_))),
+        match &slog {
+            Ok(Some(slog)) => format!("{} in pool {}", slog.device, pool),
+            Ok(None) => format!("no log device in pool {}", pool),
+            Err(e) => e.to_string(),
+        },
+        "Optional: add a log device with `zpool add <pool> log <device>`",
+    ));
+
+    Report {
+        checks,
+        arc_available,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::commands::{DEFAULT_COMMAND_TIMEOUT, DemoCommandExecutor};
+    use crate::system::filesystem::DemoFilesystemReader;
+    use std::error::Error;
+
+    struct MissingFilesystemReader;
+
+    #[async_trait::async_trait]
+    impl FilesystemReader for MissingFilesystemReader {
+        async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+            Err(format!("No such file: {}", path).into())
+        }
+    }
+
+    struct MissingCommandExecutor;
+
+    #[async_trait::async_trait]
+    impl CommandExecutor for MissingCommandExecutor {
+        async fn execute(&self, command: &str, _args: &[&str]) -> Result<String, Box<dyn Error>> {
+            Err(format!("{}: command not found", command).into())
+        }
+    }
+
+    fn render(report: &Report) -> String {
+        let mut out = Vec::new();
+        report.write(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_diagnose_demo() {
+        let report = diagnose(
+            DemoCommandExecutor,
+            DemoFilesystemReader,
+            "data",
+            DEFAULT_COMMAND_TIMEOUT,
+        )
+        .await;
+
+        assert!(report.arc_available);
+        assert!(report.checks.iter().all(|check| check.passed));
+
+        let output = render(&report);
+        assert!(output.contains("✅ ARC kstats: /proc/spl/kstat/zfs/arcstats is readable"));
+        assert!(output.contains("✅ Pools: boot-pool, data, usb-backup"));
+        assert!(output.contains("✅ SLOG: mirror-1 in pool data"));
+        assert!(!output.contains("→"));
+    }
+
+    #[tokio::test]
+    async fn test_diagnose_without_zfs() {
+        let report = diagnose(
+            MissingCommandExecutor,
+            MissingFilesystemReader,
+            "data",
+            DEFAULT_COMMAND_TIMEOUT,
+        )
+        .await;
+
+        assert!(!report.arc_available);
+        assert!(report.checks.iter().all(|check| !check.passed));
+
+        let output = render(&report);
+        assert!(output.contains("❌ ARC kstats: /proc/spl/kstat/zfs/arcstats is missing"));
+        assert!(output.contains("❌ Pools: no imported pools"));
+        assert!(output.contains("   → Load the ZFS kernel module (`modprobe zfs`)"));
+        assert!(output.contains("   → Import a pool with `zpool import <pool>`"));
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+
+/// Errors that stop the monitor before or while it runs
+#[derive(Debug)]
+pub enum MonitorError {
+    /// Neither the ZFS kstats nor the `zpool` command are available
+    ZfsUnavailable,
+    /// `--doctor` found no working ARC statistics source
+    DoctorFailed,
+    /// A refresh interval that would re-run `zpool` in a tight loop
+    InvalidInterval(u32),
+    /// ZFS is loaded and `zpool` runs, but no pool is imported
+    NoPools,
+    /// `--remote` host can't be logged into over ssh
+    RemoteUnreachable { destination: String, reason: String },
+}
+
+impl fmt::Display for MonitorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MonitorError::ZfsUnavailable => write!(
+                f,
+                "ZFS not detected: /proc/spl/kstat/zfs/arcstats is missing and `zpool` could not be run.\n\
+                 Load the ZFS kernel module (`modprobe zfs`) and install the ZFS utilities, \
+                 or set DEMO_MODE=true to try the monitor with sample data."
+            ),
+            MonitorError::DoctorFailed => {
+                write!(
+                    f,
+                    "ARC statistics are unavailable, see the failed checks above"
+                )
+            }
+            MonitorError::InvalidInterval(interval) => write!(
+                f,
+                "Invalid interval {}s: the refresh interval must be at least 1 second",
+                interval
+            ),
+            MonitorError::NoPools => write!(
+                f,
+                "ZFS is loaded but no pools are imported.\n\
+                 Run `zpool import` to see the pools available for import and `zpool import POOL` \
+                 to import one, or set DEMO_MODE=true to try the monitor with sample data."
+            ),
+            MonitorError::RemoteUnreachable {
+                destination,
+                reason,
+            } => write!(
+                f,
+                "Cannot reach {} over ssh: {}\n\
+                 Check that `ssh {} true` works without a password prompt (key or agent login).",
+                destination,
+                reason.trim(),
+                destination
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MonitorError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zfs_unavailable_display() {
+        let message = MonitorError::ZfsUnavailable.to_string();
+        assert!(message.starts_with("ZFS not detected"));
+        assert!(message.contains("DEMO_MODE=true"));
+    }
+
+    #[test]
+    fn test_no_pools_display() {
+        let message = MonitorError::NoPools.to_string();
+        assert!(message.starts_with("ZFS is loaded but no pools are imported"));
+        assert!(message.contains("zpool import"));
+    }
+
+    #[test]
+    fn test_remote_unreachable_display() {
+        let message = MonitorError::RemoteUnreachable {
+            destination: "admin@nas".to_string(),
+            reason: "Command failed: ssh: connect to host nas port 22: Connection refused\n"
+                .to_string(),
+        }
+        .to_string();
+        assert!(message.starts_with(
+            "Cannot reach admin@nas over ssh: Command failed: ssh: connect to host nas port 22: \
+             Connection refused\n"
+        ));
+        assert!(message.contains("`ssh admin@nas true`"));
+    }
+}
@@ -0,0 +1,6 @@
+//! The collectors and parsers behind the monitor, as a library for the
+//! benchmarks in `benches/`. The binary builds these modules itself.
+
+pub mod demo;
+pub mod system;
+pub mod zfs;
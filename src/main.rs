@@ -1,9 +1,16 @@
+mod cli;
+mod config;
 mod demo;
 mod display;
+mod doctor;
+mod error;
 mod monitor;
 mod system;
 mod zfs;
 
+use cli::CliArgs;
+use config::Config;
+use display::Theme;
 use std::env;
 use std::process;
 
@@ -12,17 +19,58 @@ fn main() {
     rt.block_on(async_main());
 }
 
+/// Send `log` diagnostics to stderr, filtered by `RUST_LOG` (warnings by default);
+/// `ZPOOL_RW_METER_DEBUG` still turns on this tool's debug messages
+fn init_logging() {
+    let mut builder =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn"));
+    if env::var_os("ZPOOL_RW_METER_DEBUG").is_some() {
+        builder.filter_module(module_path!(), log::LevelFilter::Debug);
+    }
+    builder.init();
+}
+
 async fn async_main() {
-    let args: Vec<String> = env::args().collect();
+    init_logging();
+    let args: Vec<String> = env::args().skip(1).collect();
 
-    // Parse command line arguments: [POOL] [INTERVAL]
-    let pool = args.get(1).map(|s| s.as_str());
-    let interval = args.get(2).and_then(|s| s.parse::<u32>().ok()).unwrap_or(2); // Default 2 seconds
+    // Config file values replace the defaults; command line flags override both
+    let config = match Config::default_path() {
+        Some(path) => Config::load(&path).await,
+        None => Ok(None),
+    };
+    let defaults = match config {
+        Ok(config) => config.unwrap_or_default().apply(CliArgs::default()),
+        Err(e) => {
+            eprintln!("Error: invalid config file {}", e);
+            process::exit(2);
+        }
+    };
+
+    // Parse command line arguments: [POOL] [INTERVAL] [OPTIONS]
+    let mut cli_args = match CliArgs::parse_with_defaults(&args, defaults) {
+        Ok(cli_args) => cli_args,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            process::exit(2);
+        }
+    };
+
+    // A theme file replaces the built-in theme picked with --theme
+    if let Some(path) = &cli_args.theme_file {
+        match Theme::load(path).await {
+            Ok(theme) => cli_args.theme = theme,
+            Err(e) => {
+                eprintln!("Error: invalid theme file {}", e);
+                process::exit(2);
+            }
+        }
+    }
 
     // Check for demo mode
     let demo_mode = env::var("DEMO_MODE").unwrap_or_else(|_| "false".to_string()) == "true";
 
-    if let Err(e) = monitor::run_with_args(demo_mode, pool, interval).await {
+    if let Err(e) = monitor::run_with_args(demo_mode, &cli_args).await {
         eprintln!("Error: {}", e);
         process::exit(1);
     }
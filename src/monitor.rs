@@ -1,38 +1,400 @@
 // Demo data imports are no longer needed since we parse from files
+use crate::cli::{CliArgs, OutputFormat};
+use crate::demo::{DEMO_SEED, SharedDemoData};
+use crate::display::json::{
+    JsonValue, Snapshot, batch_envelope, error_envelope, metrics_envelope, metrics_metadata,
+};
+use crate::display::overview::{OverviewRow, overview_table, render_overview};
+use crate::display::theme::SectionStyle;
 use crate::display::{
-    ProgressBar, Terminal, format_bytes, format_bytes_ratio, format_latency_ms,
-    format_ops_per_second, format_rate,
+    ColorMode, NumberFormat, ProgressBar, Terminal, format_bytes, format_bytes_into,
+    format_bytes_ratio, format_duration, format_latency_ms, format_ops_per_second,
+    format_ops_per_second_into, format_rate, format_rate_into,
+};
+use crate::doctor;
+use crate::error::MonitorError;
+use crate::system::commands::{
+    DemoCommandExecutor, DynamicDemoCommandExecutor, RealCommandExecutor, RemoteCommandExecutor,
+};
+use crate::system::filesystem::{
+    DemoFilesystemReader, DynamicDemoFilesystemReader, RealFilesystemReader, RemoteFilesystemReader,
+};
+use crate::system::{CommandExecutor, FilesystemReader};
+use crate::zfs::error::ZfsError;
+use crate::zfs::rate_calculator::RateCalculator;
+use crate::zfs::score;
+use crate::zfs::{
+    ArcPressure, ArcStats, CacheStatus, CacheThresholds, CollectedAt, DatasetStats, L2ArcStats,
+    PoolIoStats, PoolManager, SlogStats, ZfsStatsCollector, percent_of_peak,
 };
-use crate::system::commands::{DemoCommandExecutor, RealCommandExecutor};
-use crate::system::filesystem::{DemoFilesystemReader, RealFilesystemReader};
-use crate::zfs::{CacheStatus, ZfsStatsCollector};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use std::error::Error;
-use std::io::Write;
+use std::fs::OpenOptions;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
 
+/// Where rendered frames are written: stdout or the `--output` file
+struct Output {
+    writer: Box<dyn Write>,
+    /// Cursor movement and screen clearing only make sense on a terminal
+    is_terminal: bool,
+}
+
+impl Output {
+    /// Open the `--output` file (truncated unless `--append`), or stdout
+    fn open(args: &CliArgs) -> io::Result<Self> {
+        match &args.output {
+            Some(path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .append(args.append)
+                    .truncate(!args.append)
+                    .open(path)?;
+                let is_terminal = file.is_terminal();
+                Ok(Self {
+                    writer: Box::new(file),
+                    is_terminal,
+                })
+            }
+            None => Ok(Self {
+                is_terminal: io::stdout().is_terminal(),
+                writer: Box::new(io::stdout()),
+            }),
+        }
+    }
 
+    /// Color mode for this target: `auto` means no color unless it is a terminal
+    fn color_mode(&self, requested: ColorMode) -> ColorMode {
+        match requested {
+            ColorMode::Auto if !self.is_terminal => ColorMode::Never,
+            mode => mode,
+        }
+    }
+}
 
 /// Main monitoring loop with arguments
-pub async fn run_with_args(
+pub async fn run_with_args(demo_mode: bool, args: &CliArgs) -> Result<(), Box<dyn Error>> {
+    if args.list_metrics {
+        return Ok(list_metrics(&mut io::stdout(), args.format)?);
+    }
+
+    // In a container these reach the host's ZFS tools and /proc
+    let executor = RealCommandExecutor::with_prefix(args.command_prefix.clone());
+    match &args.remote {
+        Some(destination) if !demo_mode => {
+            let executor = connect_remote(destination, executor, args.command_timeout).await?;
+            let reader = RemoteFilesystemReader::new(executor.clone(), args.command_timeout);
+            run_with_sources(demo_mode, args, executor, reader).await
+        }
+        _ => {
+            let reader = RealFilesystemReader::with_root(args.fs_root.clone());
+            run_with_sources(demo_mode, args, executor, reader).await
+        }
+    }
+}
+
+/// Executor for the `--remote` host, once logging in there has worked
+async fn connect_remote<E: CommandExecutor>(
+    destination: &str,
+    inner: E,
+    timeout: Duration,
+) -> Result<RemoteCommandExecutor<E>, MonitorError> {
+    let executor = RemoteCommandExecutor::new(destination, inner);
+    executor
+        .check_connection(timeout)
+        .await
+        .map_err(|e| MonitorError::RemoteUnreachable {
+            destination: destination.to_string(),
+            reason: e.to_string(),
+        })?;
+    Ok(executor)
+}
+
+/// Run whatever `args` ask for, with live data read through `executor` and
+/// `reader`, which reach either this machine or the `--remote` host
+async fn run_with_sources<E, F>(
     demo_mode: bool,
-    pool: Option<&str>,
-    interval: u32,
-) -> Result<(), Box<dyn Error>> {
-    let terminal = Terminal::new();
-    let pool_name = pool.unwrap_or("data"); // Default pool
+    args: &CliArgs,
+    executor: E,
+    reader: F,
+) -> Result<(), Box<dyn Error>>
+where
+    E: CommandExecutor + Clone,
+    F: FilesystemReader + Clone,
+{
+    if args.list_pools {
+        let mut stdout = std::io::stdout();
+        return if demo_mode {
+            list_pools(&DemoCommandExecutor, args.command_timeout, &mut stdout).await
+        } else {
+            list_pools(&executor, args.command_timeout, &mut stdout).await
+        };
+    }
+
+    let pool_name = if demo_mode {
+        resolve_pool(&DemoCommandExecutor, args).await
+    } else {
+        resolve_pool(&executor, args).await
+    };
+
+    if args.doctor {
+        // The doctor checks for imported pools itself, so it runs without any
+        let pool_name = pool_name.as_deref().unwrap_or("data");
+        let report = if demo_mode {
+            doctor::diagnose(
+                DemoCommandExecutor,
+                DemoFilesystemReader,
+                pool_name,
+                args.command_timeout,
+            )
+            .await
+        } else {
+            doctor::diagnose(executor, reader, pool_name, args.command_timeout).await
+        };
+        report.write(&mut io::stdout())?;
+        return if report.arc_available {
+            Ok(())
+        } else {
+            Err(MonitorError::DoctorFailed.into())
+        };
+    }
+
+    check_interval(args.interval)?;
 
-    if demo_mode {
-        run_demo_mode(&terminal, pool_name, interval).await
+    let mut out = Output::open(args)?;
+    // Reports get pasted elsewhere, so only color them when asked to
+    let color = match args.color {
+        ColorMode::Auto if args.report => ColorMode::Never,
+        mode => mode,
+    };
+    let terminal = Terminal::with_color_mode(out.color_mode(color));
+
+    if let Some(path) = &args.replay {
+        return replay(&mut out, &terminal, path, args).await;
+    }
+    let pool_name = pool_name?;
+    let pool_name = pool_name.as_str();
+
+    if demo_mode && args.demo_static {
+        run_dashboard(&mut out, &terminal, pool_name, args, || {
+            (DemoCommandExecutor, DemoFilesystemReader)
+        })
+        .await
+    } else if demo_mode {
+        let data = SharedDemoData::new(DEMO_SEED);
+        run_dashboard(&mut out, &terminal, pool_name, args, || {
+            (
+                DynamicDemoCommandExecutor::new(data.clone()),
+                DynamicDemoFilesystemReader::new(data.clone()),
+            )
+        })
+        .await
     } else {
-        run_live_mode(&terminal, pool_name, interval).await
+        // Fail early with guidance rather than erroring out of the loop
+        detect_zfs(&executor, &reader, args.command_timeout).await?;
+
+        run_dashboard(&mut out, &terminal, pool_name, args, || {
+            (executor.clone(), reader.clone())
+        })
+        .await
+    }
+}
+
+/// Build a collector for the monitored pool, or one per imported pool with
+/// `--overview`, from `sources`, and run the monitor loop over them
+async fn run_dashboard<E: CommandExecutor, F: FilesystemReader>(
+    out: &mut Output,
+    terminal: &Terminal,
+    pool_name: &str,
+    args: &CliArgs,
+    sources: impl Fn() -> (E, F),
+) -> Result<(), Box<dyn Error>> {
+    let (executor, _) = sources();
+    let pools = monitored_pools(&executor, pool_name, args).await?;
+
+    let collectors = pools
+        .iter()
+        .map(|pool| {
+            let (executor, reader) = sources();
+            ZfsStatsCollector::new(executor, reader)
+                .with_pool(pool)
+                .with_device_name_resolution(args.short_names)
+                .with_arcstat_reads(args.arcstat_reads)
+                .with_since_boot_rates(args.since_boot_rates)
+                .with_command_timeout(args.command_timeout)
+        })
+        .collect();
+    run_monitor_loop(out, terminal, collectors, pool_name, args).await
+}
+
+/// The pool to monitor: the one named on the command line, else the busiest by
+/// write ops, else the first imported one, and "data" when `zpool` can't tell.
+/// Fails when `zpool` runs fine but there is no pool at all.
+async fn resolve_pool<E: CommandExecutor>(
+    executor: &E,
+    args: &CliArgs,
+) -> Result<String, MonitorError> {
+    if let Some(pool) = &args.pool {
+        return Ok(pool.clone());
+    }
+
+    let manager = PoolManager::new(executor).with_command_timeout(args.command_timeout);
+    if let Ok(Some(pool)) = manager.busiest_pool().await {
+        return Ok(pool);
+    }
+    match manager.get_default_pool().await {
+        Ok(pool) => Ok(pool),
+        // `zpool list` worked and came back empty
+        Err(ZfsError::SubsystemUnavailable { .. }) => Err(MonitorError::NoPools),
+        Err(_) => Ok("data".to_string()),
+    }
+}
+
+/// Pools to collect: just `pool_name`, or with `--overview` every imported pool
+/// except `--exclude-pool` ones. A pool named on the command line is never excluded.
+async fn monitored_pools<E: CommandExecutor>(
+    executor: &E,
+    pool_name: &str,
+    args: &CliArgs,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    if !args.overview {
+        return Ok(vec![pool_name.to_string()]);
+    }
+
+    let pools = PoolManager::new(executor)
+        .with_command_timeout(args.command_timeout)
+        .list_pools()
+        .await?;
+    if pools.is_empty() {
+        return Err(MonitorError::NoPools.into());
+    }
+    let pools: Vec<String> = pools
+        .into_iter()
+        .filter(|pool| {
+            args.pool.as_deref() == Some(pool.as_str()) || !args.exclude_pools.contains(pool)
+        })
+        .collect();
+    if pools.is_empty() {
+        return Err("Every imported pool is excluded by --exclude-pool".into());
+    }
+    Ok(pools)
+}
+
+/// Intervals above this many seconds are allowed but probably a typo (e.g. milliseconds)
+const MAX_SENSIBLE_INTERVAL: u32 = 3600;
+
+/// Reject a zero interval, which would run `zpool` back to back, and warn about huge ones
+fn check_interval(interval: u32) -> Result<(), MonitorError> {
+    if interval == 0 {
+        return Err(MonitorError::InvalidInterval(interval));
+    }
+    if interval > MAX_SENSIBLE_INTERVAL {
+        eprintln!(
+            "Warning: refresh interval of {}s is over an hour, did you mean {}ms?",
+            interval, interval
+        );
+    }
+    Ok(())
+}
+
+/// Check that ZFS is loaded: either the ARC kstats exist or `zpool` runs
+async fn detect_zfs<E: CommandExecutor, F: FilesystemReader>(
+    executor: &E,
+    reader: &F,
+    timeout: Duration,
+) -> Result<(), MonitorError> {
+    if reader.exists("/proc/spl/kstat/zfs/arcstats").await {
+        return Ok(());
+    }
+
+    match executor
+        .execute_with_timeout("zpool", &["list", "-H", "-o", "name"], timeout)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(_) => Err(MonitorError::ZfsUnavailable),
+    }
+}
+
+/// Print every imported pool name, one per line
+async fn list_pools<E: CommandExecutor>(
+    executor: &E,
+    timeout: Duration,
+    out: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let manager = PoolManager::new(executor).with_command_timeout(timeout);
+    for pool in manager.list_pools().await? {
+        writeln!(out, "{}", pool)?;
+    }
+    Ok(())
+}
+
+/// One aligned line per metric, or all of them as a JSON array with `--format json`
+fn list_metrics(out: &mut impl Write, format: OutputFormat) -> io::Result<()> {
+    let metrics = metrics_metadata();
+    if format == OutputFormat::Json {
+        let array = JsonValue::Array(metrics.iter().map(JsonValue::from).collect());
+        return writeln!(out, "{}", array);
+    }
+
+    let name_width = metrics
+        .iter()
+        .map(|metric| metric.name.len())
+        .max()
+        .unwrap_or(0);
+    let unit_width = metrics
+        .iter()
+        .map(|metric| metric.unit.len())
+        .max()
+        .unwrap_or(0);
+    for metric in metrics {
+        writeln!(
+            out,
+            "{:name_width$}  {:unit_width$}  {}",
+            metric.name, metric.unit, metric.description
+        )?;
     }
+    Ok(())
 }
 
-async fn run_demo_mode(
+async fn run_monitor_loop<E: CommandExecutor, F: FilesystemReader>(
+    out: &mut Output,
     terminal: &Terminal,
+    mut collectors: Vec<ZfsStatsCollector<E, F>>,
     pool_name: &str,
-    interval: u32,
+    args: &CliArgs,
 ) -> Result<(), Box<dyn Error>> {
-    let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+    if args.report {
+        return write_report(out, terminal, &mut collectors[0], pool_name, args).await;
+    }
+    if args.once {
+        return match args.format {
+            OutputFormat::Json => write_batch(out, &mut collectors[0], pool_name, args).await,
+            OutputFormat::Text | OutputFormat::Table => {
+                let mut session = Session::new();
+                write_refresh(
+                    out,
+                    true,
+                    terminal,
+                    &mut collectors,
+                    pool_name,
+                    args,
+                    &mut session,
+                )
+                .await
+            }
+        };
+    }
+
+    let mut ticker = Ticker::new(args);
+    // --adaptive may widen the interval, which the header then shows
+    let mut args = args.clone();
+    // --follow-new-pool may move on to another pool
+    let mut pool_name = pool_name.to_string();
 
     // Set up signal handler for Ctrl+C
     let (tx, mut rx) = tokio::sync::mpsc::channel(1);
@@ -42,198 +404,4109 @@ async fn run_demo_mode(
         let _ = tx_clone.send(()).await;
     });
 
+    // The first frame wipes the screen once; later frames overwrite it in place
+    let mut first_frame = true;
+    let mut refreshes = 0;
+    let mut session = Session::new();
+    // Set while ZFS is gone (e.g. the module is being reloaded) until it is back
+    let mut waiting = false;
+
+    // Take a silent sample first so the rates on the first frame aren't all 0/s,
+    // and show that frame after the warm-up rather than a whole interval
+    let mut primed = false;
+    if args.warmup_ms > 0 {
+        for collector in collectors.iter_mut() {
+            collect_sample(collector, &args).await?;
+        }
+        tokio::time::sleep(Duration::from_millis(args.warmup_ms)).await;
+        primed = true;
+    }
+
     loop {
-        tokio::select! {
-            _ = rx.recv() => {
-                // Ctrl+C received, exit gracefully
-                terminal.show_cursor()?;
-                println!("\nMonitoring stopped.");
-                return Ok(());
+        // Straight after priming, render without waiting for a tick
+        if !std::mem::take(&mut primed) {
+            tokio::select! {
+                _ = rx.recv() => {
+                    // Ctrl+C received, exit gracefully
+                    if restore_cursor(out, terminal, &args)? {
+                        writeln!(out.writer, "\nMonitoring stopped.")?;
+                        out.writer.flush()?;
+                    }
+                    return Ok(());
+                }
+                tick = ticker.tick() => {
+                    // Time to refresh
+                    if args.high_precision {
+                        for collector in collectors.iter_mut() {
+                            collector.pin_sample_time(tick);
+                        }
+                    }
+                }
             }
-            _ = tokio::time::sleep(tokio::time::Duration::from_secs(interval as u64)) => {
-                // Time to refresh
+        }
+
+        if args.follow_new_pool && !args.overview {
+            let executor = collectors[0].command_executor();
+            if let Some(pool) = replacement_pool(executor, &pool_name, args.command_timeout).await {
+                if args.quiet {
+                    writeln!(
+                        out.writer,
+                        "{} {}: pool is no longer imported, switched to {}",
+                        alert_time(),
+                        pool_name,
+                        pool
+                    )?;
+                }
+                collectors[0].switch_pool(&pool);
+                session.switched_from = Some(std::mem::replace(&mut pool_name, pool));
             }
         }
 
-        // Clear screen and hide cursor for flicker-free updates
-        terminal.clear_screen()?;
-        terminal.hide_cursor()?;
+        let started = tokio::time::Instant::now();
+        let refreshed = write_refresh(
+            out,
+            first_frame,
+            terminal,
+            &mut collectors,
+            &pool_name,
+            &args,
+            &mut session,
+        )
+        .await;
+        match refreshed {
+            Ok(()) => {
+                if waiting && args.quiet {
+                    writeln!(
+                        out.writer,
+                        "{} {}: ZFS stats are back",
+                        alert_time(),
+                        &pool_name
+                    )?;
+                    out.writer.flush()?;
+                }
+                first_frame = false;
+                waiting = false;
+            }
+            Err(e) => {
+                let Some(error) = e.downcast_ref::<ZfsError>().filter(|e| arcstats_gone(e)) else {
+                    return Err(e);
+                };
+                // Stats stopped coming in: keep retrying every interval instead of exiting.
+                // A reloaded module restarts its counters, so drop the old rate baselines.
+                for collector in collectors.iter_mut() {
+                    collector.reset_rates();
+                }
+                write_waiting(out, !waiting, terminal, error, &pool_name, &args)?;
+                waiting = true;
+                // The dashboard wipes the waiting message when it comes back
+                first_frame = true;
+            }
+        }
 
-        // Collect stats
-        let arc_stats = collector.collect_arc_stats().await?;
-        let l2arc_stats = collector.collect_l2arc_stats().await?;
-        let slog_stats = collector.collect_slog_stats().await?;
+        // Not announced on stderr, which would scribble over the dashboard; the
+        // header's Refresh shows the new interval
+        if args.adaptive && !waiting {
+            if let Some(interval) = adapted_interval(args.interval, started.elapsed()) {
+                args.interval = interval;
+                ticker.set_period(Duration::from_secs(interval as u64));
+            }
+        }
 
-        // Display all sections
-        display_header(terminal, pool_name, interval)?;
-        display_arc_section(terminal, &arc_stats)?;
-        if let Some(l2arc) = l2arc_stats {
-            display_l2arc_section(terminal, &l2arc)?;
+        // Only frames with stats count, a run that never gets any doesn't end
+        if !waiting {
+            refreshes += 1;
         }
-        if let Some(slog) = slog_stats {
-            display_slog_section(terminal, &slog)?;
+        if args.count > 0 && refreshes >= args.count {
+            restore_cursor(out, terminal, &args)?;
+            return Ok(());
         }
-        display_footer(terminal)?;
+    }
+}
+
+/// Whether `error` means the ARC kstats are gone, e.g. while the ZFS module is
+/// reloaded: worth waiting for, unlike a parse error or missing permission
+fn arcstats_gone(error: &ZfsError) -> bool {
+    matches!(
+        error,
+        ZfsError::SubsystemUnavailable { subsystem, .. } if subsystem == "ARC" || subsystem == "L2ARC"
+    )
+}
 
-        // Flush output
-        std::io::stdout().flush()?;
+/// `--follow-new-pool`: the pool to move on to once `pool` is no longer imported,
+/// picked like at startup. None while it is still there, or if there is no other.
+async fn replacement_pool<E: CommandExecutor>(
+    executor: &E,
+    pool: &str,
+    timeout: Duration,
+) -> Option<String> {
+    let manager = PoolManager::new(executor).with_command_timeout(timeout);
+    let pools = manager.list_pools().await.ok()?;
+    if pools.iter().any(|imported| imported == pool) {
+        return None;
+    }
+    match manager.busiest_pool().await {
+        Ok(Some(busiest)) if pools.contains(&busiest) => Some(busiest),
+        _ => pools.into_iter().next(),
     }
 }
 
-async fn run_live_mode(
-    terminal: &Terminal,
-    pool_name: &str,
-    interval: u32,
-) -> Result<(), Box<dyn Error>> {
-    let mut collector = ZfsStatsCollector::new(RealCommandExecutor, RealFilesystemReader);
+/// Share of the refresh interval a refresh may take before `--adaptive` widens it
+const ADAPTIVE_BUDGET: f64 = 0.5;
 
-    // Set up signal handler for Ctrl+C
-    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
-    let tx_clone = tx.clone();
-    tokio::spawn(async move {
-        tokio::signal::ctrl_c().await.unwrap();
-        let _ = tx_clone.send(()).await;
-    });
+/// The interval (in seconds) `--adaptive` switches to after a refresh that took
+/// `took`, so refreshing uses at most `ADAPTIVE_BUDGET` of it. None while
+/// `interval` still leaves enough headroom; it never narrows again.
+fn adapted_interval(interval: u32, took: Duration) -> Option<u32> {
+    let took = took.as_secs_f64();
+    if took <= interval as f64 * ADAPTIVE_BUDGET {
+        return None;
+    }
+    Some((took / ADAPTIVE_BUDGET).ceil() as u32)
+}
 
-    loop {
-        tokio::select! {
-            _ = rx.recv() => {
-                // Ctrl+C received, exit gracefully
-                terminal.show_cursor()?;
-                println!("\nMonitoring stopped.");
-                return Ok(());
+/// Decides when the next refresh is due
+enum Ticker {
+    /// Sleep `interval` after each refresh, so collection time adds to the period
+    Sleep(Duration),
+    /// `--high-precision`: a fixed cadence; a late tick delays the following ones
+    /// rather than firing a burst to catch up
+    Interval(tokio::time::Interval),
+}
+
+impl Ticker {
+    fn new(args: &CliArgs) -> Self {
+        let period = Duration::from_secs(args.interval as u64);
+        if !args.high_precision {
+            return Ticker::Sleep(period);
+        }
+
+        // Like the sleeping ticker, the first refresh comes one period in
+        let start = tokio::time::Instant::now() + period;
+        let mut interval = tokio::time::interval_at(start, period);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        Ticker::Interval(interval)
+    }
+
+    /// Switch to a new period, starting from now
+    fn set_period(&mut self, period: Duration) {
+        match self {
+            Ticker::Sleep(current) => *current = period,
+            Ticker::Interval(interval) => {
+                let start = tokio::time::Instant::now() + period;
+                *interval = tokio::time::interval_at(start, period);
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
             }
-            _ = tokio::time::sleep(tokio::time::Duration::from_secs(interval as u64)) => {
-                // Time to refresh
+        }
+    }
+
+    /// Wait for the next refresh; returns when it was scheduled for
+    async fn tick(&mut self) -> Instant {
+        match self {
+            Ticker::Sleep(period) => {
+                tokio::time::sleep(*period).await;
+                tokio::time::Instant::now().into_std()
             }
+            Ticker::Interval(interval) => interval.tick().await.into_std(),
         }
+    }
+}
 
-        // Clear screen and hide cursor for flicker-free updates
-        terminal.clear_screen()?;
-        terminal.hide_cursor()?;
+/// Show the cursor hidden by the dashboard; returns whether there was one to restore
+fn restore_cursor(out: &mut Output, terminal: &Terminal, args: &CliArgs) -> io::Result<bool> {
+    if args.format == OutputFormat::Json || !out.is_terminal {
+        return Ok(false);
+    }
+    terminal.show_cursor(&mut out.writer)?;
+    out.writer.flush()?;
+    Ok(true)
+}
 
-        // Collect stats
-        let arc_stats = collector.collect_arc_stats().await?;
-        let l2arc_stats = collector.collect_l2arc_stats().await?;
-        let slog_stats = collector.collect_slog_stats().await?;
+/// Render one refresh to `out`, redrawing the dashboard in place on a terminal
+async fn write_refresh<E: CommandExecutor, F: FilesystemReader>(
+    out: &mut Output,
+    first_frame: bool,
+    terminal: &Terminal,
+    collectors: &mut [ZfsStatsCollector<E, F>],
+    pool_name: &str,
+    args: &CliArgs,
+    session: &mut Session,
+) -> Result<(), Box<dyn Error>> {
+    if args.quiet {
+        return watch_status(out, collectors, pool_name, args, session).await;
+    }
 
-        // Display all sections
-        display_header(terminal, pool_name, interval)?;
-        display_arc_section(terminal, &arc_stats)?;
-        if let Some(l2arc) = l2arc_stats {
-            display_l2arc_section(terminal, &l2arc)?;
+    let started = tokio::time::Instant::now();
+    let mut frame = Vec::new();
+    if args.overview {
+        let collecting = refresh_overview(&mut frame, terminal, collectors, args);
+        watch_collection(out, first_frame, terminal, args, collecting).await??;
+    } else {
+        // Leave room for the footer's note of a failing `--dump-raw`
+        let rows = dashboard_rows(out, terminal, args)
+            .map(|rows| rows.saturating_sub(usize::from(session.dump_error.is_some())));
+        let collecting = refresh(
+            &mut frame,
+            terminal,
+            &mut collectors[0],
+            pool_name,
+            Some(session),
+            rows,
+            args,
+        );
+        let refreshed = watch_collection(out, first_frame, terminal, args, collecting).await?;
+        // Also when parsing failed, as those inputs are the ones worth keeping; a
+        // failing dump is noted in the footer rather than ending the monitor
+        session.dump_error = dump_raw_inputs(&mut collectors[0], args)
+            .await
+            .err()
+            .map(|e| e.to_string());
+        if let Some(error) = session
+            .dump_error
+            .as_ref()
+            .filter(|_| args.format == OutputFormat::Json)
+        {
+            eprintln!("Warning: {}", error);
         }
-        if let Some(slog) = slog_stats {
-            display_slog_section(terminal, &slog)?;
+        refreshed?;
+    }
+    session.samples += 1;
+    if args.format == OutputFormat::Text {
+        display_footer(&mut frame, terminal, session)?;
+    }
+    if let Some(path) = &args.snapshot_file {
+        write_snapshot_file(path, &frame)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    // `--min-change` leaves the sections it kept as they are on screen, unless
+    // the watchdog's banner was written over them
+    let previous = Some(session.shown.as_slice()).filter(|_| {
+        args.min_change.is_some() && !first_frame && started.elapsed() < stall_limit(args)
+    });
+    present_frame(out, first_frame, terminal, &frame, previous, args)?;
+    session.shown = frame;
+    Ok(())
+}
+
+/// How long a refresh may collect before the watchdog calls it stalled: twice
+/// the interval, as a hung device can block `zpool` beyond its command timeout
+fn stall_limit(args: &CliArgs) -> Duration {
+    Duration::from_secs(2 * args.interval.max(1) as u64)
+}
+
+/// Await `collecting`, showing a banner once it runs past [`stall_limit`] so the
+/// dashboard doesn't just look frozen while a device hangs
+async fn watch_collection<T>(
+    out: &mut Output,
+    first_frame: bool,
+    terminal: &Terminal,
+    args: &CliArgs,
+    collecting: impl Future<Output = T>,
+) -> io::Result<T> {
+    let limit = stall_limit(args);
+    tokio::pin!(collecting);
+    tokio::select! {
+        result = &mut collecting => return Ok(result),
+        _ = tokio::time::sleep(limit) => {}
+    }
+
+    // Not in JSON, where every line has to be an envelope
+    if args.format != OutputFormat::Json {
+        let banner = stall_banner(terminal, &format!("no stats for {}s", limit.as_secs()));
+        if out.is_terminal {
+            // Over the title line of the frame still on screen
+            if first_frame {
+                terminal.clear_screen(&mut out.writer)?;
+            } else {
+                terminal.move_home(&mut out.writer)?;
+            }
+            write!(out.writer, "{}", banner)?;
+            terminal.clear_to_eol(&mut out.writer)?;
+            writeln!(out.writer)?;
+        } else {
+            writeln!(out.writer, "{}", banner)?;
         }
-        display_footer(terminal)?;
+        out.writer.flush()?;
+    }
+    Ok(collecting.await)
+}
 
-        // Flush output
-        std::io::stdout().flush()?;
+/// The watchdog's warning about a stalled collection, with `detail` on how long
+fn stall_banner(terminal: &Terminal, detail: &str) -> String {
+    let banner = format!("⚠ zpool command slow — possible device hang ({})", detail);
+    if terminal.supports_color {
+        console::Style::new()
+            .yellow()
+            .force_styling(true)
+            .apply_to(banner)
+            .to_string()
+    } else {
+        banner
     }
 }
 
-fn display_header(_terminal: &Terminal, pool: &str, interval: u32) -> Result<(), Box<dyn Error>> {
-    println!("{:=^80}", " 🔍 ZFS Cache Performance Monitor ");
-    println!(
-        "Pool: {} | Refresh: {}s | Time: {}",
-        pool,
-        interval,
-        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S")
-    );
-    println!();
+/// Tell the user the monitor is waiting for ZFS to come back, every retry:
+/// a redrawn message, or in JSON an error envelope in place of the metrics
+fn write_waiting(
+    out: &mut Output,
+    first_frame: bool,
+    terminal: &Terminal,
+    error: &ZfsError,
+    pool_name: &str,
+    args: &CliArgs,
+) -> io::Result<()> {
+    let mut frame = Vec::new();
+    if args.quiet {
+        // Once when ZFS goes away, not on every retry
+        if first_frame {
+            writeln!(
+                out.writer,
+                "{} {}: waiting for ZFS: {}",
+                alert_time(),
+                pool_name,
+                error
+            )?;
+            out.writer.flush()?;
+        }
+        return Ok(());
+    }
+    if args.format == OutputFormat::Json {
+        writeln!(frame, "{}", error_envelope(pool_name, Utc::now(), error))?;
+        return present_frame(out, first_frame, terminal, &frame, None, args);
+    }
+
+    writeln!(frame, "⏳ Waiting for ZFS: {}", error)?;
+    writeln!(
+        frame,
+        "    Retrying every {}s, monitoring resumes once the stats are back",
+        args.interval
+    )?;
+    present_frame(out, first_frame, terminal, &frame, None, args)
+}
+
+/// `--quiet`: collect a sample for every monitored pool and print a line for each
+/// change since its previous sample: cache status, pool state or SLOG latency
+async fn watch_status<E: CommandExecutor, F: FilesystemReader>(
+    out: &mut Output,
+    collectors: &mut [ZfsStatsCollector<E, F>],
+    pool_name: &str,
+    args: &CliArgs,
+    session: &mut Session,
+) -> Result<(), Box<dyn Error>> {
+    let watched = if args.overview { collectors.len() } else { 1 };
+    for collector in collectors[..watched].iter_mut() {
+        let pool = collector.pool().unwrap_or(pool_name).to_string();
+        let sample = collect_sample(collector, args).await?;
+        let (efficiency, status) = sample_status(&sample, &args.thresholds);
+        let watched = Watched {
+            status,
+            efficiency,
+            pool_state: collector.pool_state().map(str::to_string),
+            // A faulted SLOG takes no writes, so it has no latency to speak of
+            slog_latency: sample
+                .slog
+                .as_ref()
+                .filter(|slog| slog.fault.is_none())
+                .map(|slog| slog.latency),
+        };
+        for change in session.observe(&pool, watched) {
+            writeln!(out.writer, "{} {}: {}", alert_time(), pool, change)?;
+        }
+    }
+    session.samples += 1;
+    out.writer.flush()?;
     Ok(())
 }
 
-fn display_arc_section(
-    _terminal: &Terminal,
-    arc: &crate::zfs::ArcStats,
+/// `--report`: like `--once`, but a single plain-text summary of every section
+/// with a short interpretation, written as-is without terminal control codes
+async fn write_report<E: CommandExecutor, F: FilesystemReader>(
+    out: &mut Output,
+    terminal: &Terminal,
+    collector: &mut ZfsStatsCollector<E, F>,
+    pool_name: &str,
+    args: &CliArgs,
 ) -> Result<(), Box<dyn Error>> {
-    println!("📊 ARC (Primary RAM Cache)");
-    let progress_bar = ProgressBar::new(20);
-    let usage_percent = (arc.size as f64 / arc.target as f64) * 100.0;
+    let args = CliArgs {
+        format: OutputFormat::Text,
+        ..args.clone()
+    };
+    // Prime like the dashboard, so the rates cover the warm-up rather than read 0/s
+    if args.warmup_ms > 0 {
+        collect_sample(collector, &args).await?;
+        tokio::time::sleep(Duration::from_millis(args.warmup_ms)).await;
+    }
+    let sample = collect_and_dump(collector, &args).await?;
 
-    println!(
-        "    Hit Rate:    {}",
-        progress_bar.render(
-            arc.hit_rate,
-            Some(&format!(
-                "{} ({})",
-                arc.hit_rate,
-                CacheStatus::from_hit_rate(arc.hit_rate)
-            ))
-        )
-    );
-    println!(
-        "    Cache Size:  {}",
-        progress_bar.render(
-            usage_percent,
-            Some(&format_bytes_ratio(arc.size, arc.target))
-        )
-    );
-    println!("    Read Ops:    {}", format_ops_per_second(arc.read_ops));
-    println!();
+    let mut report = Vec::new();
+    render_report(&mut report, terminal, &sample, pool_name, &args)?;
+    out.writer.write_all(&report)?;
+    out.writer.flush()?;
     Ok(())
 }
 
-fn display_l2arc_section(
-    _terminal: &Terminal,
-    l2arc: &crate::zfs::L2ArcStats,
+fn render_report(
+    out: &mut impl Write,
+    terminal: &Terminal,
+    sample: &Sample,
+    pool_name: &str,
+    args: &CliArgs,
 ) -> Result<(), Box<dyn Error>> {
-    println!("💾 L2ARC (Secondary SSD Cache)");
-    let progress_bar = ProgressBar::new(20);
-
-    println!(
-        "    Hit Rate:    {}",
-        progress_bar.render(
-            l2arc.hit_rate,
-            Some(&format!(
-                "{} ({})",
-                l2arc.hit_rate,
-                CacheStatus::from_hit_rate(l2arc.hit_rate)
-            ))
-        )
-    );
-    println!("    Cache Size:  {}", format_bytes(l2arc.size));
-    println!("    Read Rate:   {}", format_rate(l2arc.read_bytes));
-    println!(
-        "    Operations:  {}",
-        format_ops_per_second(l2arc.total_ops)
-    );
-    println!();
+    writeln!(
+        out,
+        "ZFS cache report for {}, {}",
+        pool_name,
+        sample.timestamp.format("%Y-%m-%d %H:%M:%S UTC")
+    )?;
+    writeln!(out)?;
+    render_sample(out, terminal, sample, pool_name, None, None, args)?;
+    writeln!(out, "Summary: {}", interpret(sample, args))?;
     Ok(())
 }
 
-fn display_slog_section(
-    _terminal: &Terminal,
-    slog: &crate::zfs::SlogStats,
+/// A one-sentence reading of a sample for `--report`, e.g.
+/// "ARC hit rate excellent; L2ARC warming; SLOG healthy."
+fn interpret(sample: &Sample, args: &CliArgs) -> String {
+    let mut findings = Vec::new();
+
+    let arc = CacheStatus::from_hit_rate_with_thresholds(sample.arc.hit_rate, &args.thresholds);
+    findings.push(format!("ARC hit rate {}", arc.to_string().to_lowercase()));
+    if let Some(eviction) = &sample.arc.eviction {
+        let pressure = eviction.pressure();
+        if matches!(pressure, ArcPressure::Medium | ArcPressure::High) {
+            findings.push(format!("ARC under {} eviction pressure", pressure));
+        }
+    }
+
+    match &sample.l2arc {
+        Some(l2arc) => {
+            let errors = l2arc.cksum_bad + l2arc.io_errors;
+            if errors > 0 {
+                findings.push(format!("L2ARC reporting {} errors", errors));
+            }
+            if l2arc.warming {
+                findings.push("L2ARC warming".to_string());
+            } else {
+                let status =
+                    CacheStatus::from_hit_rate_with_thresholds(l2arc.hit_rate, &args.thresholds);
+                findings.push(format!(
+                    "L2ARC hit rate {}",
+                    status.to_string().to_lowercase()
+                ));
+            }
+        }
+        None if !args.no_l2arc => findings.push("no L2ARC".to_string()),
+        None => {}
+    }
+
+    match (&sample.slog, &sample.slog_unavailable) {
+        (Some(slog), _) if slog.fault.is_some() => findings.push("SLOG faulted".to_string()),
+        // Same rating as the dashboard's utilization bar
+        (Some(slog), _) => findings.push(
+            match CacheStatus::from_hit_rate_with_thresholds(
+                100.0 - slog.utilization,
+                &args.thresholds,
+            ) {
+                CacheStatus::Excellent | CacheStatus::Good => "SLOG healthy",
+                CacheStatus::Fair => "SLOG busy",
+                CacheStatus::Poor => "SLOG saturated",
+            }
+            .to_string(),
+        ),
+        (None, Some(_)) => findings.push("SLOG unavailable".to_string()),
+        (None, None) if !args.no_slog => findings.push("no SLOG".to_string()),
+        (None, None) => {}
+    }
+
+    format!("{}.", findings.join("; "))
+}
+
+/// Time prefixed to `--quiet` lines, in the format of the dashboard header
+fn alert_time() -> String {
+    Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Replace `path` with `frame` atomically: write a sibling temp file and rename
+/// it over the target, so readers see the previous frame or this one, never a mix
+fn write_snapshot_file(path: &Path, frame: &[u8]) -> io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".tmp");
+    std::fs::write(&temp, frame)?;
+    std::fs::rename(&temp, path)
+}
+
+/// Write a rendered frame, redrawing in place when the output is a terminal.
+/// There only the lines that differ from the `previous` frame are rewritten.
+fn present_frame(
+    out: &mut Output,
+    first_frame: bool,
+    terminal: &Terminal,
+    frame: &[u8],
+    previous: Option<&[u8]>,
+    args: &CliArgs,
+) -> io::Result<()> {
+    match args.format {
+        OutputFormat::Text | OutputFormat::Table if out.is_terminal => {
+            // Redraw in place rather than clearing to avoid flicker
+            if first_frame {
+                terminal.clear_screen(&mut out.writer)?;
+            } else {
+                terminal.move_home(&mut out.writer)?;
+            }
+            terminal.hide_cursor(&mut out.writer)?;
+            write_frame(&mut out.writer, terminal, frame, previous)?;
+        }
+        // Files and pipes get plain frames, one after another
+        _ => out.writer.write_all(frame)?,
+    }
+
+    out.writer.flush()
+}
+
+/// Collect one round of stats and render it in the requested format. A live
+/// `session` notes whether collecting took the watchdog's [`stall_limit`]
+async fn refresh<E: CommandExecutor, F: FilesystemReader>(
+    out: &mut impl Write,
+    terminal: &Terminal,
+    collector: &mut ZfsStatsCollector<E, F>,
+    pool_name: &str,
+    mut session: Option<&mut Session>,
+    rows: Option<usize>,
+    args: &CliArgs,
 ) -> Result<(), Box<dyn Error>> {
-    println!("🟡 SLOG (Synchronous Write Log)");
-    let progress_bar = ProgressBar::new(20);
+    let started = tokio::time::Instant::now();
+    let sample = collect_sample(collector, args).await?;
+    if let Some(session) = session.as_deref_mut() {
+        // The frame after a stall still says so, in case the banner went unseen
+        session.slow_refresh = Some(started.elapsed()).filter(|took| *took > stall_limit(args));
+    }
+    render_sample(out, terminal, &sample, pool_name, session, rows, args)
+}
 
-    println!("    Device:      {}", slog.device);
-    println!(
-        "    Utilization: {}",
-        progress_bar.render(
-            slog.utilization,
-            Some(&format!(
-                "{} ({})",
-                slog.utilization,
-                CacheStatus::from_hit_rate(100.0 - slog.utilization)
-            ))
-        )
-    );
-    println!("    Write Ops:   {}", format_ops_per_second(slog.write_ops));
-    println!("    Write Rate:  {}", format_rate(slog.write_bw));
-    println!("    Latency:     {}", format_latency_ms(slog.latency));
-    println!();
-    Ok(())
+/// Dashboard sections, most important first: when the terminal is too short
+/// for all of them, they are dropped from the end of this list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum SectionPriority {
+    Header,
+    Arc,
+    Slog,
+    L2arc,
+    PoolIo,
+    Dataset,
 }
 
-fn display_footer(_terminal: &Terminal) -> Result<(), Box<dyn Error>> {
-    println!("{:=^80}", "");
-    println!("Press Ctrl+C to exit | Data refreshes every 2s");
+/// Write rendered sections in the given order. If they take more than `rows`
+/// lines, keep the most important ones that fit (the header always stays) and
+/// end with a line telling how many lines were hidden.
+fn write_sections(
+    out: &mut impl Write,
+    sections: Vec<(SectionPriority, Vec<u8>)>,
+    rows: Option<usize>,
+) -> io::Result<()> {
+    let lines = |section: &[u8]| section.iter().filter(|&&byte| byte == b'\n').count();
+    let total: usize = sections.iter().map(|(_, section)| lines(section)).sum();
+
+    let mut shown = vec![true; sections.len()];
+    if let Some(rows) = rows.filter(|&rows| total > rows) {
+        // One row goes to the hidden lines indicator
+        let mut left = rows.saturating_sub(1);
+        let mut by_priority: Vec<usize> = (0..sections.len()).collect();
+        by_priority.sort_by_key(|&i| sections[i].0);
+        let mut full = false;
+        for i in by_priority {
+            let (priority, section) = &sections[i];
+            let needed = lines(section);
+            // Once a section doesn't fit, less important ones don't jump the queue
+            if *priority != SectionPriority::Header && (full || needed > left) {
+                full = true;
+                shown[i] = false;
+            } else {
+                left = left.saturating_sub(needed);
+            }
+        }
+    }
+
+    let mut hidden = 0;
+    for ((_, section), shown) in sections.iter().zip(shown) {
+        if shown {
+            out.write_all(section)?;
+        } else {
+            hidden += lines(section);
+        }
+    }
+    if hidden > 0 {
+        writeln!(out, "…({} lines hidden)", hidden)?;
+    }
     Ok(())
 }
 
+/// Render one sample as the dashboard or a JSON envelope. With the live
+/// `session`, the header notes the pool `--follow-new-pool` moved on from and
+/// `--min-change` can keep sections as previously rendered. With `rows` the
+/// dashboard is cut down to fit, see [`write_sections`].
+fn render_sample(
+    out: &mut impl Write,
+    terminal: &Terminal,
+    sample: &Sample,
+    pool_name: &str,
+    session: Option<&mut Session>,
+    rows: Option<usize>,
+    args: &CliArgs,
+) -> Result<(), Box<dyn Error>> {
+    match args.format {
+        OutputFormat::Text => {
+            let (efficiency, status) = sample_status(sample, &args.thresholds);
+            let switched_from = session
+                .as_ref()
+                .and_then(|session| session.switched_from.as_deref());
+            // Replays show the captured pool, not one picked by resolve_pool
+            let pool_label = if let Some(previous) = switched_from {
+                format!("{} (switched from {})", pool_name, previous)
+            } else if args.pool.is_none() && args.replay.is_none() {
+                format!("{} (auto-selected)", pool_name)
+            } else {
+                pool_name.to_string()
+            };
+            // Each section is rendered on its own so a short terminal can drop some
+            let mut header = Vec::new();
+            if let Some(took) = session.as_ref().and_then(|session| session.slow_refresh) {
+                let detail = format!("refresh took {:.1}s", took.as_secs_f64());
+                writeln!(header, "{}", stall_banner(terminal, &detail))?;
+            }
+            display_header(
+                &mut header,
+                terminal,
+                &pool_label,
+                args,
+                sample.elapsed,
+                sample.timestamp,
+                Some(CacheSummary {
+                    efficiency,
+                    status,
+                    effective_hit_rate: sample
+                        .l2arc
+                        .as_ref()
+                        .and_then(|l2arc| sample.arc.effective_hit_rate(l2arc)),
+                }),
+            )?;
+            let mut arc = Vec::new();
+            display_arc_section(
+                &mut arc,
+                terminal,
+                &bar_style(terminal, args),
+                &sample.arc,
+                &args.thresholds,
+                args.number_format(),
+                &args.theme.arc,
+            )?;
+            let mut l2arc = Vec::new();
+            if let Some(stats) = &sample.l2arc {
+                display_l2arc_section(
+                    &mut l2arc,
+                    terminal,
+                    &bar_style(terminal, args),
+                    stats,
+                    &args.thresholds,
+                    args.number_format(),
+                    &args.theme.l2arc,
+                )?;
+            }
+            let mut slog = Vec::new();
+            if let Some(stats) = &sample.slog {
+                display_slog_section(
+                    &mut slog,
+                    terminal,
+                    &bar_style(terminal, args),
+                    stats,
+                    &args.thresholds,
+                    args.number_format(),
+                    &args.theme.slog,
+                )?;
+            } else if let Some(reason) = &sample.slog_unavailable {
+                writeln!(slog, "{}", args.theme.slog.render(terminal))?;
+                writeln!(slog, "    Unavailable: {}", reason)?;
+                writeln!(slog)?;
+            }
+            let mut pool_io = Vec::new();
+            if let Some(stats) = &sample.pool_io {
+                display_pool_io_section(&mut pool_io, terminal, stats, args.number_format())?;
+            } else if let Some(reason) = &sample.pool_io_unavailable {
+                writeln!(pool_io, "💿 Pool I/O ({})", pool_name)?;
+                writeln!(pool_io, "    Unavailable: {}", reason)?;
+                writeln!(pool_io)?;
+            }
+            let mut dataset = Vec::new();
+            if let Some(stats) = &sample.dataset {
+                display_dataset_section(&mut dataset, terminal, stats, args.number_format())?;
+            } else if let (Some(name), Some(reason)) = (&args.dataset, &sample.dataset_unavailable)
+            {
+                writeln!(dataset, "📂 Dataset I/O ({})", name)?;
+                writeln!(dataset, "    Unavailable: {}", reason)?;
+                writeln!(dataset)?;
+            }
+            let mut sections = vec![
+                (SectionPriority::Header, header),
+                (SectionPriority::Arc, arc),
+                (SectionPriority::L2arc, l2arc),
+                (SectionPriority::Slog, slog),
+                (SectionPriority::PoolIo, pool_io),
+                (SectionPriority::Dataset, dataset),
+            ];
+            if let (Some(percent), Some(session)) = (args.min_change, session) {
+                for (priority, section) in sections.iter_mut() {
+                    // The header's clock moves on every refresh anyway
+                    if *priority != SectionPriority::Header {
+                        let metrics = section_metrics(sample, *priority);
+                        session.settle_section(*priority, metrics, section, percent);
+                    }
+                }
+            }
+            write_sections(out, sections, rows)?;
+        }
+        OutputFormat::Json => writeln!(out, "{}", sample.envelope(pool_name))?,
+        OutputFormat::Table => {
+            let row = sample.overview_row(pool_name);
+            write!(out, "{}", overview_table(sample.arc.hit_rate, &[row]))?
+        }
+    }
+    Ok(())
+}
 
+/// Collect one round of stats for every pool and render the overview table
+/// (plain with `--format table`), or one JSON envelope per pool
+async fn refresh_overview<E: CommandExecutor, F: FilesystemReader>(
+    out: &mut impl Write,
+    terminal: &Terminal,
+    collectors: &mut [ZfsStatsCollector<E, F>],
+    args: &CliArgs,
+) -> Result<(), Box<dyn Error>> {
+    let mut rows = Vec::new();
+    let mut elapsed = None;
+    // The ARC is system-wide, so every pool's sample has the same one
+    let mut arc = None;
+    for collector in collectors.iter_mut() {
+        let pool = collector.pool().unwrap_or_default().to_string();
+        let sample = collect_sample(collector, args).await?;
+        elapsed = elapsed.or(sample.elapsed);
+
+        if args.format == OutputFormat::Json {
+            writeln!(out, "{}", sample.envelope(&pool))?;
+            continue;
+        }
+        rows.push(sample.overview_row(&pool));
+        arc.get_or_insert(sample.arc);
+    }
+    let Some(arc) = arc else {
+        return Ok(());
+    };
+
+    if args.format == OutputFormat::Table {
+        write!(out, "{}", overview_table(arc.hit_rate, &rows))?;
+    }
+
+    if args.format == OutputFormat::Text {
+        let pools: Vec<&str> = rows.iter().map(|row| row.pool.as_str()).collect();
+        display_header(
+            out,
+            terminal,
+            &pools.join(", "),
+            args,
+            elapsed,
+            Utc::now(),
+            None,
+        )?;
+        // Shown once for all pools, then what each pool has of its own
+        display_arc_section(
+            out,
+            terminal,
+            &bar_style(terminal, args),
+            &arc,
+            &args.thresholds,
+            args.number_format(),
+            &args.theme.arc,
+        )?;
+        render_overview(out, terminal, &rows, &args.thresholds)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Render captured `--format json` output (one envelope per line) through the
+/// normal display path, one frame per interval or as paced by `--replay-speed`,
+/// e.g. to reproduce a user's report
+async fn replay(
+    out: &mut Output,
+    terminal: &Terminal,
+    path: &Path,
+    args: &CliArgs,
+) -> Result<(), Box<dyn Error>> {
+    let snapshots = load_snapshots(&tokio::fs::read_to_string(path).await?)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let mut keys = tokio::io::BufReader::new(tokio::io::stdin());
+    let mut session = Session::new();
+    let mut previous: Option<CollectedAt> = None;
+    for (i, snapshot) in snapshots.into_iter().enumerate() {
+        let collected_at = snapshot.arc.collected_at;
+        if let Some(previous) = previous {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => break,
+                advance = replay_pause(&mut keys, previous, collected_at, args) => {
+                    if !advance? {
+                        break;
+                    }
+                }
+            }
+        }
+        previous = Some(collected_at);
+
+        let pool = snapshot.pool.clone();
+        let sample = Sample::from(snapshot);
+        let mut frame = Vec::new();
+        let rows = dashboard_rows(out, terminal, args);
+        render_sample(
+            &mut frame,
+            terminal,
+            &sample,
+            &pool,
+            Some(&mut session),
+            rows,
+            args,
+        )?;
+        session.samples += 1;
+        if args.format == OutputFormat::Text {
+            display_footer(&mut frame, terminal, &session)?;
+        }
+        present_frame(out, i == 0, terminal, &frame, None, args)?;
+    }
+
+    restore_cursor(out, terminal, args)?;
+    Ok(())
+}
+
+/// Wait between the replayed frames collected at `previous` and `next`: the
+/// recorded gap divided by `--replay-speed`, a line from `keys` at speed 0, else
+/// the interval. Returns false once `keys` runs out, ending a stepped replay
+async fn replay_pause(
+    keys: &mut (impl AsyncBufRead + Unpin),
+    previous: CollectedAt,
+    next: CollectedAt,
+    args: &CliArgs,
+) -> io::Result<bool> {
+    match args.replay_speed {
+        Some(0.0) => Ok(keys.read_line(&mut String::new()).await? > 0),
+        Some(speed) => {
+            tokio::time::sleep(replay_delay(previous, next, speed)).await;
+            Ok(true)
+        }
+        None => {
+            tokio::time::sleep(Duration::from_secs(args.interval as u64)).await;
+            Ok(true)
+        }
+    }
+}
+
+/// The recorded gap between two frames at `speed` times real speed; none when
+/// the clock stepped back between them
+fn replay_delay(previous: CollectedAt, next: CollectedAt, speed: f64) -> Duration {
+    next.0
+        .duration_since(previous.0)
+        .unwrap_or_default()
+        .div_f64(speed)
+}
+
+/// Parse one envelope per non-empty line, skipping error envelopes
+fn load_snapshots(content: &str) -> Result<Vec<Snapshot>, String> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(i, line)| {
+            Snapshot::from_json(line)
+                .map_err(|e| format!("line {}: {}", i + 1, e))
+                .transpose()
+        })
+        .collect()
+}
+
+/// One round of collected stats
+struct Sample {
+    taken_at: Instant,
+    /// Real time since the previous sample, None for the first
+    elapsed: Option<Duration>,
+    timestamp: DateTime<Utc>,
+    arc: ArcStats,
+    l2arc: Option<L2ArcStats>,
+    slog: Option<SlogStats>,
+    /// Why SLOG stats couldn't be collected, e.g. missing privileges
+    slog_unavailable: Option<String>,
+    pool_io: Option<PoolIoStats>,
+    /// Why pool I/O couldn't be collected, e.g. `zpool iostat` failing
+    pool_io_unavailable: Option<String>,
+    /// Only collected for `--dataset`
+    dataset: Option<DatasetStats>,
+    /// Why the `--dataset` stats couldn't be collected, e.g. no such dataset
+    dataset_unavailable: Option<String>,
+    /// Uptime the rates are averaged over with `--since-boot-rates`
+    uptime: Option<Duration>,
+}
+
+impl From<Snapshot> for Sample {
+    fn from(snapshot: Snapshot) -> Self {
+        Self {
+            taken_at: Instant::now(),
+            elapsed: None,
+            timestamp: snapshot.timestamp,
+            arc: snapshot.arc,
+            l2arc: snapshot.l2arc,
+            slog: snapshot.slog,
+            slog_unavailable: None,
+            pool_io: snapshot.pool_io,
+            pool_io_unavailable: None,
+            dataset: None,
+            dataset_unavailable: None,
+            uptime: None,
+        }
+    }
+}
+
+impl Sample {
+    fn overview_row(&self, pool: &str) -> OverviewRow {
+        OverviewRow {
+            pool: pool.to_string(),
+            l2arc_hit_rate: self.l2arc.as_ref().map(|l2arc| l2arc.hit_rate),
+            slog_write_bw: self.slog.as_ref().map(|slog| slog.write_bw),
+            slog_latency: self.slog.as_ref().map(|slog| slog.latency),
+            capacity: self
+                .pool_io
+                .as_ref()
+                .and_then(|pool_io| pool_io.capacity_percent()),
+        }
+    }
+
+    fn envelope(&self, pool: &str) -> JsonValue {
+        metrics_envelope(
+            pool,
+            self.timestamp,
+            &self.arc,
+            self.l2arc.as_ref(),
+            self.slog.as_ref(),
+            self.pool_io.as_ref(),
+        )
+    }
+}
+
+/// The overall cache efficiency score of a sample and the status it rates as
+fn sample_status(sample: &Sample, thresholds: &CacheThresholds) -> (f64, CacheStatus) {
+    let efficiency = score::cache_efficiency(
+        sample.arc.hit_rate,
+        sample.l2arc.as_ref().map(|l2arc| l2arc.hit_rate),
+        sample.slog.as_ref().map(|slog| slog.latency),
+    );
+    (
+        efficiency,
+        CacheStatus::from_hit_rate_with_thresholds(efficiency, thresholds),
+    )
+}
+
+async fn collect_sample<E: CommandExecutor, F: FilesystemReader>(
+    collector: &mut ZfsStatsCollector<E, F>,
+    args: &CliArgs,
+) -> Result<Sample, Box<dyn Error>> {
+    let taken_at = collector.sample_time();
+    let elapsed = collector.mark_sample(taken_at);
+    let timestamp = Utc::now();
+
+    // Collect stats, skipping disabled sections entirely
+    let arc = collector.collect_arc_stats().await?;
+    let l2arc = if args.no_l2arc {
+        None
+    } else {
+        collector.collect_l2arc_stats().await?
+    };
+    // A SLOG we can't see (e.g. `zpool` needs root) shouldn't stop the monitor
+    let (slog, slog_unavailable) = if args.no_slog {
+        (None, None)
+    } else {
+        match collector.collect_slog_stats().await {
+            Ok(slog) => (slog, None),
+            Err(ZfsError::SubsystemUnavailable { reason, .. }) => (None, Some(reason)),
+            Err(e) => return Err(e.into()),
+        }
+    };
+    // Like the SLOG, pool I/O is extra: a failing `zpool iostat` is noted in its section
+    let (pool_io, pool_io_unavailable) = match collector.collect_pool_io_stats().await {
+        Ok(pool_io) => (pool_io, None),
+        Err(ZfsError::SubsystemUnavailable { reason, .. }) => (None, Some(reason)),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    // A mistyped or destroyed dataset is reported in its section, not fatal
+    let (dataset, dataset_unavailable) = match &args.dataset {
+        Some(dataset) => match collector.collect_dataset_stats(dataset).await {
+            Ok(stats) => (Some(stats), None),
+            Err(e) => (None, Some(e.to_string())),
+        },
+        None => (None, None),
+    };
+
+    Ok(Sample {
+        taken_at,
+        elapsed,
+        timestamp,
+        arc,
+        l2arc,
+        slog,
+        slog_unavailable,
+        pool_io,
+        pool_io_unavailable,
+        dataset,
+        dataset_unavailable,
+        uptime: collector.rate_uptime(),
+    })
+}
+
+/// `--dump-raw`: write the unparsed inputs of the sample just taken to the
+/// directory, one file per source named after the time, e.g.
+/// `20261015-142657.123-arcstats.txt`. Priming samples aren't dumped.
+async fn dump_raw_inputs<E: CommandExecutor, F: FilesystemReader>(
+    collector: &mut ZfsStatsCollector<E, F>,
+    args: &CliArgs,
+) -> Result<(), Box<dyn Error>> {
+    let Some(dir) = &args.dump_raw else {
+        return Ok(());
+    };
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let stamp = Utc::now().format("%Y%m%d-%H%M%S%.3f");
+    for (source, content) in collector.raw_inputs().await {
+        let path = dir.join(format!("{}-{}.txt", stamp, source));
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Collect a sample for the one-shot modes and `--dump-raw` its inputs whether
+/// or not they parsed. A failing dump is only a warning.
+async fn collect_and_dump<E: CommandExecutor, F: FilesystemReader>(
+    collector: &mut ZfsStatsCollector<E, F>,
+    args: &CliArgs,
+) -> Result<Sample, Box<dyn Error>> {
+    let sample = collect_sample(collector, args).await;
+    if let Err(e) = dump_raw_inputs(collector, args).await {
+        eprintln!("Warning: {}", e);
+    }
+    sample
+}
+
+/// `--once --format json`: take two samples `interval` apart and write both
+/// together with the rates between them, so one invocation yields rates
+async fn write_batch<E: CommandExecutor, F: FilesystemReader>(
+    out: &mut Output,
+    collector: &mut ZfsStatsCollector<E, F>,
+    pool_name: &str,
+    args: &CliArgs,
+) -> Result<(), Box<dyn Error>> {
+    let first = collect_sample(collector, args).await?;
+    tokio::time::sleep(Duration::from_secs(args.interval as u64)).await;
+    let second = collect_and_dump(collector, args).await?;
+
+    let batch = batch_envelope(
+        pool_name,
+        second.taken_at.duration_since(first.taken_at),
+        vec![first.envelope(pool_name), second.envelope(pool_name)],
+        sample_rates(&first, &second),
+    );
+    writeln!(out.writer, "{}", batch)?;
+    out.writer.flush()?;
+    Ok(())
+}
+
+/// Cumulative counters that a pair of samples turns into per-second rates
+type Counter = fn(&Sample) -> Option<u64>;
+
+const SAMPLE_COUNTERS: [(&str, Counter); 4] = [
+    ("arc_hits", |s| Some(s.arc.hits_total)),
+    ("arc_misses", |s| Some(s.arc.misses_total)),
+    ("l2arc_read_bytes", |s| {
+        s.l2arc.as_ref().map(|l2| l2.read_bytes_total)
+    }),
+    ("l2arc_write_bytes", |s| {
+        s.l2arc.as_ref().map(|l2| l2.write_bytes_total)
+    }),
+];
+
+/// Per-second rates between two samples, or of the second over its uptime with
+/// `--since-boot-rates`; counters missing from either are left out
+fn sample_rates(first: &Sample, second: &Sample) -> Vec<(String, f64)> {
+    // The collector already turned read_ops into a rate the same way
+    let mut rates = vec![("arc_read_ops".to_string(), second.arc.read_ops as f64)];
+
+    if let Some(uptime) = second.uptime {
+        for (key, counter) in SAMPLE_COUNTERS {
+            if let Some(total) = counter(second) {
+                rates.push((key.to_string(), total as f64 / uptime.as_secs_f64()));
+            }
+        }
+        return rates;
+    }
+
+    let mut calculator = RateCalculator::new();
+    for (key, counter) in SAMPLE_COUNTERS {
+        if let (Some(before), Some(after)) = (counter(first), counter(second)) {
+            calculator.update(key, before, first.taken_at);
+            if let Some(rate) = calculator.calculate_rate(key, after, second.taken_at) {
+                rates.push((key.to_string(), rate));
+            }
+        }
+    }
+    rates
+}
+
+/// Write a rendered frame over the previous one, clearing leftovers of
+/// longer lines and of rows the new frame no longer has. Lines identical to
+/// those of the `previous` frame still on screen are skipped over.
+fn write_frame(
+    out: &mut impl Write,
+    terminal: &Terminal,
+    frame: &[u8],
+    previous: Option<&[u8]>,
+) -> io::Result<()> {
+    let previous = String::from_utf8_lossy(previous.unwrap_or_default());
+    let mut previous = previous.lines();
+    for line in String::from_utf8_lossy(frame).lines() {
+        if previous.next() == Some(line) {
+            terminal.next_line(out)?;
+            continue;
+        }
+        write!(out, "{}", line)?;
+        terminal.clear_to_eol(out)?;
+        writeln!(out)?;
+    }
+    terminal.clear_to_end_of_screen(out)
+}
+
+/// Whole-cache figures of a sample, shown in the header under the pool line
+struct CacheSummary {
+    efficiency: f64,
+    status: CacheStatus,
+    effective_hit_rate: Option<f64>, // ARC and L2ARC hits together, with a cache device
+}
+
+fn display_header(
+    out: &mut impl Write,
+    _terminal: &Terminal,
+    pool: &str,
+    args: &CliArgs,
+    elapsed: Option<Duration>,
+    time: DateTime<Utc>,
+    summary: Option<CacheSummary>,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(out, "{:=^80}", " 🔍 ZFS Cache Performance Monitor ")?;
+    let interval = args.interval;
+    let mut refresh = format!("{}s", interval);
+    // Under load refreshes drift; show the real interval the rates cover once
+    // it is visibly (at the printed precision) different from the configured one
+    if let Some(elapsed) = elapsed {
+        let actual = elapsed.as_secs_f64();
+        if (actual - interval as f64).abs() >= 0.1 {
+            refresh.push_str(&format!(" (actual {:.1}s)", actual));
+        }
+    }
+    writeln!(
+        out,
+        "Pool: {} | Refresh: {} | Time: {} | Rates: {}",
+        pool,
+        refresh,
+        time.format("%Y-%m-%d %H:%M:%S"),
+        if args.since_boot_rates {
+            "since boot"
+        } else {
+            "instantaneous"
+        }
+    )?;
+    if let Some(summary) = summary {
+        writeln!(
+            out,
+            "Cache Efficiency: {:.0}/100 ({})",
+            summary.efficiency, summary.status
+        )?;
+        if let Some(rate) = summary.effective_hit_rate {
+            writeln!(out, "Effective Hit Rate: {:.1}% (ARC + L2ARC)", rate)?;
+        }
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// The bars of the dashboard sections, colored as the terminal and
+/// `--gradient-bars` allow
+fn bar_style(terminal: &Terminal, args: &CliArgs) -> ProgressBar {
+    ProgressBar::new(20)
+        .with_color(terminal.supports_color)
+        .with_gradient(args.gradient_bars)
+}
+
+fn display_arc_section(
+    out: &mut impl Write,
+    terminal: &Terminal,
+    progress_bar: &ProgressBar,
+    arc: &crate::zfs::ArcStats,
+    thresholds: &CacheThresholds,
+    numbers: NumberFormat,
+    style: &SectionStyle,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(out, "{}", style.render(terminal))?;
+    let usage_percent = (arc.size as f64 / arc.target as f64) * 100.0;
+    let mut size_label = format_bytes_ratio(arc.size, arc.target, numbers.units);
+    if let Some(percent) = arc.percent_of_memory() {
+        size_label.push_str(&format!(" ({:.0}% of RAM)", percent));
+    }
+
+    let status = CacheStatus::from_hit_rate_with_thresholds(arc.hit_rate, thresholds);
+    writeln!(
+        out,
+        "    Hit Rate:    {}",
+        progress_bar.render_status(
+            arc.hit_rate,
+            Some(&format!(
+                "{} ({} {})",
+                arc.hit_rate,
+                status.symbol(),
+                status
+            )),
+            status
+        )
+    )?;
+    writeln!(
+        out,
+        "    Cache Size:  {}",
+        progress_bar.render(usage_percent, Some(&size_label))
+    )?;
+    // A growing target means ZFS has memory to spare, a shrinking one pressure
+    let trend = arc
+        .target_trend
+        .map(|trend| format!(" {}", trend.arrow()))
+        .unwrap_or_default();
+    writeln!(
+        out,
+        "    Target:      {}{} / Max: {}",
+        format_bytes(arc.target, numbers.units),
+        trend,
+        format_bytes(arc.max, numbers.units)
+    )?;
+    // Explains an ARC that stays below `c_max` although RAM looks free
+    if arc.no_grow == Some(true) {
+        let mut warning =
+            "    ⚠ No grow:   held back by memory pressure, won't use more RAM".to_string();
+        if let Some(prunes) = arc.prunes.filter(|&prunes| prunes > 0) {
+            warning.push_str(&format!(
+                " ({} prunes)",
+                format_ops_per_second(prunes, numbers.raw)
+            ));
+        }
+        if terminal.supports_color {
+            writeln!(
+                out,
+                "{}",
+                console::Style::new()
+                    .yellow()
+                    .force_styling(true)
+                    .apply_to(warning)
+            )?;
+        } else {
+            writeln!(out, "{}", warning)?;
+        }
+    }
+    if let Some(metadata) = &arc.metadata {
+        let percent_of_arc = metadata.percent_of_arc(arc.size);
+        // Against the limit where there is one, as that is what starves metadata
+        let (percent, label) = match (metadata.limit, metadata.percent_of_limit()) {
+            (Some(limit), Some(percent)) => (
+                percent,
+                format!(
+                    "{} ({:.1}% of ARC)",
+                    format_bytes_ratio(metadata.size, limit, numbers.units),
+                    percent_of_arc
+                ),
+            ),
+            _ => (
+                percent_of_arc,
+                format!("{} of ARC", format_bytes(metadata.size, numbers.units)),
+            ),
+        };
+        writeln!(
+            out,
+            "    Metadata:    {}",
+            progress_bar.render(percent, Some(&label))
+        )?;
+    }
+    writeln!(
+        out,
+        "    Read Ops:    {}",
+        format_ops_per_second(arc.read_ops, numbers.raw)
+    )?;
+    writeln!(
+        out,
+        "    Total:       {} hits, {} misses",
+        arc.hits_total, arc.misses_total
+    )?;
+    if let Some(breakdown) = &arc.hit_breakdown {
+        let (demand_data, demand_metadata, prefetch) = breakdown.percentages();
+        writeln!(
+            out,
+            "    Breakdown:   {:.1}% demand data, {:.1}% demand metadata, {:.1}% prefetch",
+            demand_data, demand_metadata, prefetch
+        )?;
+    }
+    if let Some(eviction) = &arc.eviction {
+        match eviction.pressure() {
+            ArcPressure::None => writeln!(out, "    Pressure:    none")?,
+            pressure => writeln!(
+                out,
+                "    Pressure:    {} ({} evict skips, {} short evictions, {} mutex misses)",
+                pressure,
+                format_ops_per_second(eviction.evict_skip, numbers.raw),
+                format_ops_per_second(eviction.evict_not_enough, numbers.raw),
+                format_ops_per_second(eviction.mutex_miss, numbers.raw)
+            )?,
+        }
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+fn display_l2arc_section(
+    out: &mut impl Write,
+    terminal: &Terminal,
+    progress_bar: &ProgressBar,
+    l2arc: &crate::zfs::L2ArcStats,
+    thresholds: &CacheThresholds,
+    numbers: NumberFormat,
+    style: &SectionStyle,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(out, "{}", style.render(terminal))?;
+
+    let hit_rate = if l2arc.warming {
+        // Too early for a low hit rate to mean anything
+        progress_bar.render(
+            l2arc.hit_rate,
+            Some(&format!("{} (Warming up)", l2arc.hit_rate)),
+        )
+    } else {
+        let status = CacheStatus::from_hit_rate_with_thresholds(l2arc.hit_rate, thresholds);
+        progress_bar.render_status(
+            l2arc.hit_rate,
+            Some(&format!(
+                "{} ({} {})",
+                l2arc.hit_rate,
+                status.symbol(),
+                status
+            )),
+            status,
+        )
+    };
+    writeln!(out, "    Hit Rate:    {}", hit_rate)?;
+    writeln!(
+        out,
+        "    Cache Size:  {}",
+        format_bytes(l2arc.size, numbers.units)
+    )?;
+    // Bars are relative to the busiest moment of this session
+    writeln!(
+        out,
+        "    Read Rate:   {}",
+        progress_bar.render(
+            percent_of_peak(l2arc.read_bytes, l2arc.read_bytes_peak),
+            Some(&format_rate(l2arc.read_bytes, numbers.units, numbers.bits))
+        )
+    )?;
+    writeln!(
+        out,
+        "    Fill Rate:   {}",
+        format_rate(l2arc.write_bytes, numbers.units, numbers.bits)
+    )?;
+    writeln!(
+        out,
+        "    Operations:  {}",
+        format_ops_per_second(l2arc.total_ops, numbers.raw)
+    )?;
+    writeln!(
+        out,
+        "    Total:       {} read, {} written",
+        format_bytes(l2arc.read_bytes_total, numbers.units),
+        format_bytes(l2arc.write_bytes_total, numbers.units)
+    )?;
+    if l2arc.has_errors() {
+        let warning = format!(
+            "    ⚠ Errors:    {} checksum, {} I/O (cache device may be failing)",
+            l2arc.cksum_bad, l2arc.io_errors
+        );
+        if terminal.supports_color {
+            writeln!(
+                out,
+                "{}",
+                console::Style::new()
+                    .red()
+                    .force_styling(true)
+                    .apply_to(warning)
+            )?;
+        } else {
+            writeln!(out, "{}", warning)?;
+        }
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+fn display_slog_section(
+    out: &mut impl Write,
+    terminal: &Terminal,
+    progress_bar: &ProgressBar,
+    slog: &crate::zfs::SlogStats,
+    thresholds: &CacheThresholds,
+    numbers: NumberFormat,
+    style: &SectionStyle,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(out, "{}", style.render(terminal))?;
+
+    match slog.cache_age {
+        Some(age) => writeln!(
+            out,
+            "    Device:      {} (cached {} ago)",
+            slog.display_name,
+            format_duration(age)
+        )?,
+        None => writeln!(out, "    Device:      {}", slog.display_name)?,
+    }
+    if let Some(state) = &slog.fault {
+        // Its numbers would only read as an idle log device
+        let alert = format!(
+            "    ⚠ SLOG FAULTED: {} in `zpool status`, sync writes fall back to the pool",
+            state
+        );
+        if terminal.supports_color {
+            writeln!(
+                out,
+                "{}",
+                console::Style::new()
+                    .red()
+                    .force_styling(true)
+                    .apply_to(alert)
+            )?;
+        } else {
+            writeln!(out, "{}", alert)?;
+        }
+        writeln!(out)?;
+        return Ok(());
+    }
+    writeln!(
+        out,
+        "    Utilization: {}",
+        progress_bar.render(
+            slog.utilization,
+            Some(&format!(
+                "{} ({})",
+                slog.utilization,
+                CacheStatus::from_hit_rate_with_thresholds(100.0 - slog.utilization, thresholds)
+            ))
+        )
+    )?;
+    writeln!(
+        out,
+        "    Write Ops:   {}",
+        format_ops_per_second(slog.write_ops, numbers.raw)
+    )?;
+    writeln!(
+        out,
+        "    Write Rate:  {}",
+        progress_bar.render(
+            percent_of_peak(slog.write_bw, slog.write_bw_peak),
+            Some(&format_rate(slog.write_bw, numbers.units, numbers.bits))
+        )
+    )?;
+    writeln!(
+        out,
+        "    Average:     {} since import ({})",
+        format_rate(slog.write_bw_avg, numbers.units, numbers.bits),
+        format_ops_per_second(slog.write_ops_avg, numbers.raw)
+    )?;
+    match &slog.latency_percentiles {
+        Some(percentiles) => writeln!(
+            out,
+            "    Latency:     p50 {} p95 {} p99 {}",
+            format_latency_ms(percentiles.p50),
+            format_latency_ms(percentiles.p95),
+            format_latency_ms(percentiles.p99)
+        )?,
+        None => writeln!(out, "    Latency:     {}", format_latency_ms(slog.latency))?,
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+fn display_pool_io_section(
+    out: &mut impl Write,
+    _terminal: &Terminal,
+    pool_io: &crate::zfs::PoolIoStats,
+    numbers: NumberFormat,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(out, "💿 Pool I/O ({})", pool_io.pool)?;
+    write_io_lines(
+        out,
+        [
+            ("Read:", pool_io.read_ops, pool_io.read_bw),
+            ("Write:", pool_io.write_ops, pool_io.write_bw),
+        ],
+        numbers,
+    )?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Write a line of operations and bandwidth per `(label, ops, bytes per second)`,
+/// e.g. "    Read:        47/s (234.0M/s)", building them all in one buffer
+fn write_io_lines(
+    out: &mut impl Write,
+    lines: [(&str, u64, u64); 2],
+    numbers: NumberFormat,
+) -> io::Result<()> {
+    let mut figures = String::new();
+    for (label, ops, bandwidth) in lines {
+        figures.clear();
+        format_ops_per_second_into(&mut figures, ops, numbers.raw);
+        figures.push_str(" (");
+        format_rate_into(&mut figures, bandwidth, numbers.units, numbers.bits);
+        figures.push(')');
+        writeln!(out, "    {:<13}{}", label, figures)?;
+    }
+    Ok(())
+}
+
+fn display_dataset_section(
+    out: &mut impl Write,
+    _terminal: &Terminal,
+    dataset: &DatasetStats,
+    numbers: NumberFormat,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(out, "📂 Dataset I/O ({})", dataset.name)?;
+    write_io_lines(
+        out,
+        [
+            ("Read:", dataset.reads, dataset.read_bytes),
+            ("Write:", dataset.writes, dataset.write_bytes),
+        ],
+        numbers,
+    )?;
+    let mut totals = String::new();
+    format_bytes_into(&mut totals, dataset.read_bytes_total, numbers.units);
+    totals.push_str(" read, ");
+    format_bytes_into(&mut totals, dataset.write_bytes_total, numbers.units);
+    writeln!(out, "    Total:       {} written", totals)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// How long the monitor has been running and how many samples it has shown
+struct Session {
+    started: tokio::time::Instant,
+    samples: u64,
+    /// What each pool looked like at its previous sample, for `--quiet`
+    watched: HashMap<String, Watched>,
+    /// The pool `--follow-new-pool` last moved on from
+    switched_from: Option<String>,
+    /// Each section as last rendered and the metrics it showed, for `--min-change`
+    rendered: HashMap<SectionPriority, (JsonValue, Vec<u8>)>,
+    /// The frame presented last, whose unchanged lines `--min-change` skips
+    shown: Vec<u8>,
+    /// How long the latest refresh collected for, if the watchdog found it stalled
+    slow_refresh: Option<Duration>,
+    /// Why `--dump-raw` couldn't write the latest refresh's inputs
+    dump_error: Option<String>,
+}
+
+impl Session {
+    fn new() -> Self {
+        Self {
+            started: tokio::time::Instant::now(),
+            samples: 0,
+            watched: HashMap::new(),
+            switched_from: None,
+            rendered: HashMap::new(),
+            shown: Vec::new(),
+            slow_refresh: None,
+            dump_error: None,
+        }
+    }
+
+    /// `--min-change`: replace a freshly rendered `section` with the one shown
+    /// before, unless one of its `metrics` moved by more than `percent` since then
+    /// or something else about it changed, so values that barely move don't
+    /// redraw every tick
+    fn settle_section(
+        &mut self,
+        priority: SectionPriority,
+        metrics: JsonValue,
+        section: &mut Vec<u8>,
+        percent: f64,
+    ) {
+        if let Some((before, previous)) = self.rendered.get(&priority) {
+            if !metrics_moved(before, &metrics, percent) {
+                section.clone_from(previous);
+                return;
+            }
+        }
+        self.rendered.insert(priority, (metrics, section.clone()));
+    }
+
+    /// Record `pool`'s latest sample and describe each way it changed, e.g.
+    /// "cache efficiency degraded from Good to Poor (41/100)"; nothing on its
+    /// first sample or if everything held
+    fn observe(&mut self, pool: &str, now: Watched) -> Vec<String> {
+        let mut changes = Vec::new();
+        let Some(previous) = self.watched.insert(pool.to_string(), now.clone()) else {
+            return changes;
+        };
+
+        let trend = if now.status > previous.status {
+            Some("degraded")
+        } else if now.status < previous.status {
+            Some("recovered")
+        } else {
+            None
+        };
+        if let Some(trend) = trend {
+            changes.push(format!(
+                "cache efficiency {} from {} to {} ({:.0}/100)",
+                trend, previous.status, now.status, now.efficiency
+            ));
+        }
+
+        // Unknown on either side (e.g. with --no-slog) says nothing about a change
+        if let (Some(before), Some(after)) = (&previous.pool_state, &now.pool_state) {
+            if before != after {
+                let trend = if after == "ONLINE" {
+                    "recovered"
+                } else {
+                    "degraded"
+                };
+                changes.push(format!("pool {} from {} to {}", trend, before, after));
+            }
+        }
+
+        if let (Some(before), Some(after)) = (previous.slog_latency, now.slog_latency) {
+            let slow = |latency: f64| latency >= score::SLOG_LATENCY_WORST_MS;
+            if slow(before) != slow(after) {
+                let trend = if slow(after) {
+                    "rose above"
+                } else {
+                    "back below"
+                };
+                changes.push(format!(
+                    "SLOG latency {} {}ms ({})",
+                    trend,
+                    score::SLOG_LATENCY_WORST_MS,
+                    format_latency_ms(after)
+                ));
+            }
+        }
+        changes
+    }
+}
+
+/// What `--quiet` compares between a pool's samples
+#[derive(Debug, Clone)]
+struct Watched {
+    status: CacheStatus,
+    /// Score behind `status`, shown with its changes
+    efficiency: f64,
+    /// From `zpool status`, e.g. "DEGRADED"; None when it wasn't read
+    pool_state: Option<String>,
+    /// Average SLOG latency in milliseconds; None without a working SLOG
+    slog_latency: Option<f64>,
+}
+
+/// The metrics behind a dashboard section, as in the JSON output
+fn section_metrics(sample: &Sample, priority: SectionPriority) -> JsonValue {
+    match priority {
+        SectionPriority::Header => JsonValue::Null,
+        SectionPriority::Arc => (&sample.arc).into(),
+        SectionPriority::L2arc => sample.l2arc.as_ref().into(),
+        SectionPriority::Slog => JsonValue::Array(vec![
+            sample.slog.as_ref().into(),
+            sample.slog_unavailable.as_deref().into(),
+        ]),
+        SectionPriority::PoolIo => JsonValue::Array(vec![
+            sample.pool_io.as_ref().into(),
+            sample.pool_io_unavailable.as_deref().into(),
+        ]),
+        SectionPriority::Dataset => JsonValue::Array(vec![
+            sample.dataset.as_ref().map(dataset_metrics).into(),
+            sample.dataset_unavailable.as_deref().into(),
+        ]),
+    }
+}
+
+/// The figures of the `--dataset` section
+fn dataset_metrics(stats: &DatasetStats) -> JsonValue {
+    JsonValue::Array(
+        [
+            stats.reads,
+            stats.writes,
+            stats.read_bytes,
+            stats.write_bytes,
+            stats.reads_total,
+            stats.writes_total,
+            stats.read_bytes_total,
+            stats.write_bytes_total,
+        ]
+        .into_iter()
+        .map(JsonValue::UInt)
+        .collect(),
+    )
+}
+
+/// Whether a number in `now` moved by more than `percent` from `before`, or
+/// anything that isn't a number (e.g. a state or a missing section) changed
+fn metrics_moved(before: &JsonValue, now: &JsonValue, percent: f64) -> bool {
+    let number = |value: &JsonValue| match *value {
+        JsonValue::UInt(value) => Some(value as f64),
+        JsonValue::Float(value) => Some(value),
+        _ => None,
+    };
+    match (before, now) {
+        (JsonValue::Array(before), JsonValue::Array(now)) => {
+            before.len() != now.len()
+                || before
+                    .iter()
+                    .zip(now)
+                    .any(|(before, now)| metrics_moved(before, now, percent))
+        }
+        (JsonValue::Object(before), JsonValue::Object(now)) => {
+            before.len() != now.len()
+                || before.iter().zip(now).any(|((key, before), (other, now))| {
+                    key != other || metrics_moved(before, now, percent)
+                })
+        }
+        _ => match (number(before), number(now)) {
+            (Some(before), Some(now)) => percent_change(before, now) > percent,
+            _ => before != now,
+        },
+    }
+}
+
+/// How far `now` moved from `before`, in percent of `before`
+fn percent_change(before: f64, now: f64) -> f64 {
+    if before == now {
+        0.0
+    } else if before == 0.0 {
+        f64::INFINITY
+    } else {
+        ((now - before) / before).abs() * 100.0
+    }
+}
+
+/// Lines written by [`display_footer`]
+const FOOTER_LINES: usize = 2;
+
+/// Rows the dashboard above the footer may take on the current terminal, asked
+/// on every refresh to follow resizes. None when not drawing to a terminal.
+fn dashboard_rows(out: &Output, terminal: &Terminal, args: &CliArgs) -> Option<usize> {
+    if !out.is_terminal || args.format != OutputFormat::Text {
+        return None;
+    }
+    // The newline after the last line would scroll the top row away
+    terminal
+        .height()
+        .map(|height| height.saturating_sub(FOOTER_LINES + 1))
+}
+
+fn display_footer(out: &mut impl Write, _terminal: &Terminal, session: &Session) -> io::Result<()> {
+    writeln!(out, "{:=^80}", "")?;
+    writeln!(
+        out,
+        "Uptime {} | {} sample{} | Ctrl+C to exit",
+        format_duration(session.started.elapsed()),
+        session.samples,
+        if session.samples == 1 { "" } else { "s" }
+    )?;
+    if let Some(error) = &session.dump_error {
+        writeln!(out, "Raw input dump failed: {}", error)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::{ColorMode, Theme};
+    use crate::system::commands::{
+        DEFAULT_COMMAND_TIMEOUT, NoPoolsCommandExecutor, RecordingCommandExecutor,
+        UnprivilegedCommandExecutor,
+    };
+    use crate::system::filesystem::RecordingFilesystemReader;
+    use crate::zfs::{
+        ArcEviction, ArcHitBreakdown, ArcMetadata, ArcStats, CollectedAt, L2ArcStats, PoolIoStats,
+        SlogStats,
+    };
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    fn render<F>(display: F) -> String
+    where
+        F: FnOnce(&mut Vec<u8>) -> Result<(), Box<dyn Error>>,
+    {
+        let mut out = Vec::new();
+        display(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_write_frame_clears_each_row() {
+        let terminal = Terminal::new();
+        let mut out = Vec::new();
+        write_frame(&mut out, &terminal, b"first\nsecond\n", None).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "first\x1B[K\nsecond\x1B[K\n\x1B[J"
+        );
+
+        // Lines still on screen from the previous frame are skipped over
+        let mut out = Vec::new();
+        let previous = b"first\nsecond\n".as_slice();
+        write_frame(&mut out, &terminal, b"first\nthird\n", Some(previous)).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1B[Ethird\x1B[K\n\x1B[J");
+    }
+
+    #[test]
+    fn test_display_pool_io_section() {
+        let terminal = Terminal::new();
+        let pool_io = PoolIoStats {
+            pool: "data".to_string(),
+            read_ops: 47,
+            write_ops: 23,
+            read_bw: 234 * 1024 * 1024,
+            write_bw: 12 * 1024 * 1024,
+            alloc: 0,
+            free: 0,
+        };
+
+        let output = render(|out| {
+            display_pool_io_section(out, &terminal, &pool_io, NumberFormat::default())
+        });
+        assert!(output.contains("Pool I/O (data)"));
+        assert!(output.contains("Read:        47/s (234.0M/s)"));
+        assert!(output.contains("Write:       23/s (12.0M/s)"));
+
+        let bits = NumberFormat {
+            bits: true,
+            ..NumberFormat::default()
+        };
+        let output = render(|out| display_pool_io_section(out, &terminal, &pool_io, bits));
+        assert!(output.contains("Read:        47/s (1.8Gbps)"));
+        assert!(output.contains("Write:       23/s (96.0Mbps)"));
+    }
+
+    #[test]
+    fn test_display_dataset_section() {
+        let terminal = Terminal::new();
+        let dataset = DatasetStats {
+            name: "data/home".to_string(),
+            reads: 120,
+            writes: 35,
+            read_bytes: 8 * 1024 * 1024,
+            write_bytes: 2 * 1024 * 1024,
+            reads_total: 5190347,
+            writes_total: 1832954,
+            read_bytes_total: 200 * 1024 * 1024 * 1024,
+            write_bytes_total: 57 * 1024 * 1024 * 1024,
+            collected_at: CollectedAt::default(),
+        };
+
+        let output = render(|out| {
+            display_dataset_section(out, &terminal, &dataset, NumberFormat::default())
+        });
+        assert!(output.contains("Dataset I/O (data/home)"));
+        assert!(output.contains("Read:        120/s (8.0M/s)"));
+        assert!(output.contains("Write:       35/s (2.0M/s)"));
+        assert!(output.contains("Total:       200.0G read, 57.0G written"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_dataset_is_shown_as_unavailable() {
+        let args = CliArgs {
+            dataset: Some("data/missing".to_string()),
+            ..CliArgs::default()
+        };
+        let mut collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data");
+
+        let sample = collect_sample(&mut collector, &args).await.unwrap();
+        assert!(sample.dataset.is_none());
+        let output =
+            render(|out| render_sample(out, &Terminal::new(), &sample, "data", None, None, &args));
+        assert!(output.contains("Dataset I/O (data/missing)\n    Unavailable: "));
+    }
+
+    #[test]
+    fn test_display_arc_section() {
+        let terminal = Terminal::new();
+        let mut arc = ArcStats {
+            hit_rate: 90.0,
+            size: 1024 * 1024 * 1024,
+            target: 2 * 1024 * 1024 * 1024,
+            target_trend: None,
+            max: 4 * 1024 * 1024 * 1024,
+            read_ops: 1247,
+            hits_total: 1_000_000,
+            misses_total: 2_500,
+            hit_breakdown: Some(ArcHitBreakdown {
+                demand_data: 750_000,
+                demand_metadata: 200_000,
+                prefetch: 50_000,
+            }),
+            eviction: Some(ArcEviction {
+                evict_skip: 350,
+                evict_not_enough: 12,
+                mutex_miss: 3,
+            }),
+            metadata: Some(ArcMetadata {
+                size: 256 * 1024 * 1024,
+                limit: Some(512 * 1024 * 1024),
+            }),
+            no_grow: None,
+            prunes: None,
+            memory_total: Some(4 * 1024 * 1024 * 1024),
+            collected_at: CollectedAt::default(),
+        };
+
+        let output = render(|out| {
+            display_arc_section(
+                out,
+                &terminal,
+                &bar_style(&terminal, &CliArgs::default()),
+                &arc,
+                &CacheThresholds::default(),
+                NumberFormat::default(),
+                &Theme::default().arc,
+            )
+        });
+
+        assert!(output.contains("ARC (Primary RAM Cache, system-wide)"));
+        assert!(output.contains("Hit Rate:    90 (✓ Excellent) [##################..] 90.0%"));
+        assert!(output.contains("1.0G/2.0G (25% of RAM) [##########..........] 50.0%"));
+        assert!(output.contains("Target:      2.0G / Max: 4.0G"));
+        assert!(
+            output
+                .contains("Metadata:    256.0M/512.0M (25.0% of ARC) [##########..........] 50.0%")
+        );
+        assert!(output.contains("Read Ops:    1.25K/s"));
+        assert!(output.contains("Total:       1000000 hits, 2500 misses"));
+        assert!(
+            output.contains("Breakdown:   75.0% demand data, 20.0% demand metadata, 5.0% prefetch")
+        );
+        assert!(output.contains(
+            "Pressure:    medium (350/s evict skips, 12/s short evictions, 3/s mutex misses)"
+        ));
+
+        arc.eviction = Some(ArcEviction {
+            evict_skip: 0,
+            evict_not_enough: 0,
+            mutex_miss: 0,
+        });
+        arc.metadata = Some(ArcMetadata {
+            size: 256 * 1024 * 1024,
+            limit: None,
+        });
+        let output = render(|out| {
+            display_arc_section(
+                out,
+                &terminal,
+                &bar_style(&terminal, &CliArgs::default()),
+                &arc,
+                &CacheThresholds::default(),
+                NumberFormat::default(),
+                &Theme::default().arc,
+            )
+        });
+        assert!(output.contains("Pressure:    none"));
+        assert!(output.contains("Metadata:    256.0M of ARC [#####...............] 25.0%"));
+        assert!(!output.contains("No grow"));
+
+        arc.no_grow = Some(true);
+        arc.prunes = Some(42);
+        let plain = Terminal::with_color_mode(ColorMode::Never);
+        let output = render(|out| {
+            display_arc_section(
+                out,
+                &plain,
+                &bar_style(&plain, &CliArgs::default()),
+                &arc,
+                &CacheThresholds::default(),
+                NumberFormat::default(),
+                &Theme::default().arc,
+            )
+        });
+        assert!(output.contains(
+            "    ⚠ No grow:   held back by memory pressure, won't use more RAM (42/s prunes)\n"
+        ));
+    }
+
+    #[test]
+    fn test_display_slog_section_fault() {
+        let terminal = Terminal::new();
+        let mut slog = report_sample().slog.unwrap();
+        let display = |slog: &SlogStats| {
+            render(|out| {
+                display_slog_section(
+                    out,
+                    &terminal,
+                    &bar_style(&terminal, &CliArgs::default()),
+                    slog,
+                    &CacheThresholds::default(),
+                    NumberFormat::default(),
+                    &Theme::default().slog,
+                )
+            })
+        };
+        assert!(!display(&slog).contains("FAULTED"));
+
+        slog.fault = Some("UNAVAIL".to_string());
+        let output = display(&slog);
+        assert!(output.contains(
+            "    ⚠ SLOG FAULTED: UNAVAIL in `zpool status`, sync writes fall back to the pool\n"
+        ));
+        assert!(!output.contains("Write Rate:"));
+    }
+
+    #[test]
+    fn test_display_l2arc_section_errors() {
+        let terminal = Terminal::new();
+        let mut l2arc = L2ArcStats {
+            hit_rate: 73.5,
+            size: 594_542_387_200,
+            read_bytes: 0,
+            write_bytes: 0,
+            read_bytes_peak: 0,
+            total_ops: 0,
+            read_bytes_total: 245_760_000,
+            write_bytes_total: 10 * 1024 * 1024,
+            cksum_bad: 0,
+            io_errors: 0,
+            hits_total: 0,
+            misses_total: 0,
+            warming: false,
+            collected_at: CollectedAt::default(),
+        };
+
+        let thresholds = CacheThresholds::default();
+        let output = render(|out| {
+            display_l2arc_section(
+                out,
+                &terminal,
+                &bar_style(&terminal, &CliArgs::default()),
+                &l2arc,
+                &thresholds,
+                NumberFormat::default(),
+                &Theme::default().l2arc,
+            )
+        });
+        assert!(output.contains("Fill Rate:"));
+        assert!(output.contains("Total:       234.4M read, 10.0M written"));
+        assert!(!output.contains("Errors:"));
+
+        l2arc.io_errors = 3;
+        let output = render(|out| {
+            display_l2arc_section(
+                out,
+                &terminal,
+                &bar_style(&terminal, &CliArgs::default()),
+                &l2arc,
+                &thresholds,
+                NumberFormat::default(),
+                &Theme::default().l2arc,
+            )
+        });
+        assert!(output.contains("0 checksum, 3 I/O"));
+    }
+
+    #[test]
+    fn test_display_l2arc_section_warming() {
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+        let mut l2arc = L2ArcStats {
+            hit_rate: 3.2,
+            size: 2 * 1024 * 1024 * 1024,
+            read_bytes: 0,
+            write_bytes: 64 * 1024 * 1024,
+            read_bytes_peak: 0,
+            total_ops: 0,
+            read_bytes_total: 0,
+            write_bytes_total: 2 * 1024 * 1024 * 1024,
+            cksum_bad: 0,
+            io_errors: 0,
+            hits_total: 0,
+            misses_total: 0,
+            warming: true,
+            collected_at: CollectedAt::default(),
+        };
+
+        let thresholds = CacheThresholds::default();
+        let output = render(|out| {
+            display_l2arc_section(
+                out,
+                &terminal,
+                &bar_style(&terminal, &CliArgs::default()),
+                &l2arc,
+                &thresholds,
+                NumberFormat::default(),
+                &Theme::default().l2arc,
+            )
+        });
+        assert!(output.contains("Hit Rate:    3.2 (Warming up)"));
+        assert!(!output.contains("Poor"));
+
+        // Once filled, a low hit rate is reported as such
+        l2arc.warming = false;
+        let output = render(|out| {
+            display_l2arc_section(
+                out,
+                &terminal,
+                &bar_style(&terminal, &CliArgs::default()),
+                &l2arc,
+                &thresholds,
+                NumberFormat::default(),
+                &Theme::default().l2arc,
+            )
+        });
+        assert!(output.contains("Hit Rate:    3.2 (✗ Poor)"));
+    }
+
+    #[test]
+    fn test_display_l2arc_read_rate_relative_to_peak() {
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+        let l2arc = L2ArcStats {
+            hit_rate: 73.5,
+            size: 594_542_387_200,
+            read_bytes: 512 * 1024,
+            write_bytes: 0,
+            read_bytes_peak: 1024 * 1024,
+            total_ops: 0,
+            read_bytes_total: 0,
+            write_bytes_total: 0,
+            cksum_bad: 0,
+            io_errors: 0,
+            hits_total: 0,
+            misses_total: 0,
+            warming: false,
+            collected_at: CollectedAt::default(),
+        };
+
+        let thresholds = CacheThresholds::default();
+        let output = render(|out| {
+            display_l2arc_section(
+                out,
+                &terminal,
+                &bar_style(&terminal, &CliArgs::default()),
+                &l2arc,
+                &thresholds,
+                NumberFormat::default(),
+                &Theme::default().l2arc,
+            )
+        });
+        assert!(output.contains("Read Rate:   512.0K/s [##########..........] 50.0%"));
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_demo_yields_rates() {
+        let data = SharedDemoData::new(DEMO_SEED);
+        let mut collector = ZfsStatsCollector::new(
+            DynamicDemoCommandExecutor::new(data.clone()),
+            DynamicDemoFilesystemReader::new(data),
+        );
+
+        collector.collect_arc_stats().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let arc = collector.collect_arc_stats().await.unwrap();
+
+        assert!(arc.read_ops > 0);
+    }
+
+    /// Filesystem reader for a box without the ZFS kernel module
+    struct MissingFilesystemReader;
+
+    #[async_trait::async_trait]
+    impl FilesystemReader for MissingFilesystemReader {
+        async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+            Err(format!("No such file: {}", path).into())
+        }
+    }
+
+    /// Command executor for a box without the ZFS utilities
+    struct MissingCommandExecutor;
+
+    #[async_trait::async_trait]
+    impl CommandExecutor for MissingCommandExecutor {
+        async fn execute(&self, command: &str, _args: &[&str]) -> Result<String, Box<dyn Error>> {
+            Err(format!("{}: command not found", command).into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detect_zfs() {
+        assert!(
+            detect_zfs(
+                &DemoCommandExecutor,
+                &DemoFilesystemReader,
+                DEFAULT_COMMAND_TIMEOUT
+            )
+            .await
+            .is_ok()
+        );
+        // zpool alone is enough, ARC stats then come from arcstat
+        assert!(
+            detect_zfs(
+                &DemoCommandExecutor,
+                &MissingFilesystemReader,
+                DEFAULT_COMMAND_TIMEOUT
+            )
+            .await
+            .is_ok()
+        );
+
+        let result = detect_zfs(
+            &MissingCommandExecutor,
+            &MissingFilesystemReader,
+            DEFAULT_COMMAND_TIMEOUT,
+        )
+        .await;
+        assert!(matches!(result, Err(MonitorError::ZfsUnavailable)));
+    }
+
+    #[test]
+    fn test_check_interval() {
+        assert!(matches!(
+            check_interval(0),
+            Err(MonitorError::InvalidInterval(0))
+        ));
+        assert!(check_interval(1).is_ok());
+        // Huge intervals only warn
+        assert!(check_interval(5000).is_ok());
+    }
+
+    /// Filesystem reader serving an arcstats snapshot the test can swap between refreshes
+    struct SnapshotFilesystemReader(Arc<Mutex<String>>);
+
+    #[async_trait::async_trait]
+    impl FilesystemReader for SnapshotFilesystemReader {
+        async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+            match path {
+                "/proc/spl/kstat/zfs/arcstats" => Ok(self.0.lock().unwrap().clone()),
+                _ => Err(format!("Not mocked: {}", path).into()),
+            }
+        }
+    }
+
+    fn arcstats_snapshot(read_ops: u64) -> String {
+        format!(
+            "hits 4 900\nmisses 4 100\nsize 4 1073741824\nc_max 4 2147483648\nread_ops 4 {}\n",
+            read_ops
+        )
+    }
+
+    #[tokio::test]
+    async fn test_arc_target_trend_rendered_in_arc_section() {
+        let with_target = |c: u64| format!("{}c 4 {}\n", arcstats_snapshot(0), c);
+        let snapshot = Arc::new(Mutex::new(with_target(1 << 30)));
+        let mut collector = ZfsStatsCollector::new(
+            DemoCommandExecutor,
+            SnapshotFilesystemReader(snapshot.clone()),
+        );
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+        let args = CliArgs::default();
+
+        let mut target_lines = Vec::new();
+        for c in [1 << 30, 3 << 29, 1 << 29] {
+            *snapshot.lock().unwrap() = with_target(c);
+            let mut frame = Vec::new();
+            refresh(
+                &mut frame,
+                &terminal,
+                &mut collector,
+                "data",
+                None,
+                None,
+                &args,
+            )
+            .await
+            .unwrap();
+            let frame = String::from_utf8(frame).unwrap();
+            let line = frame.lines().find(|line| line.contains("Target:")).unwrap();
+            target_lines.push(line.to_string());
+        }
+
+        // Nothing to compare the first sample against
+        assert_eq!(target_lines[0], "    Target:      1.0G / Max: 2.0G");
+        assert_eq!(target_lines[1], "    Target:      1.5G ↑ / Max: 2.0G");
+        assert_eq!(target_lines[2], "    Target:      512.0M ↓ / Max: 2.0G");
+    }
+
+    #[tokio::test]
+    async fn test_read_ops_rate_rendered_in_arc_section() {
+        let snapshot = Arc::new(Mutex::new(arcstats_snapshot(5_000)));
+        let mut collector = ZfsStatsCollector::new(
+            DemoCommandExecutor,
+            SnapshotFilesystemReader(snapshot.clone()),
+        );
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+        let args = CliArgs::default();
+
+        // The first refresh only records the counter
+        let mut frame = Vec::new();
+        refresh(
+            &mut frame,
+            &terminal,
+            &mut collector,
+            "data",
+            None,
+            None,
+            &args,
+        )
+        .await
+        .unwrap();
+        assert!(
+            String::from_utf8(frame)
+                .unwrap()
+                .contains("Read Ops:    0/s")
+        );
+
+        // 100_050 more reads over the (rewound) 100s since then is 1000.5/s
+        *snapshot.lock().unwrap() = arcstats_snapshot(105_050);
+        collector.rewind_rate_samples(Duration::from_secs(100));
+
+        let mut frame = Vec::new();
+        refresh(
+            &mut frame,
+            &terminal,
+            &mut collector,
+            "data",
+            None,
+            None,
+            &args,
+        )
+        .await
+        .unwrap();
+        let output = String::from_utf8(frame).unwrap();
+        assert!(output.contains("Read Ops:    1.00K/s"));
+        assert!(output.contains("Total:       900 hits, 100 misses"));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_sections_are_not_collected() {
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+        let executor = RecordingCommandExecutor::new(DemoCommandExecutor);
+        let log = executor.log();
+        let mut collector = ZfsStatsCollector::new(executor, DemoFilesystemReader);
+
+        // ARC and L2ARC come from kstats; only SLOG needs `zpool status`
+        let args = CliArgs {
+            no_l2arc: true,
+            no_slog: true,
+            ..CliArgs::default()
+        };
+        let mut frame = Vec::new();
+        refresh(
+            &mut frame,
+            &terminal,
+            &mut collector,
+            "data",
+            None,
+            None,
+            &args,
+        )
+        .await
+        .unwrap();
+        let output = String::from_utf8(frame).unwrap();
+        assert!(
+            !log.calls()
+                .iter()
+                .any(|(_, args)| args.first().is_some_and(|arg| arg == "status"))
+        );
+        assert!(output.contains("ARC (Primary RAM Cache, system-wide)"));
+        assert!(!output.contains("L2ARC"));
+        assert!(!output.contains("SLOG"));
+
+        let mut frame = Vec::new();
+        refresh(
+            &mut frame,
+            &terminal,
+            &mut collector,
+            "data",
+            None,
+            None,
+            &CliArgs::default(),
+        )
+        .await
+        .unwrap();
+        assert!(
+            log.calls()
+                .iter()
+                .any(|(_, args)| args.first().is_some_and(|arg| arg == "status"))
+        );
+        assert!(String::from_utf8(frame).unwrap().contains("SLOG"));
+    }
+
+    #[tokio::test]
+    async fn test_once_writes_single_frame_to_file() {
+        let path =
+            std::env::temp_dir().join(format!("zpool-rw-meter-output-{}.txt", std::process::id()));
+        let mut args = CliArgs {
+            output: Some(path.clone()),
+            once: true,
+            ..CliArgs::default()
+        };
+
+        let run = |args: CliArgs| async move {
+            let mut out = Output::open(&args).unwrap();
+            assert!(!out.is_terminal);
+            let terminal = Terminal::with_color_mode(out.color_mode(args.color));
+            let collector =
+                ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data");
+            run_monitor_loop(&mut out, &terminal, vec![collector], "data", &args)
+                .await
+                .unwrap();
+        };
+
+        run(args.clone()).await;
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            contents.starts_with("=") && contents.contains(" 🔍 ZFS Cache Performance Monitor ")
+        );
+        assert!(contents.contains("Pool: data (auto-selected) | Refresh: 2s"));
+        assert!(contents.contains("Cache Efficiency: 99/100 (Excellent)"));
+        assert!(contents.contains("Effective Hit Rate: 100.0% (ARC + L2ARC)"));
+        assert!(contents.contains("📊 ARC (Primary RAM Cache, system-wide)"));
+        // Not a terminal: no cursor control and no color
+        assert!(!contents.contains('\x1B'));
+        assert_eq!(contents.matches("ZFS Cache Performance Monitor").count(), 1);
+
+        // Truncated by default, appended with --append
+        run(args.clone()).await;
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.matches("ZFS Cache Performance Monitor").count(), 1);
+
+        args.append = true;
+        run(args).await;
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.matches("ZFS Cache Performance Monitor").count(), 2);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_once_dumps_raw_inputs() {
+        let dir = std::env::temp_dir().join(format!("zpool-rw-meter-raw-{}", std::process::id()));
+        let args = CliArgs {
+            once: true,
+            dump_raw: Some(dir.clone()),
+            ..CliArgs::default()
+        };
+        let buffer = SharedBuffer::default();
+        let mut out = Output {
+            writer: Box::new(buffer.clone()),
+            is_terminal: false,
+        };
+        let collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data");
+        run_monitor_loop(&mut out, &Terminal::new(), vec![collector], "data", &args)
+            .await
+            .unwrap();
+
+        // One file per source, and none for the priming sample
+        let mut files: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+        assert_eq!(files.len(), 3);
+        let raw = |source: &str| {
+            let path = files
+                .iter()
+                .find(|path| {
+                    path.to_string_lossy()
+                        .ends_with(&format!("-{}.txt", source))
+                })
+                .unwrap();
+            std::fs::read_to_string(path).unwrap()
+        };
+        assert_eq!(raw("arcstats"), include_str!("demo/arcstats.txt"));
+        assert_eq!(raw("zpool-status"), include_str!("demo/zpool_status.txt"));
+        assert_eq!(raw("zpool-iostat-v"), include_str!("demo/zpool_iostat.txt"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_refresh_still_dumps_raw_inputs() {
+        let dir =
+            std::env::temp_dir().join(format!("zpool-rw-meter-raw-failed-{}", std::process::id()));
+        let args = CliArgs {
+            dump_raw: Some(dir.clone()),
+            ..CliArgs::default()
+        };
+        let mut out = Output {
+            writer: Box::new(io::sink()),
+            is_terminal: false,
+        };
+        let mut collectors = vec![
+            ZfsStatsCollector::new(NoIostatCommandExecutor, DemoFilesystemReader).with_pool("data"),
+        ];
+        let mut session = Session::new();
+
+        // The SLOG's `zpool iostat` fails, yet what could be read is on disk
+        let result = write_refresh(
+            &mut out,
+            true,
+            &Terminal::new(),
+            &mut collectors,
+            "data",
+            &args,
+            &mut session,
+        )
+        .await;
+        assert!(result.is_err());
+        let files = std::fs::read_dir(&dir).unwrap().count();
+        assert_eq!(files, 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failing_dump_is_noted_in_footer() {
+        // A file where the dump directory should be
+        let path =
+            std::env::temp_dir().join(format!("zpool-rw-meter-raw-file-{}", std::process::id()));
+        std::fs::write(&path, "").unwrap();
+        let args = CliArgs {
+            dump_raw: Some(path.clone()),
+            ..CliArgs::default()
+        };
+        let buffer = SharedBuffer::default();
+        let mut out = Output {
+            writer: Box::new(buffer.clone()),
+            is_terminal: false,
+        };
+        let mut collectors = vec![
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data"),
+        ];
+        let mut session = Session::new();
+
+        write_refresh(
+            &mut out,
+            true,
+            &Terminal::new(),
+            &mut collectors,
+            "data",
+            &args,
+            &mut session,
+        )
+        .await
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("Ctrl+C to exit\nRaw input dump failed: Failed to create "));
+    }
+
+    fn sample(taken_at: Instant, hits: u64, l2_read: u64, read_ops: u64) -> Sample {
+        Sample {
+            taken_at,
+            elapsed: None,
+            timestamp: Utc::now(),
+            arc: ArcStats {
+                hit_rate: 95.0,
+                size: 1024,
+                target: 2048,
+                target_trend: None,
+                max: 4096,
+                read_ops,
+                hits_total: hits,
+                misses_total: 100,
+                hit_breakdown: None,
+                eviction: None,
+                metadata: None,
+                no_grow: None,
+                prunes: None,
+                memory_total: None,
+                collected_at: CollectedAt::default(),
+            },
+            l2arc: Some(L2ArcStats {
+                hit_rate: 50.0,
+                size: 0,
+                read_bytes: 0,
+                read_bytes_peak: 0,
+                write_bytes: 0,
+                total_ops: 0,
+                read_bytes_total: l2_read,
+                write_bytes_total: 0,
+                cksum_bad: 0,
+                io_errors: 0,
+                hits_total: 0,
+                misses_total: 0,
+                warming: false,
+                collected_at: CollectedAt::default(),
+            }),
+            slog: None,
+            slog_unavailable: None,
+            pool_io: None,
+            pool_io_unavailable: None,
+            dataset: None,
+            dataset_unavailable: None,
+            uptime: None,
+        }
+    }
+
+    #[test]
+    fn test_min_change_keeps_sections_that_barely_moved() {
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+        let args = CliArgs {
+            min_change: Some(5.0),
+            ..CliArgs::default()
+        };
+        let mut session = Session::new();
+        let mut render_next = |sample: Sample| {
+            render(|out| {
+                render_sample(
+                    out,
+                    &terminal,
+                    &sample,
+                    "data",
+                    Some(&mut session),
+                    None,
+                    &args,
+                )
+            })
+        };
+
+        let first = render_next(sample(Instant::now(), 100_000, 0, 1000));
+        assert!(first.contains("Total:       100000 hits, 100 misses"));
+
+        // 0.1% more hits and 1% more reads: the ARC section is not re-rendered
+        let second = render_next(sample(Instant::now(), 100_100, 0, 1010));
+        assert!(second.contains("Read Ops:    1.00K/s"));
+        assert!(second.contains("Total:       100000 hits, 100 misses"));
+        assert!(!second.contains("100100"));
+
+        // Twice the reads is well past the threshold
+        let third = render_next(sample(Instant::now(), 100_200, 0, 2000));
+        assert!(third.contains("Read Ops:    2.00K/s"));
+        assert!(third.contains("Total:       100200 hits, 100 misses"));
+
+        // Without --min-change every sample is rendered as is
+        let output = render(|out| {
+            render_sample(
+                out,
+                &terminal,
+                &sample(Instant::now(), 100_300, 0, 2000),
+                "data",
+                Some(&mut Session::new()),
+                None,
+                &CliArgs::default(),
+            )
+        });
+        assert!(output.contains("Total:       100300 hits, 100 misses"));
+    }
+
+    #[test]
+    fn test_metrics_moved() {
+        let metrics = |hit_rate: f64, fault: Option<&str>| {
+            JsonValue::object([
+                ("hit_rate", JsonValue::Float(hit_rate)),
+                ("fault", fault.into()),
+            ])
+        };
+        assert!(!metrics_moved(
+            &metrics(90.0, None),
+            &metrics(92.0, None),
+            5.0
+        ));
+        assert!(metrics_moved(
+            &metrics(90.0, None),
+            &metrics(99.0, None),
+            5.0
+        ));
+        // States aren't numbers: any change counts
+        assert!(metrics_moved(
+            &metrics(90.0, None),
+            &metrics(90.0, Some("FAULTED")),
+            5.0
+        ));
+        // So does a section appearing or going away
+        assert!(metrics_moved(&JsonValue::Null, &metrics(90.0, None), 5.0));
+    }
+
+    #[tokio::test]
+    async fn test_min_change_skips_unchanged_lines() {
+        let args = CliArgs {
+            min_change: Some(5.0),
+            ..CliArgs::default()
+        };
+        let buffer = SharedBuffer::default();
+        let mut out = Output {
+            writer: Box::new(buffer.clone()),
+            is_terminal: true,
+        };
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+        let mut collectors = vec![
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data"),
+        ];
+        let mut session = Session::new();
+
+        for first_frame in [true, false] {
+            write_refresh(
+                &mut out,
+                first_frame,
+                &terminal,
+                &mut collectors,
+                "data",
+                &args,
+                &mut session,
+            )
+            .await
+            .unwrap();
+        }
+
+        // The static demo counters don't move, so the second frame only moves the
+        // cursor past the sections
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let (first, second) = output.split_once("\x1B[H").unwrap();
+        assert!(first.contains("📊 ARC (Primary RAM Cache, system-wide)"));
+        assert!(!second.contains("📊 ARC"));
+        assert!(second.contains("\x1B[E"));
+        assert!(second.contains("2 samples"));
+    }
+
+    fn report_sample() -> Sample {
+        let mut sample = sample(Instant::now(), 900, 0, 10);
+        if let Some(l2arc) = &mut sample.l2arc {
+            l2arc.warming = true;
+        }
+        sample.slog = Some(SlogStats {
+            device: "mirror-1".to_string(),
+            display_name: "sdc".to_string(),
+            write_ops: 23,
+            write_bw: 12_582_912,
+            write_bw_peak: 25_165_824,
+            write_ops_avg: 120_000,
+            write_bw_avg: 64_000_000_000,
+            utilization: 12.5,
+            latency: 2.1,
+            cache_age: None,
+            collected_at: CollectedAt::default(),
+            latency_percentiles: None,
+            fault: None,
+        });
+        sample
+    }
+
+    #[tokio::test]
+    async fn test_refresh_reads_arcstats_once() {
+        let reader = RecordingFilesystemReader::new(DemoFilesystemReader);
+        let log = reader.log();
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, reader).with_pool("data");
+        let args = CliArgs::default();
+        let arcstats = "/proc/spl/kstat/zfs/arcstats";
+
+        // ARC and L2ARC both come from arcstats, read only once per refresh
+        let sample = collect_sample(&mut collector, &args).await.unwrap();
+        assert!(sample.l2arc.is_some());
+        assert_eq!(log.count(arcstats), 1);
+
+        // The next refresh reads it again rather than reusing stale counters
+        collect_sample(&mut collector, &args).await.unwrap();
+        assert_eq!(log.count(arcstats), 2);
+    }
+
+    #[test]
+    fn test_render_report() {
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+        let args = CliArgs {
+            report: true,
+            ..CliArgs::default()
+        };
+
+        let output = render(|out| render_report(out, &terminal, &report_sample(), "data", &args));
+        assert!(output.starts_with("ZFS cache report for data, "));
+        assert!(output.contains("ARC (Primary RAM Cache, system-wide)"));
+        assert!(output.contains("L2ARC (Secondary SSD Cache)"));
+        assert!(output.contains("SLOG (Synchronous Write Log)"));
+        assert!(
+            output.ends_with("Summary: ARC hit rate excellent; L2ARC warming; SLOG healthy.\n")
+        );
+        assert!(!output.contains('\x1b'));
+
+        let mut sample = report_sample();
+        if let Some(slog) = &mut sample.slog {
+            slog.fault = Some("FAULTED".to_string());
+        }
+        assert!(interpret(&sample, &args).ends_with("L2ARC warming; SLOG faulted."));
+    }
+
+    #[test]
+    fn test_interpret() {
+        let args = CliArgs::default();
+        let mut sample = report_sample();
+        sample.arc.hit_rate = 55.0;
+        sample.arc.eviction = Some(ArcEviction {
+            evict_skip: 2500,
+            evict_not_enough: 10,
+            mutex_miss: 0,
+        });
+        if let Some(l2arc) = &mut sample.l2arc {
+            l2arc.warming = false;
+            l2arc.io_errors = 3;
+        }
+        if let Some(slog) = &mut sample.slog {
+            slog.utilization = 80.0;
+        }
+        assert_eq!(
+            interpret(&sample, &args),
+            "ARC hit rate fair; ARC under high eviction pressure; L2ARC reporting 3 errors; \
+             L2ARC hit rate fair; SLOG saturated."
+        );
+
+        sample.l2arc = None;
+        sample.slog = None;
+        sample.slog_unavailable = Some("needs root".to_string());
+        sample.arc.eviction = None;
+        assert_eq!(
+            interpret(&sample, &args),
+            "ARC hit rate fair; no L2ARC; SLOG unavailable."
+        );
+
+        let args = CliArgs {
+            no_l2arc: true,
+            no_slog: true,
+            ..CliArgs::default()
+        };
+        sample.slog_unavailable = None;
+        assert_eq!(interpret(&sample, &args), "ARC hit rate fair.");
+    }
+
+    #[test]
+    fn test_slog_rating_uses_thresholds() {
+        let strict = CliArgs {
+            thresholds: CacheThresholds {
+                excellent: 95.0,
+                good: 90.0,
+                fair: 80.0,
+            },
+            ..CliArgs::default()
+        };
+        let mut sample = report_sample();
+        sample.slog.as_mut().unwrap().utilization = 25.0;
+
+        // 75% headroom is good by default, poor by the strict thresholds
+        assert!(interpret(&sample, &CliArgs::default()).ends_with("SLOG healthy."));
+        assert!(interpret(&sample, &strict).ends_with("SLOG saturated."));
+
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+        let output = render(|out| {
+            display_slog_section(
+                out,
+                &terminal,
+                &bar_style(&terminal, &strict),
+                sample.slog.as_ref().unwrap(),
+                &strict.thresholds,
+                NumberFormat::default(),
+                &Theme::default().slog,
+            )
+        });
+        assert!(output.contains("25 (Poor)"), "{}", output);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_pool() {
+        // The demo's `data` pool has more write ops than boot-pool and usb-backup
+        let mut args = CliArgs::default();
+        assert_eq!(
+            resolve_pool(&DemoCommandExecutor, &args).await.unwrap(),
+            "data"
+        );
+        assert_eq!(
+            resolve_pool(&MissingCommandExecutor, &args).await.unwrap(),
+            "data"
+        );
+
+        args.pool = Some("usb-backup".to_string());
+        assert_eq!(
+            resolve_pool(&DemoCommandExecutor, &args).await.unwrap(),
+            "usb-backup"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_pools_imported() {
+        let mut args = CliArgs::default();
+        assert!(matches!(
+            resolve_pool(&NoPoolsCommandExecutor, &args).await,
+            Err(MonitorError::NoPools)
+        ));
+        // ZFS missing altogether is left to detect_zfs, not reported as no pools
+        assert!(resolve_pool(&MissingCommandExecutor, &args).await.is_ok());
+
+        args.overview = true;
+        let error = monitored_pools(&NoPoolsCommandExecutor, "data", &args)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<MonitorError>(),
+            Some(MonitorError::NoPools)
+        ));
+
+        args.exclude_pools = vec!["boot-pool".into(), "data".into(), "usb-backup".into()];
+        let error = monitored_pools(&DemoCommandExecutor, "data", &args)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("--exclude-pool"));
+    }
+
+    #[tokio::test]
+    async fn test_monitored_pools_excludes() {
+        let mut args = CliArgs {
+            overview: true,
+            exclude_pools: vec!["boot-pool".to_string()],
+            ..CliArgs::default()
+        };
+        let pools = monitored_pools(&DemoCommandExecutor, "data", &args)
+            .await
+            .unwrap();
+        assert_eq!(pools, vec!["data", "usb-backup"]);
+
+        // An explicitly named pool wins over its exclusion
+        args.pool = Some("boot-pool".to_string());
+        let pools = monitored_pools(&DemoCommandExecutor, "boot-pool", &args)
+            .await
+            .unwrap();
+        assert_eq!(pools, vec!["boot-pool", "data", "usb-backup"]);
+
+        // Without --overview only the monitored pool is collected
+        args.overview = false;
+        let pools = monitored_pools(&DemoCommandExecutor, "boot-pool", &args)
+            .await
+            .unwrap();
+        assert_eq!(pools, vec!["boot-pool"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_overview_shows_arc_once() {
+        let args = CliArgs {
+            overview: true,
+            once: true,
+            ..CliArgs::default()
+        };
+        let buffer = SharedBuffer::default();
+        let mut out = Output {
+            writer: Box::new(buffer.clone()),
+            is_terminal: false,
+        };
+        let collectors = ["data", "usb-backup"]
+            .into_iter()
+            .map(|pool| {
+                ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool(pool)
+            })
+            .collect();
+
+        run_monitor_loop(&mut out, &Terminal::new(), collectors, "data", &args)
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("Pool: data, usb-backup"));
+        // The ARC is the same for every pool, so it's shown once above them
+        assert_eq!(
+            output
+                .matches("ARC (Primary RAM Cache, system-wide)")
+                .count(),
+            1
+        );
+        assert_eq!(output.matches("Hit Rate:").count(), 1);
+        let table = output.split("POOL ").nth(1).unwrap();
+        assert!(table.contains("\ndata "));
+        assert!(table.contains("\nusb-backup "));
+        assert!(table.lines().nth(1).unwrap().ends_with("58%"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_high_precision_ticks_evenly() {
+        let args = CliArgs {
+            high_precision: true,
+            ..CliArgs::default()
+        };
+        let mut ticker = Ticker::new(&args);
+        let start = tokio::time::Instant::now().into_std();
+
+        // However long each refresh takes, ticks stay on the 2s grid
+        let mut ticks = Vec::new();
+        for work_ms in [0, 700, 1500, 300] {
+            ticks.push(ticker.tick().await);
+            tokio::time::sleep(Duration::from_millis(work_ms)).await;
+        }
+        assert_eq!(ticks[0] - start, Duration::from_secs(2));
+        for pair in ticks.windows(2) {
+            assert_eq!(pair[1] - pair[0], Duration::from_secs(2));
+        }
+
+        // Sleeping after each refresh adds the work time to the period
+        let mut ticker = Ticker::new(&CliArgs::default());
+        let first = ticker.tick().await;
+        tokio::time::sleep(Duration::from_millis(700)).await;
+        let second = ticker.tick().await;
+        assert_eq!(second - first, Duration::from_millis(2700));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_high_precision_pins_rate_timestamps() {
+        let args = CliArgs {
+            high_precision: true,
+            ..CliArgs::default()
+        };
+        let mut ticker = Ticker::new(&args);
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        // Refreshes are stamped with their tick, not the moment stats were read
+        let tick = ticker.tick().await;
+        collector.pin_sample_time(tick);
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        let sample = collect_sample(&mut collector, &args).await.unwrap();
+        assert_eq!(sample.taken_at, tick);
+
+        let tick = ticker.tick().await;
+        collector.pin_sample_time(tick);
+        let sample = collect_sample(&mut collector, &args).await.unwrap();
+        assert_eq!(sample.elapsed, Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_display_header_shows_drift() {
+        let terminal = Terminal::new();
+        let time = DateTime::parse_from_rfc3339("2025-09-14T17:10:08Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut args = CliArgs {
+            interval: 2,
+            ..CliArgs::default()
+        };
+        let header = |args: &CliArgs, elapsed| {
+            render(|out| display_header(out, &terminal, "data", args, elapsed, time, None))
+        };
+
+        assert!(header(&args, None).contains(
+            "Pool: data | Refresh: 2s | Time: 2025-09-14 17:10:08 | Rates: instantaneous"
+        ));
+        assert!(header(&args, Some(Duration::from_millis(2030))).contains("| Refresh: 2s |"));
+        assert!(
+            header(&args, Some(Duration::from_millis(2340)))
+                .contains("Pool: data | Refresh: 2s (actual 2.3s) | Time: 2025-09-14 17:10:08")
+        );
+
+        args.since_boot_rates = true;
+        assert!(header(&args, None).contains("| Rates: since boot\n"));
+    }
+
+    #[test]
+    fn test_render_sample_notes_unavailable_slog() {
+        let mut sample = sample(Instant::now(), 10_000, 0, 0);
+        sample.slog_unavailable =
+            Some("`zpool status` needs elevated privileges, run with sudo".to_string());
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+
+        let output = render(|out| {
+            render_sample(
+                out,
+                &terminal,
+                &sample,
+                "data",
+                None,
+                None,
+                &CliArgs::default(),
+            )
+        });
+        assert!(output.contains(
+            "🟡 SLOG (Synchronous Write Log)\n    \
+             Unavailable: `zpool status` needs elevated privileges, run with sudo\n"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_collect_sample_survives_failing_pool_io() {
+        let mut collector =
+            ZfsStatsCollector::new(NoIostatCommandExecutor, DemoFilesystemReader).with_pool("data");
+        // The SLOG reads `zpool iostat` too, leave it out
+        let args = CliArgs {
+            no_slog: true,
+            ..CliArgs::default()
+        };
+        let sample = collect_sample(&mut collector, &args).await.unwrap();
+        assert!(sample.pool_io.is_none());
+        let reason = sample.pool_io_unavailable.clone().unwrap();
+        assert!(reason.contains("iostat"), "{}", reason);
+
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+        let output =
+            render(|out| render_sample(out, &terminal, &sample, "data", None, None, &args));
+        assert!(output.contains(&format!(
+            "💿 Pool I/O (data)\n    Unavailable: {}\n",
+            reason
+        )));
+        assert!(output.contains("📊 ARC (Primary RAM Cache, system-wide)"));
+    }
+
+    #[tokio::test]
+    async fn test_unprivileged_refresh_notes_slog_and_pool_io() {
+        let mut collector =
+            ZfsStatsCollector::new(UnprivilegedCommandExecutor, DemoFilesystemReader)
+                .with_pool("data");
+        let args = CliArgs::default();
+        let sample = collect_sample(&mut collector, &args).await.unwrap();
+
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+        let output =
+            render(|out| render_sample(out, &terminal, &sample, "data", None, None, &args));
+        assert!(output.contains("needs elevated privileges, run with sudo for SLOG stats"));
+        assert!(output.contains(
+            "💿 Pool I/O (data)\n    Unavailable: `zpool iostat -v data` needs elevated privileges, \
+             run with sudo for Pool I/O stats\n"
+        ));
+        assert!(output.contains("📊 ARC (Primary RAM Cache, system-wide)"));
+    }
+
+    /// Demo commands on a system where `zpool iostat` fails, e.g. a hung device
+    struct NoIostatCommandExecutor;
+
+    #[async_trait::async_trait]
+    impl CommandExecutor for NoIostatCommandExecutor {
+        async fn execute(&self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+            if command == "zpool" && args.first() == Some(&"iostat") {
+                return Err("cannot open 'data': I/O error".into());
+            }
+            DemoCommandExecutor.execute(command, args).await
+        }
+    }
+
+    #[test]
+    fn test_render_sample_fits_short_terminal() {
+        let sample = report_sample();
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+        let args = CliArgs::default();
+        let full = render(|out| render_sample(out, &terminal, &sample, "data", None, None, &args));
+        assert_eq!(full.lines().count(), 28);
+
+        // A 24 row terminal leaves 21 above the footer: enough for the header (5 lines),
+        // ARC (7) and SLOG (8) plus the indicator, but not for L2ARC (8) too
+        let mut terminal = terminal;
+        terminal.fixed_height = Some(24);
+        let out = Output {
+            writer: Box::new(io::sink()),
+            is_terminal: true,
+        };
+        let rows = dashboard_rows(&out, &terminal, &args);
+        assert_eq!(rows, Some(21));
+
+        let fitted =
+            render(|out| render_sample(out, &terminal, &sample, "data", None, rows, &args));
+        assert_eq!(fitted.lines().count(), 21);
+        assert!(fitted.contains("Cache Efficiency"));
+        assert!(fitted.contains("ARC (Primary RAM Cache, system-wide)"));
+        assert!(fitted.contains("SLOG (Synchronous Write Log)"));
+        assert!(!fitted.contains("L2ARC (Secondary SSD Cache)"));
+        assert!(fitted.ends_with("…(8 lines hidden)\n"));
+
+        // Everything fits once the terminal is tall enough again
+        terminal.fixed_height = Some(31);
+        let rows = dashboard_rows(&out, &terminal, &args);
+        let resized =
+            render(|out| render_sample(out, &terminal, &sample, "data", None, rows, &args));
+        assert_eq!(resized, full);
+    }
+
+    #[test]
+    fn test_sample_rates_reflect_delta() {
+        let t0 = Instant::now();
+        let first = sample(t0, 10_000, 1_000_000, 0);
+        let second = sample(t0 + Duration::from_secs(2), 12_400, 5_000_000, 1200);
+
+        let rates = sample_rates(&first, &second);
+        assert_eq!(
+            rates,
+            vec![
+                ("arc_read_ops".to_string(), 1200.0),
+                ("arc_hits".to_string(), 1200.0),
+                ("arc_misses".to_string(), 0.0),
+                ("l2arc_read_bytes".to_string(), 2_000_000.0),
+                ("l2arc_write_bytes".to_string(), 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sample_rates_since_boot() {
+        let t0 = Instant::now();
+        let first = sample(t0, 10_000, 1_000_000, 0);
+        let mut second = sample(t0 + Duration::from_secs(2), 12_400, 5_000_000, 3);
+        second.uptime = Some(Duration::from_secs(100));
+
+        // The second sample's counters over its uptime, not the change since the first
+        let rates = sample_rates(&first, &second);
+        assert_eq!(
+            rates,
+            vec![
+                ("arc_read_ops".to_string(), 3.0),
+                ("arc_hits".to_string(), 124.0),
+                ("arc_misses".to_string(), 1.0),
+                ("l2arc_read_bytes".to_string(), 50_000.0),
+                ("l2arc_write_bytes".to_string(), 0.0),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_once_json_writes_batch() {
+        let path =
+            std::env::temp_dir().join(format!("zpool-rw-meter-batch-{}.json", std::process::id()));
+        let args = CliArgs {
+            output: Some(path.clone()),
+            once: true,
+            format: OutputFormat::Json,
+            interval: 0,
+            ..CliArgs::default()
+        };
+
+        let mut out = Output::open(&args).unwrap();
+        let collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data");
+        run_monitor_loop(&mut out, &Terminal::new(), vec![collector], "data", &args)
+            .await
+            .unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.starts_with(r#"{"schema_version":2,"pool":"data","interval_seconds":"#));
+        assert_eq!(contents.matches(r#""metrics":"#).count(), 2);
+        // The static demo counters don't move, so every rate is zero
+        assert!(contents.contains(r#""rates":{"arc_read_ops":0,"arc_hits":0,"arc_misses":0,"#));
+        // The SLOG figures from `zpool iostat` are averages, not counters
+        assert!(contents.contains(r#""l2arc_write_bytes":0}"#));
+        assert!(!contents.contains("slog_write"));
+    }
+
+    /// Demo reader counting `/proc/meminfo` reads, which happen once per refresh
+    struct CountingFilesystemReader(Arc<Mutex<usize>>);
+
+    #[async_trait::async_trait]
+    impl FilesystemReader for CountingFilesystemReader {
+        async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+            if path == "/proc/meminfo" {
+                *self.0.lock().unwrap() += 1;
+            }
+            DemoFilesystemReader.read_to_string(path).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_count_limits_refreshes() {
+        let refreshes = Arc::new(Mutex::new(0));
+        let args = CliArgs {
+            interval: 0,
+            count: 3,
+            warmup_ms: 0,
+            ..CliArgs::default()
+        };
+        let mut out = Output {
+            writer: Box::new(io::sink()),
+            is_terminal: false,
+        };
+        let collector = ZfsStatsCollector::new(
+            DemoCommandExecutor,
+            CountingFilesystemReader(refreshes.clone()),
+        );
+
+        run_monitor_loop(&mut out, &Terminal::new(), vec![collector], "data", &args)
+            .await
+            .unwrap();
+        assert_eq!(*refreshes.lock().unwrap(), 3);
+    }
+
+    /// Writer noting how many refreshes a `CountingFilesystemReader` had seen
+    /// when the first byte was written
+    struct FirstWriteProbe {
+        refreshes: Arc<Mutex<usize>>,
+        seen: Arc<Mutex<Option<usize>>>,
+    }
+
+    impl Write for FirstWriteProbe {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let mut seen = self.seen.lock().unwrap();
+            seen.get_or_insert(*self.refreshes.lock().unwrap());
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_warmup_primes_before_first_frame() {
+        let refreshes = Arc::new(Mutex::new(0));
+        let seen = Arc::new(Mutex::new(None));
+        let args = CliArgs {
+            count: 1,
+            ..CliArgs::default()
+        };
+        let mut out = Output {
+            writer: Box::new(FirstWriteProbe {
+                refreshes: refreshes.clone(),
+                seen: seen.clone(),
+            }),
+            is_terminal: false,
+        };
+        let collector = ZfsStatsCollector::new(
+            DemoCommandExecutor,
+            CountingFilesystemReader(refreshes.clone()),
+        );
+
+        let start = tokio::time::Instant::now();
+        run_monitor_loop(&mut out, &Terminal::new(), vec![collector], "data", &args)
+            .await
+            .unwrap();
+
+        // The priming sample and the rendered one, shown after the warm-up
+        // instead of a whole interval
+        assert_eq!(*seen.lock().unwrap(), Some(2));
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+    }
+
+    /// Command executor for a slow NAS: every command stalls for the given time
+    struct SlowCommandExecutor(Duration);
+
+    #[async_trait::async_trait]
+    impl CommandExecutor for SlowCommandExecutor {
+        async fn execute(&self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+            tokio::time::sleep(self.0).await;
+            DemoCommandExecutor.execute(command, args).await
+        }
+    }
+
+    /// Command executor for a system where `data` is exported after the first
+    /// `zpool list`, leaving `boot-pool` and `usb-backup` imported
+    #[derive(Default)]
+    struct ExportingCommandExecutor {
+        listed: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait::async_trait]
+    impl CommandExecutor for ExportingCommandExecutor {
+        async fn execute(&self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+            let output = DemoCommandExecutor.execute(command, args).await?;
+            let exported = match (command, args) {
+                ("zpool", ["list", ..]) => {
+                    self.listed.swap(true, std::sync::atomic::Ordering::SeqCst)
+                }
+                _ => self.listed.load(std::sync::atomic::Ordering::SeqCst),
+            };
+            if !exported || !matches!(args, ["list", ..] | ["iostat", "-H", "-p"]) {
+                return Ok(output);
+            }
+            Ok(output
+                .lines()
+                .filter(|line| line.split_whitespace().next() != Some("data"))
+                .map(|line| format!("{}\n", line))
+                .collect())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_follow_new_pool_switches_when_exported() {
+        let args = CliArgs {
+            count: 2,
+            warmup_ms: 0,
+            follow_new_pool: true,
+            ..CliArgs::default()
+        };
+        let buffer = SharedBuffer::default();
+        let mut out = Output {
+            writer: Box::new(buffer.clone()),
+            is_terminal: false,
+        };
+        let collector =
+            ZfsStatsCollector::new(ExportingCommandExecutor::default(), DemoFilesystemReader)
+                .with_pool("data");
+
+        run_monitor_loop(&mut out, &Terminal::new(), vec![collector], "data", &args)
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let headers: Vec<&str> = output
+            .lines()
+            .filter(|line| line.starts_with("Pool: "))
+            .collect();
+        assert_eq!(headers.len(), 2);
+        assert!(headers[0].starts_with("Pool: data (auto-selected)"));
+        // The busiest of the pools still imported
+        assert!(headers[1].starts_with("Pool: boot-pool (switched from data)"));
+    }
+
+    /// Writer whose bytes stay readable after it's boxed into an `Output`
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_adapted_interval() {
+        assert_eq!(adapted_interval(2, Duration::from_millis(900)), None);
+        assert_eq!(adapted_interval(2, Duration::from_secs(1)), None);
+        assert_eq!(adapted_interval(2, Duration::from_millis(1100)), Some(3));
+        assert_eq!(adapted_interval(1, Duration::from_millis(2600)), Some(6));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_adaptive_widens_interval_for_slow_commands() {
+        let args = CliArgs {
+            interval: 1,
+            count: 3,
+            adaptive: true,
+            // Priming would fill the command cache and hide the slow first refresh
+            warmup_ms: 0,
+            ..CliArgs::default()
+        };
+        let buffer = SharedBuffer::default();
+        let mut out = Output {
+            writer: Box::new(buffer.clone()),
+            is_terminal: false,
+        };
+        // Each command alone takes longer than the whole 1s interval
+        let collector = ZfsStatsCollector::new(
+            SlowCommandExecutor(Duration::from_millis(1500)),
+            DemoFilesystemReader,
+        )
+        .with_pool("data");
+
+        run_monitor_loop(&mut out, &Terminal::new(), vec![collector], "data", &args)
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let intervals: Vec<u32> = output
+            .lines()
+            .filter_map(|line| line.split("Refresh: ").nth(1))
+            .map(|refresh| refresh[..refresh.find('s').unwrap()].parse().unwrap())
+            .collect();
+        assert_eq!(intervals.len(), 3);
+        assert_eq!(intervals[0], 1);
+        assert!(intervals[1] > 1, "{:?}", intervals);
+        assert!(intervals[2] >= intervals[1], "{:?}", intervals);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_watchdog_flags_stalled_collection() {
+        let args = CliArgs {
+            interval: 1,
+            once: true,
+            ..CliArgs::default()
+        };
+        let run = |executor| {
+            let args = args.clone();
+            async move {
+                let buffer = SharedBuffer::default();
+                let mut out = Output {
+                    writer: Box::new(buffer.clone()),
+                    is_terminal: false,
+                };
+                let collector =
+                    ZfsStatsCollector::new(executor, DemoFilesystemReader).with_pool("data");
+                run_monitor_loop(&mut out, &Terminal::new(), vec![collector], "data", &args)
+                    .await
+                    .unwrap();
+                String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap()
+            }
+        };
+
+        // A hanging device: every zpool call blocks for 3s, far past 2x the interval
+        let output = run(SlowCommandExecutor(Duration::from_secs(3))).await;
+        let (banner, frame) = output.split_once('\n').unwrap();
+        assert_eq!(
+            banner,
+            "⚠ zpool command slow — possible device hang (no stats for 2s)"
+        );
+        assert!(frame.starts_with("⚠ zpool command slow — possible device hang (refresh took "));
+        assert!(frame.contains("ZFS Cache Performance Monitor"));
+
+        let output = run(SlowCommandExecutor(Duration::from_millis(100))).await;
+        assert!(!output.contains("zpool command slow"));
+    }
+
+    /// Filesystem reader for a ZFS module that unloads on the 3rd and 4th read of
+    /// the ARC kstats and comes back with restarted counters
+    struct ReloadingFilesystemReader(Arc<Mutex<u64>>);
+
+    #[async_trait::async_trait]
+    impl FilesystemReader for ReloadingFilesystemReader {
+        async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+            if path != "/proc/spl/kstat/zfs/arcstats" {
+                return Err(format!("Not mocked: {}", path).into());
+            }
+            let mut reads = self.0.lock().unwrap();
+            *reads += 1;
+            match *reads {
+                1..=2 => Ok(arcstats_snapshot(*reads * 1000)),
+                3..=4 => Err("No such file or directory".into()),
+                // Restarted counters that already passed the old value would
+                // otherwise make a bogus rate against the stale baseline
+                _ => Ok(arcstats_snapshot(3000 + (*reads - 5) * 100)),
+            }
+        }
+    }
+
+    /// Demo commands, except that arcstat can't stand in for the missing kstats
+    struct NoArcstatCommandExecutor;
+
+    #[async_trait::async_trait]
+    impl CommandExecutor for NoArcstatCommandExecutor {
+        async fn execute(&self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+            if command.starts_with("arcstat") {
+                return Err(format!("{}: command not found", command).into());
+            }
+            DemoCommandExecutor.execute(command, args).await
+        }
+    }
+
+    /// A healthy pool with a fast SLOG and the given cache status
+    fn watched(status: CacheStatus) -> Watched {
+        Watched {
+            status,
+            efficiency: 80.0,
+            pool_state: Some("ONLINE".to_string()),
+            slog_latency: Some(2.1),
+        }
+    }
+
+    #[test]
+    fn test_session_reports_status_changes() {
+        let mut session = Session::new();
+        let changes: Vec<String> = [CacheStatus::Good, CacheStatus::Poor, CacheStatus::Good]
+            .into_iter()
+            .flat_map(|status| session.observe("data", watched(status)))
+            .collect();
+
+        assert_eq!(
+            changes,
+            [
+                "cache efficiency degraded from Good to Poor (80/100)",
+                "cache efficiency recovered from Poor to Good (80/100)"
+            ]
+        );
+        // Pools are tracked separately, each starting without a change
+        let backup = session.observe("backup", watched(CacheStatus::Poor));
+        assert!(backup.is_empty());
+        let data = session.observe("data", watched(CacheStatus::Good));
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn test_session_reports_pool_and_slog_changes() {
+        let mut session = Session::new();
+        session.observe("data", watched(CacheStatus::Good));
+
+        let degraded = Watched {
+            pool_state: Some("DEGRADED".to_string()),
+            slog_latency: Some(25.3),
+            ..watched(CacheStatus::Good)
+        };
+        assert_eq!(
+            session.observe("data", degraded.clone()),
+            [
+                "pool degraded from ONLINE to DEGRADED",
+                "SLOG latency rose above 20ms (25.3ms)"
+            ]
+        );
+        // Staying degraded and slow isn't news
+        assert!(session.observe("data", degraded).is_empty());
+        assert_eq!(
+            session.observe("data", watched(CacheStatus::Good)),
+            [
+                "pool recovered from DEGRADED to ONLINE",
+                "SLOG latency back below 20ms (2.1ms)"
+            ]
+        );
+
+        // Without a reading on one side there is nothing to compare
+        let unknown = Watched {
+            pool_state: None,
+            slog_latency: None,
+            ..watched(CacheStatus::Good)
+        };
+        assert!(session.observe("data", unknown).is_empty());
+        let known_again = session.observe("data", watched(CacheStatus::Good));
+        assert!(known_again.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_quiet_prints_nothing_while_status_holds() {
+        let args = CliArgs {
+            interval: 1,
+            count: 3,
+            warmup_ms: 0,
+            quiet: true,
+            ..CliArgs::default()
+        };
+        let buffer = SharedBuffer::default();
+        let mut out = Output {
+            writer: Box::new(buffer.clone()),
+            is_terminal: false,
+        };
+        let collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data");
+
+        run_monitor_loop(&mut out, &Terminal::new(), vec![collector], "data", &args)
+            .await
+            .unwrap();
+
+        assert!(buffer.0.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_table_format_for_single_pool() {
+        let args = CliArgs {
+            once: true,
+            format: OutputFormat::Table,
+            ..CliArgs::default()
+        };
+        let buffer = SharedBuffer::default();
+        let mut out = Output {
+            writer: Box::new(buffer.clone()),
+            is_terminal: false,
+        };
+        let collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data");
+
+        run_monitor_loop(&mut out, &Terminal::new(), vec![collector], "data", &args)
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4, "{}", output);
+        assert_eq!(lines[0], "ARC HIT% (system-wide): 96.9");
+        assert!(lines[1].starts_with("POOL  L2 HIT%"));
+        assert!(lines[2].starts_with("----  -------"));
+        assert!(lines[3].starts_with("data  "));
+        assert!(!output.contains("Uptime"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_footer_counts_samples() {
+        let args = CliArgs {
+            interval: 1,
+            count: 3,
+            warmup_ms: 0,
+            ..CliArgs::default()
+        };
+        let buffer = SharedBuffer::default();
+        let mut out = Output {
+            writer: Box::new(buffer.clone()),
+            is_terminal: false,
+        };
+        let collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data");
+
+        run_monitor_loop(&mut out, &Terminal::new(), vec![collector], "data", &args)
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let footers: Vec<&str> = output
+            .lines()
+            .filter(|line| line.starts_with("Uptime "))
+            .collect();
+        assert_eq!(
+            footers,
+            [
+                "Uptime 1s | 1 sample | Ctrl+C to exit",
+                "Uptime 2s | 2 samples | Ctrl+C to exit",
+                "Uptime 3s | 3 samples | Ctrl+C to exit",
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_waits_for_zfs_module_reload() {
+        let args = CliArgs {
+            interval: 1,
+            // Waiting frames don't count
+            count: 4,
+            warmup_ms: 0,
+            // Rates over the paused clock's ticks rather than real time
+            high_precision: true,
+            no_l2arc: true,
+            no_slog: true,
+            ..CliArgs::default()
+        };
+        let buffer = SharedBuffer::default();
+        let mut out = Output {
+            writer: Box::new(buffer.clone()),
+            is_terminal: false,
+        };
+        let collector = ZfsStatsCollector::new(
+            NoArcstatCommandExecutor,
+            ReloadingFilesystemReader(Arc::new(Mutex::new(0))),
+        )
+        .with_pool("data");
+
+        run_monitor_loop(&mut out, &Terminal::new(), vec![collector], "data", &args)
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(output.matches("⏳ Waiting for ZFS").count(), 2);
+        assert!(output.contains("Retrying every 1s"));
+        let read_ops: Vec<&str> = output
+            .lines()
+            .filter_map(|line| line.strip_prefix("    Read Ops:    "))
+            .collect();
+        // Resumed with fresh baselines: 0/s again, then the new counters' rate
+        assert_eq!(read_ops, ["0/s", "1.00K/s", "0/s", "100/s"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_other_errors_end_the_monitor() {
+        let args = CliArgs {
+            interval: 1,
+            count: 3,
+            warmup_ms: 0,
+            ..CliArgs::default()
+        };
+        let mut out = Output {
+            writer: Box::new(io::sink()),
+            is_terminal: false,
+        };
+        // SLOG stats need `zpool iostat`; a failing one won't come back by waiting
+        let collector =
+            ZfsStatsCollector::new(NoIostatCommandExecutor, DemoFilesystemReader).with_pool("data");
+
+        let Err(error) =
+            run_monitor_loop(&mut out, &Terminal::new(), vec![collector], "data", &args).await
+        else {
+            panic!("a failing zpool iostat was waited for");
+        };
+        let error = error.downcast_ref::<ZfsError>().unwrap();
+        assert!(matches!(error, ZfsError::CommandError { .. }), "{}", error);
+        assert!(!arcstats_gone(error));
+        let unloaded = ZfsError::subsystem_unavailable("ARC", "kstats missing");
+        assert!(arcstats_gone(&unloaded));
+    }
+
+    #[tokio::test]
+    async fn test_theme_file_renames_sections() {
+        let path =
+            std::env::temp_dir().join(format!("zpool-rw-meter-theme-{}.toml", std::process::id()));
+        std::fs::write(&path, "[arc]\nlabel = \"[CACHE]\"\ncolor = \"cyan\"\n").unwrap();
+        let args = CliArgs {
+            theme: Theme::load(&path).await.unwrap(),
+            ..CliArgs::default()
+        };
+        std::fs::remove_file(&path).unwrap();
+
+        let terminal = Terminal::with_color_mode(ColorMode::Never);
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+        let mut frame = Vec::new();
+        refresh(
+            &mut frame,
+            &terminal,
+            &mut collector,
+            "data",
+            None,
+            None,
+            &args,
+        )
+        .await
+        .unwrap();
+
+        let output = String::from_utf8(frame).unwrap();
+        assert!(output.contains("\n[CACHE]\n    Hit Rate:"));
+        assert!(!output.contains("ARC (Primary RAM Cache, system-wide)"));
+        // Other sections keep the default labels
+        assert!(output.contains("💾 L2ARC (Secondary SSD Cache)"));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_file_holds_latest_frame() {
+        let path = std::env::temp_dir().join(format!(
+            "zpool-rw-meter-snapshot-{}.json",
+            std::process::id()
+        ));
+        let args = CliArgs {
+            interval: 0,
+            count: 2,
+            warmup_ms: 0,
+            format: OutputFormat::Json,
+            snapshot_file: Some(path.clone()),
+            ..CliArgs::default()
+        };
+        let mut out = Output {
+            writer: Box::new(io::sink()),
+            is_terminal: false,
+        };
+        let collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data");
+
+        run_monitor_loop(&mut out, &Terminal::new(), vec![collector], "data", &args)
+            .await
+            .unwrap();
+
+        // Replaced rather than appended: exactly one complete envelope
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        let snapshot = Snapshot::from_json(contents.trim()).unwrap().unwrap();
+        assert_eq!(snapshot.pool, "data");
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        assert!(!Path::new(&temp).exists());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_renders_captured_frames() {
+        let dir = std::env::temp_dir();
+        let capture = dir.join(format!(
+            "zpool-rw-meter-capture-{}.jsonl",
+            std::process::id()
+        ));
+        let rendered = dir.join(format!("zpool-rw-meter-replay-{}.txt", std::process::id()));
+
+        // Capture two refreshes the way `--format json --output` would
+        let mut collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data");
+        let capture_args = CliArgs {
+            format: OutputFormat::Json,
+            ..CliArgs::default()
+        };
+        let mut lines = Vec::new();
+        for _ in 0..2 {
+            refresh(
+                &mut lines,
+                &Terminal::new(),
+                &mut collector,
+                "data",
+                None,
+                None,
+                &capture_args,
+            )
+            .await
+            .unwrap();
+        }
+        std::fs::write(&capture, &lines).unwrap();
+
+        let args = CliArgs {
+            replay: Some(capture.clone()),
+            output: Some(rendered.clone()),
+            interval: 0,
+            ..CliArgs::default()
+        };
+        let mut out = Output::open(&args).unwrap();
+        replay(&mut out, &Terminal::new(), &capture, &args)
+            .await
+            .unwrap();
+
+        let contents = std::fs::read_to_string(&rendered).unwrap();
+        std::fs::remove_file(&capture).unwrap();
+        std::fs::remove_file(&rendered).unwrap();
+
+        assert_eq!(contents.matches("ZFS Cache Performance Monitor").count(), 2);
+        assert!(contents.contains("Pool: data | Refresh: 0s"));
+        assert!(contents.contains("📊 ARC (Primary RAM Cache, system-wide)"));
+        assert!(contents.contains("🟡 SLOG"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_replay_speed_scales_recorded_gaps() {
+        let capture =
+            std::env::temp_dir().join(format!("zpool-rw-meter-paced-{}.jsonl", std::process::id()));
+        // Frames recorded 4s and then 6s apart
+        let sample = report_sample();
+        let start = DateTime::parse_from_rfc3339("2025-09-14T17:10:08Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let lines: Vec<String> = [0, 4, 10]
+            .into_iter()
+            .map(|offset| {
+                let timestamp = start + chrono::Duration::seconds(offset);
+                metrics_envelope("data", timestamp, &sample.arc, None, None, None).to_string()
+            })
+            .collect();
+        std::fs::write(&capture, lines.join("\n")).unwrap();
+
+        let args = CliArgs {
+            replay: Some(capture.clone()),
+            replay_speed: Some(2.0),
+            format: OutputFormat::Json,
+            ..CliArgs::default()
+        };
+        let mut out = Output {
+            writer: Box::new(io::sink()),
+            is_terminal: false,
+        };
+        let started = tokio::time::Instant::now();
+        replay(&mut out, &Terminal::new(), &capture, &args)
+            .await
+            .unwrap();
+        std::fs::remove_file(&capture).unwrap();
+
+        // Twice as fast: 2s and 3s on the paused clock
+        assert_eq!(started.elapsed(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_replay_delay() {
+        let at = |secs| CollectedAt(std::time::UNIX_EPOCH + Duration::from_secs(secs));
+        assert_eq!(replay_delay(at(10), at(14), 2.0), Duration::from_secs(2));
+        assert_eq!(replay_delay(at(10), at(14), 0.5), Duration::from_secs(8));
+        // A clock stepping back doesn't stall the replay
+        assert_eq!(replay_delay(at(14), at(10), 1.0), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_replay_pause_steps_per_line() {
+        let args = CliArgs {
+            replay_speed: Some(0.0),
+            ..CliArgs::default()
+        };
+        let now = CollectedAt::now();
+        let mut keys: &[u8] = b"\n\n";
+        assert!(replay_pause(&mut keys, now, now, &args).await.unwrap());
+        assert!(replay_pause(&mut keys, now, now, &args).await.unwrap());
+        // Stdin closed: the replay ends instead of spinning
+        assert!(!replay_pause(&mut keys, now, now, &args).await.unwrap());
+    }
+
+    #[test]
+    fn test_load_snapshots_reports_line() {
+        let error = load_snapshots("\n{\"schema_version\":1}\n").unwrap_err();
+        assert!(error.starts_with("line 2: missing field `timestamp`"));
+    }
+
+    #[test]
+    fn test_load_snapshots_skips_errors() {
+        let error = ZfsError::subsystem_unavailable("ARC", "kstats missing");
+        let content = format!("{}\n", error_envelope("data", Utc::now(), &error));
+        assert!(load_snapshots(&content).unwrap().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_json_stream_reports_errors_while_waiting() {
+        let args = CliArgs {
+            format: OutputFormat::Json,
+            interval: 1,
+            count: 3,
+            warmup_ms: 0,
+            no_l2arc: true,
+            no_slog: true,
+            ..CliArgs::default()
+        };
+        let buffer = SharedBuffer::default();
+        let mut out = Output {
+            writer: Box::new(buffer.clone()),
+            is_terminal: false,
+        };
+        let collector = ZfsStatsCollector::new(
+            NoArcstatCommandExecutor,
+            ReloadingFilesystemReader(Arc::new(Mutex::new(0))),
+        )
+        .with_pool("data");
+
+        run_monitor_loop(&mut out, &Terminal::new(), vec![collector], "data", &args)
+            .await
+            .unwrap();
+
+        // Every refresh is one document; those while ZFS is gone have an error
+        // and don't count towards the three
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1].get("metrics").is_some());
+        let error = &lines[2]["error"];
+        assert_eq!(error["kind"], "SubsystemUnavailable");
+        assert_eq!(error["subsystem"], "ARC");
+        assert!(lines[3].get("error").is_some());
+        assert!(lines[4].get("metrics").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_list_pools_demo() {
+        let mut out = Vec::new();
+        list_pools(&DemoCommandExecutor, DEFAULT_COMMAND_TIMEOUT, &mut out)
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(out).unwrap();
+        assert_eq!(output, "boot-pool\ndata\nusb-backup\n");
+    }
+
+    #[tokio::test]
+    async fn test_connect_remote() {
+        // Stands in for an ssh that logs in fine
+        let executor = connect_remote("admin@nas", NoPoolsCommandExecutor, Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert!(executor.execute("zpool", &["list"]).await.is_ok());
+
+        // The demo executor knows no `ssh`, like a host that refuses the login
+        let Err(error) =
+            connect_remote("admin@nas", DemoCommandExecutor, Duration::from_secs(1)).await
+        else {
+            panic!("Expected the login to fail");
+        };
+        assert!(matches!(
+            &error,
+            MonitorError::RemoteUnreachable { destination, .. } if destination == "admin@nas"
+        ));
+        assert!(
+            error
+                .to_string()
+                .starts_with("Cannot reach admin@nas over ssh: ")
+        );
+    }
+
+    #[test]
+    fn test_list_metrics() {
+        let mut out = Vec::new();
+        list_metrics(&mut out, OutputFormat::Text).unwrap();
+        let output = String::from_utf8(out).unwrap();
+        assert_eq!(output.lines().count(), metrics_metadata().len());
+        let first = output.lines().next().unwrap();
+        assert!(first.starts_with("arc.hit_rate "));
+        assert!(first.ends_with("  ARC hit rate"));
+
+        let mut out = Vec::new();
+        list_metrics(&mut out, OutputFormat::Json).unwrap();
+        let metrics: Vec<serde_json::Value> = serde_json::from_slice(&out).unwrap();
+        assert_eq!(metrics.len(), metrics_metadata().len());
+        assert_eq!(metrics[0]["unit"], "percent");
+    }
+}
@@ -1,32 +1,43 @@
+use super::clock::{Clock, SystemClock};
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
 /// A simple time-based cache for expensive operations
 #[derive(Debug)]
-pub struct Cache<T> {
+pub struct Cache<T, C: Clock = SystemClock> {
     data: HashMap<String, CacheEntry<T>>,
     default_ttl: Duration,
+    clock: C,
 }
 
 #[derive(Debug)]
 struct CacheEntry<T> {
     value: T,
+    inserted_at: Instant,
     expires_at: Instant,
 }
 
 impl<T> Cache<T> {
     /// Create a new cache with default TTL
     pub fn new(default_ttl: Duration) -> Self {
+        Self::with_clock(default_ttl, SystemClock)
+    }
+}
+
+impl<T, C: Clock> Cache<T, C> {
+    /// Create a new cache with default TTL, reading the time from `clock`
+    pub fn with_clock(default_ttl: Duration, clock: C) -> Self {
         Self {
             data: HashMap::new(),
             default_ttl,
+            clock,
         }
     }
 
     /// Get a value from cache if it exists and hasn't expired
     pub fn get(&self, key: &str) -> Option<&T> {
         if let Some(entry) = self.data.get(key) {
-            if Instant::now() < entry.expires_at {
+            if self.clock.now() < entry.expires_at {
                 return Some(&entry.value);
             }
         }
@@ -40,11 +51,56 @@ impl<T> Cache<T> {
 
     /// Insert a value into cache with custom TTL
     pub fn insert_with_ttl(&mut self, key: String, value: T, ttl: Duration) {
-        let expires_at = Instant::now() + ttl;
-        self.data.insert(key, CacheEntry { value, expires_at });
+        let inserted_at = self.clock.now();
+        let expires_at = inserted_at + ttl;
+        self.data.insert(
+            key,
+            CacheEntry {
+                value,
+                inserted_at,
+                expires_at,
+            },
+        );
+    }
+
+    /// Number of stored entries, including expired ones that haven't been replaced
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Whether nothing is stored, not even expired entries
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Number of entries that haven't expired yet
+    pub fn valid_len(&self) -> usize {
+        let now = self.clock.now();
+        self.data
+            .values()
+            .filter(|entry| now < entry.expires_at)
+            .count()
     }
 
+    /// Whether every stored entry has expired
+    pub fn is_effectively_empty(&self) -> bool {
+        self.valid_len() == 0
+    }
+
+    /// Drop every entry, e.g. when what was cached no longer applies
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
 
+    /// Get how long ago a still-valid entry was inserted
+    pub fn age(&self, key: &str) -> Option<Duration> {
+        self.get(key)?;
+        let now = self.clock.now();
+        self.data
+            .get(key)
+            .map(|entry| now.duration_since(entry.inserted_at))
+    }
 }
 
 impl<T> Default for Cache<T> {
@@ -57,7 +113,7 @@ impl<T> Default for Cache<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::thread;
+    use crate::system::clock::MockClock;
 
     #[test]
     fn test_cache_insert_and_get() {
@@ -70,14 +126,15 @@ mod tests {
 
     #[test]
     fn test_cache_expiration() {
-        let mut cache = Cache::new(Duration::from_millis(50));
+        let clock = MockClock::new();
+        let mut cache = Cache::with_clock(Duration::from_millis(50), clock.clone());
         cache.insert("test".to_string(), 42);
 
-        // Should still be valid
+        // Should still be valid, right up to the TTL
+        clock.advance(Duration::from_millis(49));
         assert_eq!(cache.get("test"), Some(&42));
 
-        // Wait for expiration
-        thread::sleep(Duration::from_millis(60));
+        clock.advance(Duration::from_millis(1));
 
         // Should be expired
         assert_eq!(cache.get("test"), None);
@@ -85,7 +142,8 @@ mod tests {
 
     #[test]
     fn test_cache_custom_ttl() {
-        let mut cache = Cache::new(Duration::from_secs(1)); // default 1s
+        let clock = MockClock::new();
+        let mut cache = Cache::with_clock(Duration::from_secs(1), clock.clone()); // default 1s
         cache.insert_with_ttl("short".to_string(), 1, Duration::from_millis(50));
         cache.insert_with_ttl("long".to_string(), 2, Duration::from_secs(2));
 
@@ -93,14 +151,52 @@ mod tests {
         assert_eq!(cache.get("short"), Some(&1));
         assert_eq!(cache.get("long"), Some(&2));
 
-        // Wait for short to expire but long to remain
-        thread::sleep(Duration::from_millis(60));
+        // Short expires but long remains
+        clock.advance(Duration::from_millis(60));
 
         assert_eq!(cache.get("short"), None);
         assert_eq!(cache.get("long"), Some(&2));
     }
 
+    #[test]
+    fn test_cache_valid_len() {
+        let clock = MockClock::new();
+        let mut cache = Cache::with_clock(Duration::from_secs(1), clock.clone());
+        assert!(cache.is_empty());
+        assert!(cache.is_effectively_empty());
+
+        cache.insert_with_ttl("short".to_string(), 1, Duration::from_millis(50));
+        cache.insert_with_ttl("long".to_string(), 2, Duration::from_secs(2));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.valid_len(), 2);
+
+        // Short expires: it is still stored, but no longer counts as valid
+        clock.advance(Duration::from_millis(60));
 
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.valid_len(), 1);
+        assert!(!cache.is_effectively_empty());
+
+        cache.insert_with_ttl("long".to_string(), 2, Duration::from_secs(0));
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.is_empty());
+        assert!(cache.is_effectively_empty());
+    }
+
+    #[test]
+    fn test_cache_age() {
+        let clock = MockClock::new();
+        let mut cache = Cache::with_clock(Duration::from_millis(100), clock.clone());
+        assert_eq!(cache.age("test"), None);
+
+        cache.insert("test".to_string(), 42);
+        clock.advance(Duration::from_millis(20));
+        assert_eq!(cache.age("test"), Some(Duration::from_millis(20)));
+
+        // Expired entries have no age
+        clock.advance(Duration::from_millis(80));
+        assert_eq!(cache.age("test"), None);
+    }
 
     #[test]
     fn test_cache_overwrite() {
@@ -119,8 +215,6 @@ mod tests {
         assert_eq!(cache.get("test"), Some(&300));
     }
 
-
-
     #[test]
     fn test_cache_with_complex_types() {
         let mut cache: Cache<Vec<String>> = Cache::new(Duration::from_secs(1));
@@ -137,21 +231,17 @@ mod tests {
 
     #[test]
     fn test_cache_very_short_ttl() {
-        let mut cache = Cache::new(Duration::from_nanos(1)); // Very short TTL
+        let clock = MockClock::new();
+        let mut cache = Cache::with_clock(Duration::from_nanos(1), clock.clone()); // Very short TTL
 
         cache.insert("test".to_string(), 42);
+        assert_eq!(cache.get("test"), Some(&42));
 
-        // Should be expired immediately (or very soon)
-        thread::sleep(Duration::from_nanos(10));
+        // Expired one nanosecond later
+        clock.advance(Duration::from_nanos(1));
         assert_eq!(cache.get("test"), None);
     }
 
-
-
-
-
-
-
     #[test]
     fn test_cache_default_ttl() {
         // Default should be 30 seconds
@@ -0,0 +1,62 @@
+#[cfg(test)]
+use std::time::Duration;
+use std::time::Instant;
+
+/// Abstraction for reading the current time so expiry can be tested without sleeping
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// Real clock backed by `Instant::now()`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Test clock that only moves when advanced; clones share the same time
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: std::sync::Arc<std::sync::Mutex<Instant>>,
+}
+
+#[cfg(test)]
+impl MockClock {
+    pub fn new() -> Self {
+        Self {
+            now: std::sync::Arc::new(std::sync::Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Move the clock forward by `by`
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_advances_shared_time() {
+        let clock = MockClock::new();
+        let handle = clock.clone();
+        let start = clock.now();
+
+        assert_eq!(clock.now(), start);
+        handle.advance(Duration::from_secs(5));
+        assert_eq!(clock.now(), start + Duration::from_secs(5));
+    }
+}
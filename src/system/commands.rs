@@ -1,3 +1,4 @@
+use crate::demo::SharedDemoData;
 use async_trait::async_trait;
 use std::error::Error;
 use std::process::Stdio;
@@ -5,51 +6,184 @@ use std::time::Duration;
 use tokio::process::Command as TokioCommand;
 use tokio::time;
 
+/// How long a command may run before collection gives up on it
+pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Error of a command that didn't finish within the given time
+#[derive(Debug)]
+pub struct CommandTimeout(pub Duration);
+
+impl std::fmt::Display for CommandTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Command timed out after {:?}", self.0)
+    }
+}
+
+impl Error for CommandTimeout {}
+
 /// Abstraction for command execution to enable testing without real commands
 #[async_trait]
-pub trait CommandExecutor {
+pub trait CommandExecutor: Sync {
     async fn execute(&self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>>;
+
+    /// Like `execute`, but fails with `CommandTimeout` once `timeout_duration` passes
     async fn execute_with_timeout(
         &self,
         command: &str,
         args: &[&str],
         timeout_duration: Duration,
-    ) -> Result<String, Box<dyn Error>>;
+    ) -> Result<String, Box<dyn Error>> {
+        match time::timeout(timeout_duration, self.execute(command, args)).await {
+            Ok(output) => output,
+            Err(_) => Err(Box::new(CommandTimeout(timeout_duration))),
+        }
+    }
 }
 
 /// Real command executor using std::process::Command
-pub struct RealCommandExecutor;
+#[derive(Debug, Clone, Default)]
+pub struct RealCommandExecutor {
+    prefix: Vec<String>,
+}
+
+impl RealCommandExecutor {
+    /// Run every command through `prefix`, e.g. `nsenter -t 1 -m --` to reach
+    /// the host's ZFS tools from a container
+    pub fn with_prefix(prefix: Vec<String>) -> Self {
+        Self { prefix }
+    }
+
+    /// The program and arguments actually run for `command args`
+    fn argv(&self, command: &str, args: &[&str]) -> Vec<String> {
+        self.prefix
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(command))
+            .chain(args.iter().copied())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn command(&self, command: &str, args: &[&str]) -> TokioCommand {
+        let argv = self.argv(command, args);
+        let mut command = TokioCommand::new(&argv[0]);
+        // Under e.g. de_DE zpool and arcstat print "1,5M"; the C locale keeps
+        // their output in the format the parsers expect
+        command.args(&argv[1..]).env("LC_ALL", "C").env("LANG", "C");
+        // A command given up on after its timeout mustn't linger, e.g. a
+        // `zpool` stuck on a hung device piling up every refresh
+        command.kill_on_drop(true);
+        command
+    }
+}
 
 #[async_trait]
 impl CommandExecutor for RealCommandExecutor {
     async fn execute(&self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
-        let output = TokioCommand::new(command)
-            .args(args)
+        let output = self
+            .command(command, args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .output()
             .await?;
 
         if output.status.success() {
-            Ok(String::from_utf8(output.stdout)?)
+            Ok(decode_output(command, &output.stdout))
         } else {
-            let stderr = String::from_utf8(output.stderr)?;
+            let stderr = decode_output(command, &output.stderr);
             Err(format!("Command failed: {}", stderr).into())
         }
     }
+}
 
-    async fn execute_with_timeout(
-        &self,
-        command: &str,
-        args: &[&str],
-        timeout_duration: Duration,
-    ) -> Result<String, Box<dyn Error>> {
-        let result = time::timeout(timeout_duration, self.execute(command, args)).await;
-        match result {
-            Ok(output) => output,
-            Err(_) => Err(format!("Command timed out after {:?}", timeout_duration).into()),
+/// Runs every command on another host through `ssh`, e.g. the NAS the pool
+/// lives on. `BatchMode` keeps ssh from prompting for a password over the
+/// dashboard, so the login has to work with a key or agent.
+#[derive(Debug, Clone)]
+pub struct RemoteCommandExecutor<E = RealCommandExecutor> {
+    destination: String,
+    inner: E,
+}
+
+impl<E: CommandExecutor> RemoteCommandExecutor<E> {
+    /// Run commands on `destination` (`user@host` or `host`) via `ssh`, which
+    /// `inner` runs locally
+    pub fn new(destination: &str, inner: E) -> Self {
+        Self {
+            destination: destination.to_string(),
+            inner,
+        }
+    }
+
+    /// The ssh arguments for `command args`. The remote shell splits the
+    /// command line again, so each word is quoted. ssh doesn't pass on our
+    /// environment, so `env` puts the remote command in the C locale, as
+    /// `RealCommandExecutor` does locally.
+    fn ssh_args(&self, command: &str, args: &[&str]) -> Vec<String> {
+        ["-o", "BatchMode=yes", self.destination.as_str()]
+            .into_iter()
+            .map(str::to_string)
+            .chain(
+                ["env", "LC_ALL=C", "LANG=C", command]
+                    .into_iter()
+                    .chain(args.iter().copied())
+                    .map(shell_quote),
+            )
+            .collect()
+    }
+
+    /// Log in and run `true`, so an unreachable host or a refused login
+    /// shows up once, before the first refresh
+    pub async fn check_connection(&self, timeout: Duration) -> Result<(), Box<dyn Error>> {
+        self.execute_with_timeout("true", &[], timeout).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<E: CommandExecutor> CommandExecutor for RemoteCommandExecutor<E> {
+    async fn execute(&self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+        let ssh_args = self.ssh_args(command, args);
+        let ssh_args: Vec<&str> = ssh_args.iter().map(String::as_str).collect();
+        self.inner.execute("ssh", &ssh_args).await
+    }
+}
+
+/// Quote `word` for a POSIX shell, leaving plain words (pool names, paths,
+/// flags) as they are
+fn shell_quote(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=,%+@".contains(c));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// Decode command output, replacing invalid UTF-8 (e.g. an odd device name in
+/// `zpool status`) with U+FFFD rather than failing the whole collection
+pub fn decode_output(command: &str, bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    if let std::borrow::Cow::Owned(_) = text {
+        log::debug!("`{}` printed invalid UTF-8, replaced with U+FFFD", command);
+    }
+    text.into_owned()
+}
+
+/// Whether a `CommandExecutor` error means we lacked the privileges to run it:
+/// either spawning failed with EACCES, or the command itself said so on stderr
+/// (e.g. zpool's "Permission denied the ZFS utilities must be run as root.")
+pub fn is_permission_denied(error: &(dyn Error + 'static)) -> bool {
+    if let Some(io_error) = error.downcast_ref::<std::io::Error>() {
+        if io_error.kind() == std::io::ErrorKind::PermissionDenied {
+            return true;
         }
     }
+    let message = error.to_string().to_lowercase();
+    message.contains("permission denied") || message.contains("must be run as root")
 }
 
 /// Demo command executor that returns predefined responses
@@ -59,13 +193,28 @@ impl DemoCommandExecutor {
     fn get_demo_response(&self, command: &str, args: &[&str]) -> Option<&'static str> {
         match (command, args) {
             ("zpool", ["list", "-H", "-o", "name"]) => Some("boot-pool\ndata\nusb-backup\n"),
-            ("zpool", ["status"]) => Some(include_str!("../demo/zpool_status.txt")),
-            ("zpool", ["iostat", "-v"]) => Some(include_str!("../demo/zpool_iostat.txt")),
+            ("zpool", ["status", ..]) => Some(include_str!("../demo/zpool_status.txt")),
+            ("zpool", ["iostat", "-H", "-p"]) => Some(
+                "boot-pool\t2147483648\t30064771072\t3\t1\t65536\t16384\n\
+                 data\t3793315160064\t2803752878080\t47\t23\t245366784\t12582912\n\
+                 usb-backup\t824633720832\t1099511627776\t0\t0\t0\t0\n",
+            ),
+            ("zpool", ["iostat", "-w", ..]) => {
+                Some(include_str!("../demo/zpool_iostat_latency.txt"))
+            }
+            ("zpool", ["iostat", "-v", "-H", "-p", ..]) => {
+                Some(include_str!("../demo/zpool_iostat_parseable.txt"))
+            }
+            ("zpool", ["iostat", "-v", ..]) => Some(include_str!("../demo/zpool_iostat.txt")),
+            ("zfs", ["get", "-H", "-o", "value", "objsetid", "data/home"]) => Some("54\n"),
             ("arcstat", ["-f", "hit%,miss%,read,arcsz,c", "1", "1"]) => {
                 Some("100.0 0.0 1247 49720066048 49910562816\n")
             }
+            ("arcstat", ["-f", "l2hit%,l2miss%,l2size,l2read", "1", "1"]) => {
+                Some("l2hit% l2miss% l2size l2read\n    73      26    553G     12\n")
+            }
             ("arcstat", ["1", "1"]) => Some("100.0 0.0 1247 49720066048 49910562816\n"),
-            ("echo", ["|", "arcstat"]) => Some("100.0 0.0 1247 49720066048 49910562816\n"),
+            ("arcstat.py", ["1", "1"]) => Some("100.0 0.0 1247 49720066048 49910562816\n"),
             _ => None,
         }
     }
@@ -80,13 +229,291 @@ impl CommandExecutor for DemoCommandExecutor {
             Err(format!("Demo: Command not mocked: {} {:?}", command, args).into())
         }
     }
+}
+
+/// Demo command executor whose `zpool iostat` counters advance over time
+pub struct DynamicDemoCommandExecutor {
+    data: SharedDemoData,
+}
+
+impl DynamicDemoCommandExecutor {
+    pub fn new(data: SharedDemoData) -> Self {
+        Self { data }
+    }
+}
+
+#[async_trait]
+impl CommandExecutor for DynamicDemoCommandExecutor {
+    async fn execute(&self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+        match (command, args) {
+            ("zpool", ["iostat", "-v", "-H", "-p", rest @ ..]) => {
+                // An interval sample ends in its interval and count
+                let sampled = rest.ends_with(&["1", "2"]);
+                Ok(self.data.zpool_iostat_parseable(sampled))
+            }
+            _ => DemoCommandExecutor.execute(command, args).await,
+        }
+    }
+}
+
+/// A recorded invocation: command name and its arguments
+#[cfg(test)]
+pub type CommandCall = (String, Vec<String>);
+
+/// Test executor for a system with ZFS loaded but no pool imported
+#[cfg(test)]
+pub struct NoPoolsCommandExecutor;
+
+#[cfg(test)]
+#[async_trait]
+impl CommandExecutor for NoPoolsCommandExecutor {
+    async fn execute(&self, _command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+        match args {
+            ["status", ..] => Ok("no pools available\n".to_string()),
+            _ => Ok(String::new()),
+        }
+    }
+}
+
+/// Test executor for an unprivileged user: `zpool` refuses to run
+#[cfg(test)]
+pub struct UnprivilegedCommandExecutor;
+
+#[cfg(test)]
+#[async_trait]
+impl CommandExecutor for UnprivilegedCommandExecutor {
+    async fn execute(&self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+        match command {
+            "zpool" => Err(
+                "Command failed: Permission denied the ZFS utilities must be run as root.\n".into(),
+            ),
+            _ => DemoCommandExecutor.execute(command, args).await,
+        }
+    }
+}
+
+/// Shared log of invocations seen by a `RecordingCommandExecutor`
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct CommandLog(std::sync::Arc<std::sync::Mutex<Vec<CommandCall>>>);
+
+#[cfg(test)]
+impl CommandLog {
+    /// All invocations so far, oldest first
+    pub fn calls(&self) -> Vec<CommandCall> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Number of invocations of `command`
+    pub fn count(&self, command: &str) -> usize {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(cmd, _)| cmd == command)
+            .count()
+    }
+
+    fn record(&self, command: &str, args: &[&str]) {
+        self.0.lock().unwrap().push((
+            command.to_string(),
+            args.iter().map(|arg| arg.to_string()).collect(),
+        ));
+    }
+}
+
+/// Test executor that delegates to `inner` while recording every invocation
+#[cfg(test)]
+pub struct RecordingCommandExecutor<E> {
+    inner: E,
+    log: CommandLog,
+}
+
+#[cfg(test)]
+impl<E: CommandExecutor> RecordingCommandExecutor<E> {
+    pub fn new(inner: E) -> Self {
+        Self {
+            inner,
+            log: CommandLog::default(),
+        }
+    }
+
+    /// Handle to the call log that stays valid after the executor is moved
+    pub fn log(&self) -> CommandLog {
+        self.log.clone()
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl<E: CommandExecutor + Sync> CommandExecutor for RecordingCommandExecutor<E> {
+    async fn execute(&self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+        self.log.record(command, args);
+        self.inner.execute(command, args).await
+    }
 
     async fn execute_with_timeout(
         &self,
         command: &str,
         args: &[&str],
-        _timeout: Duration,
+        timeout_duration: Duration,
     ) -> Result<String, Box<dyn Error>> {
-        self.execute(command, args).await
+        self.log.record(command, args);
+        self.inner
+            .execute_with_timeout(command, args, timeout_duration)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_prefix_argv() {
+        let executor = RealCommandExecutor::with_prefix(vec![
+            "nsenter".to_string(),
+            "-t".to_string(),
+            "1".to_string(),
+            "-m".to_string(),
+            "--".to_string(),
+        ]);
+        assert_eq!(
+            executor.argv("zpool", &["status", "data"]),
+            ["nsenter", "-t", "1", "-m", "--", "zpool", "status", "data"]
+        );
+        assert_eq!(
+            RealCommandExecutor::default().argv("zpool", &["list"]),
+            ["zpool", "list"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_prefix() {
+        // `env` runs the rest of its arguments as a command, like nsenter or chroot
+        let executor = RealCommandExecutor::with_prefix(vec!["env".to_string()]);
+        let output = executor.execute("echo", &["data"]).await.unwrap();
+        assert_eq!(output, "data\n");
+    }
+
+    #[tokio::test]
+    async fn test_execute_in_c_locale() {
+        let output = RealCommandExecutor::default()
+            .execute("sh", &["-c", "echo $LC_ALL $LANG"])
+            .await
+            .unwrap();
+        assert_eq!(output, "C C\n");
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_command_is_killed() {
+        let pid_file =
+            std::env::temp_dir().join(format!("zpool-rw-meter-{}.pid", std::process::id()));
+        let script = format!("echo $$ > {}; exec sleep 30", pid_file.display());
+        let result = RealCommandExecutor::default()
+            .execute_with_timeout("sh", &["-c", &script], Duration::from_millis(200))
+            .await;
+        assert!(result.unwrap_err().is::<CommandTimeout>());
+
+        // Killed, and at most waiting to be reaped
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        std::fs::remove_file(&pid_file).unwrap();
+        let stat = format!("/proc/{}/stat", pid.trim());
+        let mut running = true;
+        for _ in 0..50 {
+            running = std::fs::read_to_string(&stat).is_ok_and(|stat| !stat.contains(") Z "));
+            if !running {
+                break;
+            }
+            time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(!running, "sleep is still running");
+    }
+
+    #[tokio::test]
+    async fn test_remote_executor_wraps_commands_in_ssh() {
+        let recording = RecordingCommandExecutor::new(DemoCommandExecutor);
+        let log = recording.log();
+        let executor = RemoteCommandExecutor::new("admin@nas", recording);
+
+        // The demo executor has no answer for `ssh`, only the call matters here
+        let _ = executor.execute("zpool", &["status", "tank"]).await;
+        let _ = executor
+            .execute("zfs", &["get", "objsetid", "tank/my data"])
+            .await;
+        let commands: Vec<String> = log
+            .calls()
+            .into_iter()
+            .map(|(command, args)| format!("{} {}", command, args.join(" ")))
+            .collect();
+        assert_eq!(
+            commands,
+            [
+                "ssh -o BatchMode=yes admin@nas env LC_ALL=C LANG=C zpool status tank",
+                "ssh -o BatchMode=yes admin@nas env LC_ALL=C LANG=C zfs get objsetid 'tank/my data'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(
+            shell_quote("/proc/spl/kstat/zfs/arcstats"),
+            "/proc/spl/kstat/zfs/arcstats"
+        );
+        assert_eq!(shell_quote("hit%,miss%"), "hit%,miss%");
+        assert_eq!(shell_quote("my pool"), "'my pool'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote("$(reboot)"), "'$(reboot)'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn test_decode_output() {
+        assert_eq!(decode_output("zpool", b"data  ONLINE\n"), "data  ONLINE\n");
+        assert_eq!(
+            decode_output("zpool", b"ata-\xffdisk  ONLINE\n"),
+            "ata-\u{FFFD}disk  ONLINE\n"
+        );
+    }
+
+    #[test]
+    fn test_is_permission_denied() {
+        let eacces: Box<dyn Error> =
+            Box::new(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        assert!(is_permission_denied(eacces.as_ref()));
+
+        let stderr: Box<dyn Error> =
+            "Command failed: Permission denied the ZFS utilities must be run as root.\n".into();
+        assert!(is_permission_denied(stderr.as_ref()));
+
+        let missing: Box<dyn Error> = Box::new(std::io::Error::from(std::io::ErrorKind::NotFound));
+        assert!(!is_permission_denied(missing.as_ref()));
+        let other: Box<dyn Error> = "Command failed: cannot open 'tank': no such pool".into();
+        assert!(!is_permission_denied(other.as_ref()));
+    }
+
+    #[tokio::test]
+    async fn test_recording_executor_logs_calls() {
+        let executor = RecordingCommandExecutor::new(DemoCommandExecutor);
+        let log = executor.log();
+
+        executor.execute("zpool", &["status"]).await.unwrap();
+        let _ = executor
+            .execute_with_timeout("arcstat", &["1", "1"], Duration::from_secs(1))
+            .await;
+
+        assert_eq!(
+            log.calls(),
+            vec![
+                ("zpool".to_string(), vec!["status".to_string()]),
+                (
+                    "arcstat".to_string(),
+                    vec!["1".to_string(), "1".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(log.count("zpool"), 1);
+        assert_eq!(log.count("sysctl"), 0);
     }
 }
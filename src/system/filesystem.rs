@@ -1,16 +1,93 @@
+use super::commands::{CommandExecutor, RemoteCommandExecutor};
+use crate::demo::SharedDemoData;
+use async_trait::async_trait;
 use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Abstraction for filesystem access to enable testing without real files
-pub trait FilesystemReader {
-    fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>>;
+#[async_trait]
+pub trait FilesystemReader: Sync {
+    async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>>;
+
+    /// Whether a file exists and can be read
+    async fn exists(&self, path: &str) -> bool {
+        self.read_to_string(path).await.is_ok()
+    }
+
+    /// Read the target of a symbolic link
+    async fn read_link(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        Err(format!("Symlinks not supported: {}", path).into())
+    }
+}
+
+/// Real filesystem reader using tokio::fs so reads don't block the runtime
+#[derive(Debug, Clone, Default)]
+pub struct RealFilesystemReader {
+    root: Option<PathBuf>,
 }
 
-/// Real filesystem reader using std::fs
-pub struct RealFilesystemReader;
+impl RealFilesystemReader {
+    /// Read every path below `root` instead of `/`, e.g. the host's /proc
+    /// mounted at `/host/proc` inside a container
+    pub fn with_root(root: Option<PathBuf>) -> Self {
+        Self { root }
+    }
+
+    /// Where `path` is actually read from
+    fn resolve(&self, path: &str) -> PathBuf {
+        match &self.root {
+            Some(root) => root.join(path.trim_start_matches('/')),
+            None => Path::new(path).to_path_buf(),
+        }
+    }
+}
 
+#[async_trait]
 impl FilesystemReader for RealFilesystemReader {
-    fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
-        Ok(std::fs::read_to_string(path)?)
+    async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        Ok(tokio::fs::read_to_string(self.resolve(path)).await?)
+    }
+
+    async fn read_link(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        Ok(tokio::fs::read_link(self.resolve(path))
+            .await?
+            .to_string_lossy()
+            .into_owned())
+    }
+}
+
+/// Reads files of another host with `ssh HOST cat PATH`, alongside a
+/// `RemoteCommandExecutor` for the same host, whose C locale the `cat` and
+/// `readlink` calls share
+#[derive(Debug, Clone)]
+pub struct RemoteFilesystemReader<E> {
+    executor: RemoteCommandExecutor<E>,
+    timeout: Duration,
+}
+
+impl<E: CommandExecutor> RemoteFilesystemReader<E> {
+    /// Give up on a read after `timeout`, like any other command, so a stalled
+    /// connection can't hang the refresh
+    pub fn new(executor: RemoteCommandExecutor<E>, timeout: Duration) -> Self {
+        Self { executor, timeout }
+    }
+}
+
+#[async_trait]
+impl<E: CommandExecutor> FilesystemReader for RemoteFilesystemReader<E> {
+    async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        self.executor
+            .execute_with_timeout("cat", &[path], self.timeout)
+            .await
+    }
+
+    async fn read_link(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        let target = self
+            .executor
+            .execute_with_timeout("readlink", &[path], self.timeout)
+            .await?;
+        Ok(target.trim_end().to_string())
     }
 }
 
@@ -21,13 +98,17 @@ impl DemoFilesystemReader {
     fn get_demo_content(&self, path: &str) -> Option<&'static str> {
         match path {
             "/proc/spl/kstat/zfs/arcstats" => Some(include_str!("../demo/arcstats.txt")),
+            "/proc/meminfo" => Some(include_str!("../demo/meminfo.txt")),
+            "/proc/uptime" => Some(include_str!("../demo/uptime.txt")),
+            "/proc/spl/kstat/zfs/data/objset-0x36" => Some(include_str!("../demo/objset.txt")),
             _ => None,
         }
     }
 }
 
+#[async_trait]
 impl FilesystemReader for DemoFilesystemReader {
-    fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+    async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
         if let Some(content) = self.get_demo_content(path) {
             Ok(content.to_string())
         } else {
@@ -35,3 +116,221 @@ impl FilesystemReader for DemoFilesystemReader {
         }
     }
 }
+
+/// Demo filesystem reader whose kstat counters advance over time
+pub struct DynamicDemoFilesystemReader {
+    data: SharedDemoData,
+}
+
+impl DynamicDemoFilesystemReader {
+    pub fn new(data: SharedDemoData) -> Self {
+        Self { data }
+    }
+}
+
+#[async_trait]
+impl FilesystemReader for DynamicDemoFilesystemReader {
+    async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        match path {
+            "/proc/spl/kstat/zfs/arcstats" => Ok(self.data.arcstats()),
+            "/proc/uptime" => Ok(self.data.uptime()),
+            _ => DemoFilesystemReader.read_to_string(path).await,
+        }
+    }
+}
+
+/// Test reader that delegates to `inner` while recording every path read
+#[cfg(test)]
+pub struct RecordingFilesystemReader<F> {
+    inner: F,
+    log: ReadLog,
+}
+
+#[cfg(test)]
+impl<F: FilesystemReader> RecordingFilesystemReader<F> {
+    pub fn new(inner: F) -> Self {
+        Self {
+            inner,
+            log: ReadLog::default(),
+        }
+    }
+
+    /// Handle to the read log that stays valid after the reader is moved
+    pub fn log(&self) -> ReadLog {
+        self.log.clone()
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl<F: FilesystemReader> FilesystemReader for RecordingFilesystemReader<F> {
+    async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        self.log.0.lock().unwrap().push(path.to_string());
+        self.inner.read_to_string(path).await
+    }
+
+    async fn read_link(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        self.inner.read_link(path).await
+    }
+}
+
+/// Shared log of paths read through a `RecordingFilesystemReader`
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct ReadLog(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+#[cfg(test)]
+impl ReadLog {
+    /// Number of reads of `path`
+    pub fn count(&self, path: &str) -> usize {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|read| *read == path)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::commands::{CommandTimeout, RecordingCommandExecutor};
+
+    #[tokio::test]
+    async fn test_demo_reader_async_read() {
+        let content = DemoFilesystemReader
+            .read_to_string("/proc/spl/kstat/zfs/arcstats")
+            .await
+            .unwrap();
+        assert!(content.contains("l2_size"));
+
+        let result = DemoFilesystemReader.read_to_string("/nonexistent").await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_demo_reader() {
+        let reader = DynamicDemoFilesystemReader::new(SharedDemoData::new(crate::demo::DEMO_SEED));
+
+        let content = reader
+            .read_to_string("/proc/spl/kstat/zfs/arcstats")
+            .await
+            .unwrap();
+        assert!(content.contains("l2_size"));
+        assert!(reader.read_to_string("/nonexistent").await.is_err());
+    }
+
+    /// Stands in for `ssh` on a host whose only file is its arcstats
+    struct FakeSsh;
+
+    #[async_trait]
+    impl CommandExecutor for FakeSsh {
+        async fn execute(&self, _command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+            match args {
+                [.., "cat", "/proc/spl/kstat/zfs/arcstats"] => Ok("hits 4 42\n".to_string()),
+                [.., "readlink", "/dev/disk/by-id/nvme-slog"] => Ok("../../nvme0n1\n".to_string()),
+                [.., path] => Err(format!("Command failed: cat: {}: No such file", path).into()),
+                [] => Err("Command failed: no command".into()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remote_reader_reads_through_ssh_cat() {
+        let ssh = RecordingCommandExecutor::new(FakeSsh);
+        let log = ssh.log();
+        let reader = RemoteFilesystemReader::new(
+            RemoteCommandExecutor::new("admin@nas", ssh),
+            Duration::from_secs(5),
+        );
+
+        let content = reader
+            .read_to_string("/proc/spl/kstat/zfs/arcstats")
+            .await
+            .unwrap();
+        assert_eq!(content, "hits 4 42\n");
+        let (command, args) = &log.calls()[0];
+        assert_eq!(command, "ssh");
+        assert_eq!(
+            args.join(" "),
+            "-o BatchMode=yes admin@nas env LC_ALL=C LANG=C cat /proc/spl/kstat/zfs/arcstats"
+        );
+
+        assert!(!reader.exists("/proc/meminfo").await);
+        assert_eq!(
+            reader.read_link("/dev/disk/by-id/nvme-slog").await.unwrap(),
+            "../../nvme0n1"
+        );
+        let (_, args) = log.calls().pop().unwrap();
+        assert_eq!(
+            args.join(" "),
+            "-o BatchMode=yes admin@nas env LC_ALL=C LANG=C readlink /dev/disk/by-id/nvme-slog"
+        );
+    }
+
+    /// Stands in for `ssh` to a host that stopped answering
+    struct HangingSsh;
+
+    #[async_trait]
+    impl CommandExecutor for HangingSsh {
+        async fn execute(&self, _command: &str, _args: &[&str]) -> Result<String, Box<dyn Error>> {
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_remote_reader_times_out() {
+        let reader = RemoteFilesystemReader::new(
+            RemoteCommandExecutor::new("admin@nas", HangingSsh),
+            Duration::from_secs(3),
+        );
+
+        let error = reader
+            .read_to_string("/proc/spl/kstat/zfs/arcstats")
+            .await
+            .unwrap_err();
+        assert!(error.is::<CommandTimeout>());
+        assert!(reader.read_link("/dev/disk/by-id/nvme-slog").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_real_reader_async_read() {
+        let path = std::env::temp_dir().join(format!("zfs-monitor-fs-{}", std::process::id()));
+        std::fs::write(&path, "hits 4 42\n").unwrap();
+
+        let content = RealFilesystemReader::default()
+            .read_to_string(path.to_str().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(content, "hits 4 42\n");
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(
+            RealFilesystemReader::default()
+                .read_to_string(path.to_str().unwrap())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_real_reader_with_root() {
+        let root = std::env::temp_dir().join(format!("zfs-monitor-root-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("proc/spl/kstat/zfs")).unwrap();
+        std::fs::write(root.join("proc/spl/kstat/zfs/arcstats"), "hits 4 7\n").unwrap();
+
+        let reader = RealFilesystemReader::with_root(Some(root.clone()));
+        let content = reader
+            .read_to_string("/proc/spl/kstat/zfs/arcstats")
+            .await
+            .unwrap();
+        assert_eq!(content, "hits 4 7\n");
+        assert!(reader.exists("/proc/spl/kstat/zfs/arcstats").await);
+        assert!(!reader.exists("/proc/meminfo").await);
+        // There, but not a file that can be read
+        assert!(!reader.exists("/proc/spl/kstat/zfs").await);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 //! System interface abstractions for testing and development
 
 pub mod cache;
+pub mod clock;
 pub mod commands;
 pub mod filesystem;
 
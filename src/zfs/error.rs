@@ -1,4 +1,6 @@
+use crate::system::commands::CommandTimeout;
 use std::fmt;
+use std::time::Duration;
 
 /// Errors that can occur during ZFS statistics collection and parsing
 #[derive(Debug)]
@@ -10,6 +12,13 @@ pub enum ZfsError {
         source: Box<dyn std::error::Error + Send + Sync>,
     },
 
+    /// Command didn't finish in time, e.g. `zpool` stuck on a hung device
+    TimeoutError {
+        command: String,
+        args: Vec<String>,
+        timeout: Duration,
+    },
+
     /// File system operation failed
     FilesystemError {
         path: String,
@@ -18,10 +27,7 @@ pub enum ZfsError {
     },
 
     /// Parsing failed for a specific data source
-    ParseError {
-        data_source: String,
-        reason: String,
-    },
+    ParseError { data_source: String, reason: String },
 
     /// Invalid or unexpected data format
     InvalidFormat {
@@ -40,6 +46,19 @@ impl fmt::Display for ZfsError {
             ZfsError::CommandError { command, args, .. } => {
                 write!(f, "Command failed: {} {:?}", command, args)
             }
+            ZfsError::TimeoutError {
+                command,
+                args,
+                timeout,
+            } => {
+                write!(
+                    f,
+                    "Command timed out after {}s: {} {:?}",
+                    timeout.as_secs_f64(),
+                    command,
+                    args
+                )
+            }
             ZfsError::FilesystemError {
                 path, operation, ..
             } => {
@@ -92,6 +111,23 @@ impl ZfsError {
         }
     }
 
+    /// Create a command error from what the executor returned, keeping a
+    /// timeout apart from other failures
+    pub fn command_failed(
+        command: &str,
+        args: &[&str],
+        error: &(dyn std::error::Error + 'static),
+    ) -> Self {
+        match error.downcast_ref::<CommandTimeout>() {
+            Some(CommandTimeout(timeout)) => ZfsError::TimeoutError {
+                command: command.to_string(),
+                args: args.iter().map(|s| s.to_string()).collect(),
+                timeout: *timeout,
+            },
+            None => Self::command_error(command, args, &error.to_string()),
+        }
+    }
+
     /// Create a filesystem error
     pub fn filesystem_error(path: &str, operation: &str, message: &str) -> Self {
         ZfsError::FilesystemError {
@@ -129,8 +165,67 @@ impl ZfsError {
         }
     }
 
-
+    /// Stable name of the variant, for machine-readable output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ZfsError::CommandError { .. } => "CommandError",
+            ZfsError::TimeoutError { .. } => "TimeoutError",
+            ZfsError::FilesystemError { .. } => "FilesystemError",
+            ZfsError::ParseError { .. } => "ParseError",
+            ZfsError::InvalidFormat { .. } => "InvalidFormat",
+            ZfsError::SubsystemUnavailable { .. } => "SubsystemUnavailable",
+        }
+    }
 }
 
 /// Result type alias for ZFS operations
 pub type ZfsResult<T> = Result<T, ZfsError>;
+
+/// Turn the error of a filesystem operation into a [`ZfsError::FilesystemError`]
+/// naming the path and operation, e.g.
+/// `reader.read_to_string(path).await.with_fs_context(path, "read")?`
+pub trait FsContext<T> {
+    fn with_fs_context(self, path: &str, operation: &str) -> ZfsResult<T>;
+}
+
+impl<T, E: fmt::Display> FsContext<T> for Result<T, E> {
+    fn with_fs_context(self, path: &str, operation: &str) -> ZfsResult<T> {
+        self.map_err(|e| ZfsError::filesystem_error(path, operation, &e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn test_with_fs_context() {
+        let result: Result<(), io::Error> = Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "permission denied",
+        ));
+
+        let error = result
+            .with_fs_context("/proc/spl/kstat/zfs/arcstats", "read")
+            .unwrap_err();
+        let ZfsError::FilesystemError {
+            path,
+            operation,
+            source,
+        } = &error
+        else {
+            panic!("Expected a filesystem error, got {:?}", error);
+        };
+        assert_eq!(path, "/proc/spl/kstat/zfs/arcstats");
+        assert_eq!(operation, "read");
+        assert_eq!(source.to_string(), "permission denied");
+        assert_eq!(
+            error.to_string(),
+            "Filesystem read failed for path: /proc/spl/kstat/zfs/arcstats"
+        );
+
+        let ok: Result<u64, Box<dyn std::error::Error>> = Ok(7);
+        assert_eq!(ok.with_fs_context("/proc/meminfo", "read").unwrap(), 7);
+    }
+}
@@ -1,11 +1,19 @@
 //! ZFS statistics collection and data structures
 
 pub mod error;
+pub mod peak_tracker;
 pub mod pools;
 pub mod rate_calculator;
+pub mod score;
 pub mod stats;
 pub mod types;
 
 // Re-export commonly used items
+pub use peak_tracker::percent_of_peak;
+pub use pools::PoolManager;
 pub use stats::ZfsStatsCollector;
-pub use types::{ArcStats, CacheStatus, L2ArcStats, SlogStats};
+pub use types::{
+    ArcEviction, ArcHitBreakdown, ArcMetadata, ArcPressure, ArcStats, ArcstatReads, CacheStatus,
+    CacheThresholds, CollectedAt, DatasetStats, L2ArcStats, LatencyPercentiles, PoolIoStats,
+    SlogStats, Trend,
+};
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+/// Tracks the highest value observed per metric during the session
+#[derive(Debug, Default)]
+pub struct PeakTracker {
+    peaks: HashMap<String, u64>,
+}
+
+impl PeakTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a value and return the peak for the metric including it
+    pub fn observe(&mut self, key: &str, value: u64) -> u64 {
+        let peak = self.peaks.entry(key.to_string()).or_insert(value);
+        *peak = (*peak).max(value);
+        *peak
+    }
+}
+
+/// How close `current` is to `peak`, in percent.
+/// The first non-zero sample sets the peak and therefore reads 100%.
+pub fn percent_of_peak(current: u64, peak: u64) -> f64 {
+    if peak == 0 {
+        return 0.0;
+    }
+    current as f64 / peak as f64 * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observe_keeps_maximum() {
+        let mut tracker = PeakTracker::new();
+
+        assert_eq!(tracker.observe("bw", 100), 100);
+        assert_eq!(tracker.observe("bw", 400), 400);
+        assert_eq!(tracker.observe("bw", 200), 400);
+
+        // Metrics are tracked independently
+        assert_eq!(tracker.observe("ops", 5), 5);
+    }
+
+    #[test]
+    fn test_percent_of_peak_over_sequence() {
+        let mut tracker = PeakTracker::new();
+
+        for current in [100, 400, 200, 300, 0] {
+            let peak = tracker.observe("bw", current);
+            let percent = percent_of_peak(current, peak);
+            assert_eq!(percent, current as f64 / peak as f64 * 100.0);
+        }
+
+        // First sample is its own peak
+        assert_eq!(percent_of_peak(250, 250), 100.0);
+        // Nothing observed yet
+        assert_eq!(percent_of_peak(0, 0), 0.0);
+    }
+}
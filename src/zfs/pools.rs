@@ -1 +1,172 @@
+use super::error::{ZfsError, ZfsResult};
+use crate::system::CommandExecutor;
+use crate::system::commands::DEFAULT_COMMAND_TIMEOUT;
+use std::time::Duration;
 
+/// Pool discovery and validation via `zpool list`
+pub struct PoolManager<'a, E: CommandExecutor> {
+    command_executor: &'a E,
+    command_timeout: Duration,
+}
+
+impl<'a, E: CommandExecutor> PoolManager<'a, E> {
+    pub fn new(command_executor: &'a E) -> Self {
+        Self {
+            command_executor,
+            command_timeout: DEFAULT_COMMAND_TIMEOUT,
+        }
+    }
+
+    /// Give up on a `zpool` call after `timeout` instead of the default
+    pub fn with_command_timeout(mut self, timeout: Duration) -> Self {
+        self.command_timeout = timeout;
+        self
+    }
+
+    /// List the names of all imported pools
+    pub async fn list_pools(&self) -> ZfsResult<Vec<String>> {
+        let args = ["list", "-H", "-o", "name"];
+        let output = self
+            .command_executor
+            .execute_with_timeout("zpool", &args, self.command_timeout)
+            .await
+            .map_err(|e| ZfsError::command_failed("zpool", &args, e.as_ref()))?;
+
+        Ok(parse_pool_list(&output))
+    }
+
+    /// The first imported pool, for when nothing better picks one
+    pub async fn get_default_pool(&self) -> ZfsResult<String> {
+        self.list_pools().await?.into_iter().next().ok_or_else(|| {
+            ZfsError::subsystem_unavailable("zpool", "ZFS is loaded but no pools are imported")
+        })
+    }
+
+    /// The pool with the most write operations per `zpool iostat`, None when no
+    /// pool reports any
+    pub async fn busiest_pool(&self) -> ZfsResult<Option<String>> {
+        let args = ["iostat", "-H", "-p"];
+        let output = self
+            .command_executor
+            .execute_with_timeout("zpool", &args, self.command_timeout)
+            .await
+            .map_err(|e| ZfsError::command_failed("zpool", &args, e.as_ref()))?;
+
+        Ok(parse_busiest_pool(&output))
+    }
+}
+
+/// Parse `zpool list -H -o name` output into pool names. Without pools it
+/// prints nothing, though some versions say "no pools available" instead.
+fn parse_pool_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && *line != "no pools available")
+        .map(str::to_string)
+        .collect()
+}
+
+/// Pick the pool with the highest write ops from `zpool iostat -H -p` rows
+/// (name, alloc, free, read ops, write ops, read bytes, write bytes)
+fn parse_busiest_pool(output: &str) -> Option<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let write_ops = fields.get(4)?.parse::<u64>().ok()?;
+            Some((fields[0], write_ops))
+        })
+        .filter(|(_, write_ops)| *write_ops > 0)
+        // max_by_key keeps the last maximum; prefer the first listed pool on ties
+        .rev()
+        .max_by_key(|(_, write_ops)| *write_ops)
+        .map(|(name, _)| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system::commands::{DemoCommandExecutor, NoPoolsCommandExecutor};
+
+    #[test]
+    fn test_parse_pool_list() {
+        assert_eq!(
+            parse_pool_list("boot-pool\ndata\n\n  usb-backup  \n"),
+            vec!["boot-pool", "data", "usb-backup"]
+        );
+        assert!(parse_pool_list("").is_empty());
+        assert!(parse_pool_list("no pools available\n").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_no_pools_imported() {
+        let executor = NoPoolsCommandExecutor;
+        let manager = PoolManager::new(&executor);
+
+        assert!(manager.list_pools().await.unwrap().is_empty());
+        assert_eq!(manager.busiest_pool().await.unwrap(), None);
+        let error = manager.get_default_pool().await.unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("ZFS is loaded but no pools are imported")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_pools_demo() {
+        let executor = DemoCommandExecutor;
+        let pools = PoolManager::new(&executor).list_pools().await.unwrap();
+        assert_eq!(pools, vec!["boot-pool", "data", "usb-backup"]);
+    }
+
+    #[test]
+    fn test_parse_busiest_pool() {
+        let output = "quiet\t100\t200\t50\t3\t4096\t8192\nbusy\t100\t200\t1\t40\t512\t65536\n";
+        assert_eq!(parse_busiest_pool(output).as_deref(), Some("busy"));
+
+        // Ties go to the first pool, idle pools and malformed rows are ignored
+        let output = "a\t1\t2\t0\t7\t0\t0\nb\t1\t2\t0\t7\t0\t0\n";
+        assert_eq!(parse_busiest_pool(output).as_deref(), Some("a"));
+        assert_eq!(parse_busiest_pool("idle\t1\t2\t5\t0\t9\t0\n"), None);
+        assert_eq!(parse_busiest_pool("garbage\n"), None);
+    }
+
+    #[tokio::test]
+    async fn test_busiest_pool_demo() {
+        let executor = DemoCommandExecutor;
+        let manager = PoolManager::new(&executor);
+        assert_eq!(
+            manager.busiest_pool().await.unwrap().as_deref(),
+            Some("data")
+        );
+        assert_eq!(manager.get_default_pool().await.unwrap(), "boot-pool");
+    }
+
+    /// Command executor whose `zpool` takes longer than any timeout in these tests
+    struct StalledCommandExecutor;
+
+    #[async_trait::async_trait]
+    impl CommandExecutor for StalledCommandExecutor {
+        async fn execute(
+            &self,
+            command: &str,
+            args: &[&str],
+        ) -> Result<String, Box<dyn std::error::Error>> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            DemoCommandExecutor.execute(command, args).await
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_command_timeout() {
+        let executor = StalledCommandExecutor;
+        let manager = PoolManager::new(&executor).with_command_timeout(Duration::from_secs(2));
+
+        let started = tokio::time::Instant::now();
+        assert!(manager.list_pools().await.is_err());
+        assert!(manager.busiest_pool().await.is_err());
+        assert_eq!(started.elapsed(), Duration::from_secs(4));
+    }
+}
@@ -1,4 +1,7 @@
+use super::types::Trend;
 use std::collections::HashMap;
+#[cfg(test)]
+use std::time::Duration;
 use std::time::Instant;
 
 /// Tracks metrics over time to calculate rates (operations per second)
@@ -63,7 +66,61 @@ impl RateCalculator {
         rate
     }
 
+    /// Direction `current_value` moved in since the previous call for `key`, and
+    /// store it for the next one. None for the first measurement.
+    pub fn trend_and_update(
+        &mut self,
+        key: &str,
+        current_value: u64,
+        current_time: Instant,
+    ) -> Option<Trend> {
+        let trend = self
+            .previous_values
+            .get(key)
+            .map(|&previous| Trend::between(previous, current_value));
+        self.update(key, current_value, current_time);
+        trend
+    }
+
+    /// Last stored value and timestamp of every tracked metric, e.g. for
+    /// exporters that publish all metrics without naming each key
+    #[allow(dead_code)]
+    pub fn rates_snapshot(&self) -> HashMap<String, (u64, Instant)> {
+        self.previous_values
+            .iter()
+            .filter_map(|(key, &value)| {
+                let timestamp = *self.previous_timestamps.get(key)?;
+                Some((key.clone(), (value, timestamp)))
+            })
+            .collect()
+    }
+
+    /// Names of all tracked metrics, in no particular order
+    #[allow(dead_code)]
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.previous_values.keys().map(String::as_str)
+    }
+
+    /// Forget every stored sample, e.g. after the counters were reset by a ZFS
+    /// module reload, so the next measurement starts a fresh baseline
+    pub fn reset(&mut self) {
+        self.previous_values.clear();
+        self.previous_timestamps.clear();
+    }
+
+    /// Forget the stored samples of every metric `keep` rejects
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.previous_values.retain(|key, _| keep(key));
+        self.previous_timestamps.retain(|key, _| keep(key));
+    }
 
+    /// Move every stored sample back in time, as if it had been taken `by` earlier
+    #[cfg(test)]
+    pub fn rewind(&mut self, by: Duration) {
+        for timestamp in self.previous_timestamps.values_mut() {
+            *timestamp -= by;
+        }
+    }
 }
 
 impl Default for RateCalculator {
@@ -75,7 +132,7 @@ impl Default for RateCalculator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::thread;
+    use crate::system::clock::{Clock, MockClock};
     use std::time::Duration;
 
     #[test]
@@ -90,37 +147,57 @@ mod tests {
     #[test]
     fn test_rate_calculation() {
         let mut calculator = RateCalculator::new();
-        let start = Instant::now();
+        let clock = MockClock::new();
 
         // First measurement
-        calculator.update("ops", 1000, start);
-        thread::sleep(Duration::from_millis(100));
+        calculator.update("ops", 1000, clock.now());
+        clock.advance(Duration::from_millis(100));
 
         // Second measurement
-        let now = Instant::now();
-        let rate = calculator.calculate_rate("ops", 1100, now).unwrap();
+        let rate = calculator.calculate_rate("ops", 1100, clock.now()).unwrap();
 
-        // Should be approximately 1000 ops per second (100 ops / 0.1 second)
-        assert!((rate - 1000.0).abs() < 200.0); // Allow reasonable tolerance for timing
+        // 100 ops / 0.1 second
+        assert_eq!(rate, 1000.0);
     }
 
     #[test]
     fn test_calculate_and_update() {
         let mut calculator = RateCalculator::new();
-        let start = Instant::now();
+        let clock = MockClock::new();
 
         // First call should return None
-        let rate1 = calculator.calculate_and_update("test", 100, start);
+        let rate1 = calculator.calculate_and_update("test", 100, clock.now());
         assert!(rate1.is_none());
 
-        thread::sleep(Duration::from_millis(50));
+        clock.advance(Duration::from_millis(50));
 
         // Second call should return a rate
+        let rate2 = calculator
+            .calculate_and_update("test", 150, clock.now())
+            .unwrap();
+
+        // 50 ops / 0.05 seconds
+        assert_eq!(rate2, 1000.0);
+    }
+
+    #[test]
+    fn test_trend_and_update() {
+        let mut calculator = RateCalculator::new();
         let now = Instant::now();
-        let rate2 = calculator.calculate_and_update("test", 150, now).unwrap();
 
-        // Should be approximately 1000 ops per second (50 ops / 0.05 seconds)
-        assert!((rate2 - 1000.0).abs() < 100.0);
+        assert_eq!(calculator.trend_and_update("c", 1000, now), None);
+        assert_eq!(
+            calculator.trend_and_update("c", 1500, now),
+            Some(Trend::Rising)
+        );
+        assert_eq!(
+            calculator.trend_and_update("c", 1500, now),
+            Some(Trend::Steady)
+        );
+        assert_eq!(
+            calculator.trend_and_update("c", 900, now),
+            Some(Trend::Falling)
+        );
     }
 
     #[test]
@@ -184,11 +261,57 @@ mod tests {
         assert_eq!(rate, 0.0);
     }
 
+    #[test]
+    fn test_reset_forgets_baselines() {
+        let mut calculator = RateCalculator::new();
+        let now = Instant::now();
 
+        calculator.update("test", 200, now);
+        calculator.reset();
 
+        assert!(calculator.calculate_rate("test", 10, now).is_none());
+        assert_eq!(calculator.keys().count(), 0);
+    }
 
+    #[test]
+    fn test_retain_forgets_rejected_baselines() {
+        let mut calculator = RateCalculator::new();
+        let now = Instant::now();
 
+        calculator.update("arc_read_ops", 100, now);
+        calculator.update("slog_sdc_write_ops", 200, now);
+        calculator.retain(|key| !key.starts_with("slog_"));
+
+        assert_eq!(calculator.keys().collect::<Vec<_>>(), vec!["arc_read_ops"]);
+    }
 
+    #[test]
+    fn test_rates_snapshot() {
+        let mut calculator = RateCalculator::new();
+        let now = Instant::now();
+        let later = now + Duration::from_secs(1);
+
+        assert!(calculator.rates_snapshot().is_empty());
+        assert_eq!(calculator.keys().count(), 0);
+
+        calculator.update("arc_read_ops", 100, now);
+        calculator.update("l2_read_bytes", 4096, now);
+        calculator.calculate_and_update("slog_write_ops", 7, now);
+        calculator.update("arc_read_ops", 250, later);
+
+        let snapshot = calculator.rates_snapshot();
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(snapshot["arc_read_ops"], (250, later));
+        assert_eq!(snapshot["l2_read_bytes"], (4096, now));
+        assert_eq!(snapshot["slog_write_ops"], (7, now));
+
+        let mut keys: Vec<&str> = calculator.keys().collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            vec!["arc_read_ops", "l2_read_bytes", "slog_write_ops"]
+        );
+    }
 
     #[test]
     fn test_precision_with_small_deltas() {
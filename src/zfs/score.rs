@@ -0,0 +1,87 @@
+//! Single headline "cache efficiency" score derived from the collected stats
+
+/// SLOG latency at or below which sync writes count as fully efficient (ms)
+const SLOG_LATENCY_BEST_MS: f64 = 1.0;
+/// SLOG latency at or above which sync writes count as not efficient at all (ms)
+pub const SLOG_LATENCY_WORST_MS: f64 = 20.0;
+/// Share of the score given to SLOG latency when the pool has a log device
+const SLOG_WEIGHT: f64 = 0.2;
+
+/// Combine the cache stats into a 0-100 score:
+///
+/// - Reads: `arc + (100 - arc) * l2arc / 100`, the percentage of reads served by
+///   either cache. L2ARC only sees ARC misses, so it counts in proportion to the
+///   misses it offloads; without L2ARC this is just the ARC hit rate.
+/// - Sync writes: 100 at or below 1 ms SLOG latency, 0 at or above 20 ms, linear
+///   in between.
+///
+/// With a SLOG the score is `0.8 * reads + 0.2 * sync writes`, otherwise reads alone.
+pub fn cache_efficiency(
+    arc_hit_rate: f64,
+    l2arc_hit_rate: Option<f64>,
+    slog_latency: Option<f64>,
+) -> f64 {
+    let arc = arc_hit_rate.clamp(0.0, 100.0);
+    let l2arc = l2arc_hit_rate.unwrap_or(0.0).clamp(0.0, 100.0);
+    let reads = arc + (100.0 - arc) * l2arc / 100.0;
+
+    match slog_latency {
+        Some(latency) => {
+            let writes = (SLOG_LATENCY_WORST_MS - latency)
+                / (SLOG_LATENCY_WORST_MS - SLOG_LATENCY_BEST_MS)
+                * 100.0;
+            (1.0 - SLOG_WEIGHT) * reads + SLOG_WEIGHT * writes.clamp(0.0, 100.0)
+        }
+        None => reads,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zfs::CacheStatus;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_arc_only() {
+        assert_close(cache_efficiency(100.0, None, None), 100.0);
+        assert_close(cache_efficiency(40.0, None, None), 40.0);
+        assert_eq!(
+            CacheStatus::from_hit_rate(cache_efficiency(40.0, None, None)),
+            CacheStatus::Poor
+        );
+    }
+
+    #[test]
+    fn test_l2arc_offloads_arc_misses() {
+        // Half of the 40% ARC misses are served by L2ARC
+        assert_close(cache_efficiency(60.0, Some(50.0), None), 80.0);
+        // An L2ARC that never hits doesn't drag the score down
+        assert_close(cache_efficiency(90.0, Some(0.0), None), 90.0);
+        // Nothing left to offload
+        assert_close(cache_efficiency(100.0, Some(10.0), None), 100.0);
+    }
+
+    #[test]
+    fn test_slog_latency() {
+        assert_close(cache_efficiency(100.0, None, Some(0.5)), 100.0);
+        assert_close(cache_efficiency(100.0, None, Some(20.0)), 80.0);
+        assert_close(cache_efficiency(100.0, None, Some(50.0)), 80.0);
+        // Halfway between 1 ms and 20 ms
+        assert_close(cache_efficiency(50.0, None, Some(10.5)), 50.0);
+    }
+
+    #[test]
+    fn test_out_of_range_inputs_are_clamped() {
+        assert_close(cache_efficiency(120.0, Some(-5.0), None), 100.0);
+        assert_close(cache_efficiency(-1.0, None, None), 0.0);
+    }
+}
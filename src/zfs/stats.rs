@@ -1,16 +1,41 @@
-use super::error::{ZfsError, ZfsResult};
+use super::error::{FsContext, ZfsError, ZfsResult};
+use super::peak_tracker::PeakTracker;
 use super::rate_calculator::RateCalculator;
-use super::types::{ArcStats, L2ArcStats, SlogStats};
+use super::types::{
+    ArcEviction, ArcHitBreakdown, ArcMetadata, ArcStats, ArcstatReads, CollectedAt, DatasetStats,
+    L2ArcStats, LatencyPercentiles, PoolIoStats, PoolVdevs, SlogStats, Trend,
+};
+use crate::system::commands::{DEFAULT_COMMAND_TIMEOUT, is_permission_denied};
 use crate::system::{Cache, CommandExecutor, FilesystemReader};
 // async_trait is used via the derive macro
 use std::time::{Duration, Instant};
 
+/// Interval and count appended to `zpool iostat` for the SLOG's current rates: the
+/// first report averages since import, the second covers the one-second interval
+const SLOG_SAMPLE: [&str; 2] = ["1", "2"];
+
 /// ZFS statistics collector with rate calculation and caching
 pub struct ZfsStatsCollector<E: CommandExecutor, F: FilesystemReader> {
     command_executor: E,
     filesystem_reader: F,
     rate_calculator: RateCalculator,
+    peak_tracker: PeakTracker,
     cache: Cache<String>,
+    pool: Option<String>,
+    resolve_device_names: bool,
+    last_sample: Option<Instant>,
+    pinned_time: Option<Instant>,
+    // Layout from the last `zpool status`, to cross-check L2ARC against cache vdevs
+    // and for the pool's state
+    vdevs: Option<PoolVdevs>,
+    // arcstats as read during the refresh marked at that time, shared by ARC and L2ARC
+    arcstats: Option<(Instant, String)>,
+    arcstat_reads: ArcstatReads,
+    command_timeout: Duration,
+    since_boot_rates: bool,
+    // Uptime since-boot rates are averaged over, and the refresh it was read in
+    uptime: Option<Duration>,
+    uptime_read_in: Option<Instant>,
 }
 
 impl<E: CommandExecutor, F: FilesystemReader> ZfsStatsCollector<E, F> {
@@ -19,68 +44,364 @@ impl<E: CommandExecutor, F: FilesystemReader> ZfsStatsCollector<E, F> {
             command_executor,
             filesystem_reader,
             rate_calculator: RateCalculator::new(),
+            peak_tracker: PeakTracker::new(),
             // Cache expensive operations for 30 seconds
             cache: Cache::new(Duration::from_secs(30)),
+            pool: None,
+            resolve_device_names: false,
+            last_sample: None,
+            pinned_time: None,
+            vdevs: None,
+            arcstats: None,
+            arcstat_reads: ArcstatReads::default(),
+            command_timeout: DEFAULT_COMMAND_TIMEOUT,
+            since_boot_rates: false,
+            uptime: None,
+            uptime_read_in: None,
+        }
+    }
+
+    /// Restrict pool-specific commands to a single pool
+    pub fn with_pool(mut self, pool: &str) -> Self {
+        self.pool = Some(pool.to_string());
+        self
+    }
+
+    /// Pool this collector is restricted to, if any
+    pub fn pool(&self) -> Option<&str> {
+        self.pool.as_deref()
+    }
+
+    /// The pool's state (e.g. "ONLINE", "DEGRADED") per the `zpool status` read
+    /// for the SLOG, None until SLOG stats were collected
+    pub fn pool_state(&self) -> Option<&str> {
+        self.vdevs.as_ref()?.state.as_deref()
+    }
+
+    /// Give up on a command (`zpool`, `arcstat`, ...) after `timeout`, so a hung
+    /// device fails the refresh instead of freezing the monitor
+    pub fn with_command_timeout(mut self, timeout: Duration) -> Self {
+        self.command_timeout = timeout;
+        self
+    }
+
+    /// The executor commands run through, e.g. to look up the imported pools
+    pub fn command_executor(&self) -> &E {
+        &self.command_executor
+    }
+
+    /// Restrict the collector to `pool` from now on. What belonged to the previous
+    /// pool goes: SLOG and dataset rate baselines, its layout and cached output.
+    /// ARC and L2ARC are shared by all pools, so their rates carry on.
+    pub fn switch_pool(&mut self, pool: &str) {
+        self.pool = Some(pool.to_string());
+        self.rate_calculator
+            .retain(|key| !key.starts_with("slog_") && !key.starts_with("dataset_"));
+        self.vdevs = None;
+        self.cache.clear();
+    }
+
+    /// Show devices by their kernel names (e.g. "sdc") instead of by-id identifiers
+    pub fn with_device_name_resolution(mut self, enabled: bool) -> Self {
+        self.resolve_device_names = enabled;
+        self
+    }
+
+    /// Report rates as averages since boot, each kstat counter over the system
+    /// uptime, instead of the change between samples. SLOG figures come from
+    /// `zpool iostat`, which already averages them since import; dataset counters
+    /// also start at import and stay rates between samples.
+    pub fn with_since_boot_rates(mut self, enabled: bool) -> Self {
+        self.since_boot_rates = enabled;
+        self
+    }
+
+    /// Whether the arcstat fallback takes one sample (cumulative `read`) or two
+    /// (per-second `read`), see `ArcstatReads`
+    pub fn with_arcstat_reads(mut self, reads: ArcstatReads) -> Self {
+        self.arcstat_reads = reads;
+        self
+    }
+
+    /// Timestamp every following sample with `tick` instead of the time it is read
+    /// at, so rates cover exactly the time between scheduled ticks
+    pub fn pin_sample_time(&mut self, tick: Instant) {
+        self.pinned_time = Some(tick);
+    }
+
+    /// Time to record for a sample taken now
+    pub fn sample_time(&self) -> Instant {
+        self.pinned_time.unwrap_or_else(Instant::now)
+    }
+
+    /// `sample_time` for a collection that computes rates. For since-boot rates it
+    /// also reads the uptime they are averaged over, once per refresh (see
+    /// `mark_sample`); without one they fall back to the change between samples.
+    async fn rate_sample_time(&mut self) -> Instant {
+        if self.since_boot_rates
+            && (self.last_sample.is_none() || self.uptime_read_in != self.last_sample)
+        {
+            self.uptime = self.read_uptime().await;
+            self.uptime_read_in = self.last_sample;
+            if self.uptime.is_none() {
+                log::debug!("No /proc/uptime, showing rates between samples instead of since boot");
+            }
+        }
+        self.sample_time()
+    }
+
+    /// Uptime the current rates are averaged over, None for rates between samples
+    pub fn rate_uptime(&self) -> Option<Duration> {
+        self.uptime.filter(|uptime| !uptime.is_zero())
+    }
+
+    /// Per-second rate of the cumulative kstat counter `key`: over the uptime for
+    /// since-boot rates, else over the time since its previous sample (0 on the first)
+    fn counter_rate(&mut self, key: &str, total: u64, now: Instant) -> f64 {
+        match self.rate_uptime() {
+            Some(uptime) => {
+                self.rate_calculator.update(key, total, now);
+                total as f64 / uptime.as_secs_f64()
+            }
+            None => self
+                .rate_calculator
+                .calculate_and_update(key, total, now)
+                .unwrap_or(0.0),
         }
     }
 
+    /// Record a round of collection at `now`, returning the real time since the
+    /// previous one; rates are computed over this, not the configured interval
+    pub fn mark_sample(&mut self, now: Instant) -> Option<Duration> {
+        let elapsed = self.last_sample.map(|last| now.duration_since(last));
+        self.last_sample = Some(now);
+        elapsed
+    }
+
+    /// Drop all rate baselines; the next collection reports rates as on the first one
+    pub fn reset_rates(&mut self) {
+        self.rate_calculator.reset();
+    }
+
+    /// Pretend the previous rate samples were taken `by` earlier, for deterministic rates
+    #[cfg(test)]
+    pub fn rewind_rate_samples(&mut self, by: Duration) {
+        self.rate_calculator.rewind(by);
+    }
+
     /// Collect ARC statistics
     pub async fn collect_arc_stats(&mut self) -> ZfsResult<ArcStats> {
-        let now = Instant::now();
+        let now = self.rate_sample_time().await;
+
+        let mut stats = self.collect_arc_stats_from_sources(now).await?;
+        stats.memory_total = self.read_total_memory().await;
+        Ok(stats)
+    }
+
+    async fn collect_arc_stats_from_sources(&mut self, now: Instant) -> ZfsResult<ArcStats> {
+        // On Linux, try to get ARC stats from /proc/spl/kstat/zfs/arcstats first
+        let mut proc_error = None;
+        if cfg!(target_os = "linux") {
+            match self.collect_arc_stats_from_proc(now).await {
+                Ok(stats) => return Ok(stats),
+                Err(e) => {
+                    // e.g. a hardened system where arcstats isn't world-readable
+                    let reason = describe_error(&e);
+                    log::debug!("ARC stats from /proc unavailable: {}", reason);
+                    proc_error = Some(reason);
+                }
+            }
+        }
 
-        // Try to get ARC stats from /proc/spl/kstat/zfs/arcstats first
-        match self.collect_arc_stats_from_proc(now).await {
+        // FreeBSD/NetBSD expose the same kstats through sysctl
+        match self.collect_arc_stats_from_sysctl(now).await {
             Ok(stats) => Ok(stats),
             Err(_) => {
-                // Fallback to arcstat command
-                self.collect_arc_stats_from_arcstat(now).await
+                // Fallback to arcstat command, keeping the /proc failure in the final
+                // error since it is usually the one worth fixing
+                self.collect_arc_stats_from_arcstat(now)
+                    .await
+                    .map_err(|e| match (e, proc_error) {
+                        (ZfsError::SubsystemUnavailable { subsystem, reason }, Some(proc)) => {
+                            ZfsError::SubsystemUnavailable {
+                                subsystem,
+                                reason: format!("{} (/proc read failed: {})", reason, proc),
+                            }
+                        }
+                        (e, _) => e,
+                    })
             }
         }
     }
 
-    /// Collect ARC statistics from /proc/spl/kstat/zfs/arcstats
-    async fn collect_arc_stats_from_proc(&mut self, now: Instant) -> ZfsResult<ArcStats> {
+    /// Total physical memory in bytes from /proc/meminfo, None where it doesn't exist
+    pub async fn read_total_memory(&self) -> Option<u64> {
+        let content = self
+            .filesystem_reader
+            .read_to_string("/proc/meminfo")
+            .await
+            .ok()?;
+        parse_mem_total(&content)
+    }
+
+    /// Time since boot from /proc/uptime, None where it doesn't exist
+    pub async fn read_uptime(&self) -> Option<Duration> {
+        let content = self
+            .filesystem_reader
+            .read_to_string("/proc/uptime")
+            .await
+            .ok()?;
+        parse_uptime(&content)
+    }
+
+    /// Read /proc/spl/kstat/zfs/arcstats once per refresh (see `mark_sample`):
+    /// ARC and L2ARC stats both come from it. Outside a marked refresh every
+    /// call reads the file.
+    async fn read_arcstats(&mut self) -> Result<String, Box<dyn std::error::Error>> {
+        if let (Some(refresh), Some((read_in, content))) = (self.last_sample, &self.arcstats) {
+            if refresh == *read_in {
+                return Ok(content.clone());
+            }
+        }
+
         let content = self
             .filesystem_reader
             .read_to_string("/proc/spl/kstat/zfs/arcstats")
-            .map_err(|e| {
-                ZfsError::filesystem_error("/proc/spl/kstat/zfs/arcstats", "read", &e.to_string())
-            })?;
+            .await?;
+        self.arcstats = self.last_sample.map(|refresh| (refresh, content.clone()));
+        Ok(content)
+    }
 
-        // Parse the kstat format
-        let mut hits = 0u64;
-        let mut misses = 0u64;
-        let mut size = 0u64;
-        let mut c_max = 0u64;
-        let mut read_ops_total = 0u64;
+    /// Collect ARC statistics from /proc/spl/kstat/zfs/arcstats
+    async fn collect_arc_stats_from_proc(&mut self, now: Instant) -> ZfsResult<ArcStats> {
+        let content = self
+            .read_arcstats()
+            .await
+            .with_fs_context("/proc/spl/kstat/zfs/arcstats", "read")?;
 
+        // Parse the kstat format: "name type value"
+        let mut kstats = Vec::new();
         for line in content.lines() {
             let line = line.trim();
             if line.is_empty() || line.starts_with("name") {
                 continue; // Skip header lines
             }
 
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 3 {
-                let value_str = parts[2];
+            // Runs for every line on every refresh, so no per-line allocation
+            let mut fields = line.split_whitespace();
+            if let (Some(name), Some(_), Some(value_str)) =
+                (fields.next(), fields.next(), fields.next())
+            {
                 let value = value_str.parse::<u64>().map_err(|_| {
-                    ZfsError::parse_error(
-                        "ARC kstat",
-                        &format!("Invalid number: {}", value_str),
-                    )
+                    ZfsError::parse_error("ARC kstat", &format!("Invalid number: {}", value_str))
                 })?;
+                kstats.push((name, value));
+            }
+        }
 
-                match parts[0] {
-                    "hits" => hits = value,
-                    "misses" => misses = value,
-                    "size" => size = value,
-                    "c_max" => c_max = value,
-                    "read_ops" => read_ops_total = value,
-                    _ => {}
+        Ok(self.arc_stats_from_kstats(&kstats, now))
+    }
+
+    /// Collect ARC statistics from `sysctl kstat.zfs.misc.arcstats` (FreeBSD/NetBSD)
+    async fn collect_arc_stats_from_sysctl(&mut self, now: Instant) -> ZfsResult<ArcStats> {
+        let args = ["kstat.zfs.misc.arcstats"];
+        let output = self
+            .run_command("sysctl", &args)
+            .await
+            .map_err(|e| ZfsError::command_failed("sysctl", &args, e.as_ref()))?;
+
+        let kstats = self.parse_sysctl_arcstats(&output)?;
+        if kstats.is_empty() {
+            return Err(ZfsError::invalid_format(
+                "kstat.zfs.misc.arcstats.* entries",
+                "no entries",
+                "sysctl output",
+            ));
+        }
+
+        Ok(self.arc_stats_from_kstats(&kstats, now))
+    }
+
+    /// Parse `kstat.zfs.misc.arcstats.<name>: <value>` lines into kstat name/value pairs
+    fn parse_sysctl_arcstats<'a>(&self, output: &'a str) -> ZfsResult<Vec<(&'a str, u64)>> {
+        let mut kstats = Vec::new();
+
+        for line in output.lines() {
+            let Some((key, value_str)) = line.trim().split_once(':') else {
+                continue;
+            };
+            let Some(name) = key.strip_prefix("kstat.zfs.misc.arcstats.") else {
+                continue;
+            };
+
+            let value_str = value_str.trim();
+            let value = value_str.parse::<u64>().map_err(|_| {
+                ZfsError::parse_error(
+                    "sysctl arcstats",
+                    &format!("Invalid number for {}: {}", name, value_str),
+                )
+            })?;
+            kstats.push((name, value));
+        }
+
+        Ok(kstats)
+    }
+
+    /// Build ARC statistics from kstat name/value pairs, whichever source they came from
+    fn arc_stats_from_kstats(&mut self, kstats: &[(&str, u64)], now: Instant) -> ArcStats {
+        let mut hits = 0u64;
+        let mut misses = 0u64;
+        let mut size = 0u64;
+        let mut c = None;
+        let mut c_max = 0u64;
+        let mut read_ops_total = 0u64;
+        let mut demand_data_hits = None;
+        let mut demand_metadata_hits = None;
+        let mut prefetch_hits = None;
+        let mut evict_skip = None;
+        let mut evict_not_enough = None;
+        let mut mutex_miss = None;
+        let mut metadata_size = None;
+        let mut meta_used = None;
+        let mut meta_limit = None;
+        let mut no_grow = None;
+        let mut prune = None;
+
+        for &(name, value) in kstats {
+            match name {
+                "hits" => hits = value,
+                "misses" => misses = value,
+                "size" => size = value,
+                "c" => c = Some(value),
+                "c_max" => c_max = value,
+                "read_ops" => read_ops_total = value,
+                "demand_data_hits" => demand_data_hits = Some(value),
+                "demand_metadata_hits" => demand_metadata_hits = Some(value),
+                "prefetch_data_hits" | "prefetch_metadata_hits" => {
+                    prefetch_hits = Some(prefetch_hits.unwrap_or(0) + value)
                 }
+                "evict_skip" => evict_skip = Some(value),
+                "evict_not_enough" => evict_not_enough = Some(value),
+                "mutex_miss" => mutex_miss = Some(value),
+                "metadata_size" => metadata_size = Some(value),
+                "arc_meta_used" => meta_used = Some(value),
+                "arc_meta_limit" => meta_limit = Some(value),
+                // Not every ZFS version exports these, so they're just left out then
+                "arc_no_grow" => no_grow = Some(value != 0),
+                "arc_prune" => prune = Some(value),
+                _ => {}
             }
         }
 
+        let hit_breakdown = match (demand_data_hits, demand_metadata_hits, prefetch_hits) {
+            (Some(demand_data), Some(demand_metadata), Some(prefetch)) => Some(ArcHitBreakdown {
+                demand_data,
+                demand_metadata,
+                prefetch,
+            }),
+            _ => None,
+        };
+
         // Calculate hit/miss rates
         let total = hits + misses;
         let hit_rate = if total > 0 {
@@ -89,43 +410,79 @@ impl<E: CommandExecutor, F: FilesystemReader> ZfsStatsCollector<E, F> {
             0.0
         };
 
+        let eviction = match (evict_skip, evict_not_enough, mutex_miss) {
+            (Some(evict_skip), Some(evict_not_enough), Some(mutex_miss)) => {
+                let mut rate = |key: &str, total: u64| self.counter_rate(key, total, now) as u64;
+                Some(ArcEviction {
+                    evict_skip: rate("arc_evict_skip", evict_skip),
+                    evict_not_enough: rate("arc_evict_not_enough", evict_not_enough),
+                    mutex_miss: rate("arc_mutex_miss", mutex_miss),
+                })
+            }
+            _ => None,
+        };
+
+        // `arc_meta_used` also counts headers and dnodes, which the limit applies to
+        let metadata = meta_used.or(metadata_size).map(|size| ArcMetadata {
+            size,
+            limit: meta_limit,
+        });
+
         // Calculate read operations per second
-        let read_ops_rate = self
-            .rate_calculator
-            .calculate_and_update("arc_read_ops", read_ops_total, now)
-            .unwrap_or(0.0);
+        let read_ops_rate = self.counter_rate("arc_read_ops", read_ops_total, now);
 
-        Ok(ArcStats {
+        let target_trend = c.and_then(|c| self.rate_calculator.trend_and_update("arc_c", c, now));
+
+        // Prune callbacks ask other caches (e.g. dnodes, inodes) to release ARC memory
+        let prunes = prune.map(|prune| self.counter_rate("arc_prune", prune, now) as u64);
+
+        ArcStats {
             hit_rate,
             size,
-            target: c_max,
+            // The ARC is sized by `c`; `c_max` is only the ceiling it may grow to
+            target: c.unwrap_or(c_max),
+            target_trend,
+            max: c_max,
             read_ops: read_ops_rate as u64,
-        })
+            hits_total: hits,
+            misses_total: misses,
+            hit_breakdown,
+            eviction,
+            metadata,
+            no_grow,
+            prunes,
+            memory_total: None,
+            collected_at: CollectedAt::now(),
+        }
     }
 
     /// Collect ARC statistics from arcstat command (fallback)
     async fn collect_arc_stats_from_arcstat(&mut self, now: Instant) -> ZfsResult<ArcStats> {
+        let count = match self.arcstat_reads {
+            ArcstatReads::Cumulative => "1",
+            ArcstatReads::PerSecond => "2",
+        };
         // Try different arcstat command formats
         let commands = vec![
-            ("arcstat", vec!["-f", "hit%,miss%,read,arcsz,c", "1", "1"]),
-            ("arcstat", vec!["1", "1"]),
-            ("echo", vec!["|", "arcstat"]),
+            ("arcstat", vec!["-f", "hit%,miss%,read,arcsz,c", "1", count]),
+            ("arcstat", vec!["1", count]),
+            // Older ZFS on Linux releases ship the script under its original name
+            ("arcstat.py", vec!["1", count]),
         ];
 
         for (cmd, args) in commands {
-            match self
-                .command_executor
-                .execute_with_timeout(cmd, &args, std::time::Duration::from_secs(3))
-                .await
-            {
+            match self.run_command(cmd, &args).await {
                 Ok(output) => {
                     match self.parse_arcstat_output(&output) {
                         Ok(mut stats) => {
-                            // Calculate read operations rate
-                            stats.read_ops = self
-                                .rate_calculator
-                                .calculate_and_update("arc_read_ops", stats.read_ops, now)
-                                .unwrap_or(0.0) as u64;
+                            if self.arcstat_reads == ArcstatReads::Cumulative {
+                                // Calculate read operations rate
+                                stats.read_ops =
+                                    self.counter_rate("arc_read_ops", stats.read_ops, now) as u64;
+                            }
+                            stats.target_trend =
+                                self.rate_calculator
+                                    .trend_and_update("arc_c", stats.target, now);
                             return Ok(stats);
                         }
                         Err(_) => continue, // Try next command
@@ -150,8 +507,21 @@ impl<E: CommandExecutor, F: FilesystemReader> ZfsStatsCollector<E, F> {
 
     /// Parse arcstat command output
     fn parse_arcstat_output(&self, output: &str) -> ZfsResult<ArcStats> {
-        // Parse the output format: "100.0 0.0 1247 49720066048 49910562816"
-        let parts: Vec<&str> = output.split_whitespace().collect();
+        // Parse the output format: "100.0 0.0 1247 49720066048 49910562816", i.e.
+        // hit% miss% read arcsz c, or without the miss% column. Some arcstat
+        // versions print a "hit% miss% read arcsz c" header first, so use the
+        // last line that starts with a number (the latest of several samples)
+        let lines = output.lines().filter(|line| !line.trim().is_empty());
+        let first_line = lines.clone().next().unwrap_or("");
+        let data_line = lines
+            .rev()
+            .find(|line| {
+                line.split_whitespace()
+                    .next()
+                    .is_some_and(|token| parse_percent(token).is_ok())
+            })
+            .unwrap_or(first_line);
+        let parts: Vec<&str> = data_line.split_whitespace().collect();
         if parts.len() < 4 {
             return Err(ZfsError::invalid_format(
                 "at least 4 space-separated numbers",
@@ -160,49 +530,65 @@ impl<E: CommandExecutor, F: FilesystemReader> ZfsStatsCollector<E, F> {
             ));
         }
 
-        let hit_rate = parts[0].parse::<f64>().map_err(|_| {
+        let hit_rate = parse_percent(parts[0]).map_err(|_| {
             ZfsError::parse_error("arcstat hit_rate", "Invalid hit rate percentage")
         })?;
+        // Skip the miss% column when it's there
+        let parts = if parts.len() >= 5 {
+            &parts[2..]
+        } else {
+            &parts[1..]
+        };
 
-        let read_ops = parts[1].parse::<u64>().map_err(|_| {
-            ZfsError::parse_error(
-                "arcstat read_ops",
-                "Invalid read operations count",
-            )
+        let read_ops = parts[0].parse::<u64>().map_err(|_| {
+            ZfsError::parse_error("arcstat read_ops", "Invalid read operations count")
         })?;
 
-        let size = parts[2]
+        let size = parts[1]
             .parse::<u64>()
             .map_err(|_| ZfsError::parse_error("arcstat size", "Invalid cache size"))?;
 
-        let target = parts[3].parse::<u64>().map_err(|_| {
-            ZfsError::parse_error("arcstat target", "Invalid target size")
-        })?;
+        let target = parts[2]
+            .parse::<u64>()
+            .map_err(|_| ZfsError::parse_error("arcstat target", "Invalid target size"))?;
 
+        // arcstat only reports per-interval figures, no cumulative counters,
+        // and its `c` column is the target; the ceiling is unknown
         Ok(ArcStats {
             hit_rate,
             size,
             target,
+            target_trend: None,
+            max: target,
             read_ops,
+            hits_total: 0,
+            misses_total: 0,
+            hit_breakdown: None,
+            eviction: None,
+            metadata: None,
+            no_grow: None,
+            prunes: None,
+            memory_total: None,
+            collected_at: CollectedAt::now(),
         })
     }
 
     /// Collect L2ARC statistics
     pub async fn collect_l2arc_stats(&mut self) -> ZfsResult<Option<L2ArcStats>> {
-        let now = Instant::now();
+        let now = self.rate_sample_time().await;
 
         // Check if L2ARC is available by looking at arcstats
-        let arc_content = self
-            .filesystem_reader
-            .read_to_string("/proc/spl/kstat/zfs/arcstats")
-            .map_err(|e| {
-                ZfsError::filesystem_error("/proc/spl/kstat/zfs/arcstats", "read", &e.to_string())
-            })?;
+        let arc_content = match self.read_arcstats().await {
+            Ok(content) => content,
+            // Fallback to arcstat command
+            Err(_) => return self.collect_l2arc_stats_from_arcstat().await,
+        };
 
         // Check for L2ARC presence
         let has_l2arc = arc_content.lines().any(|line| line.starts_with("l2_size"));
 
         if !has_l2arc {
+            self.check_cache_vdevs(false);
             return Ok(None);
         }
 
@@ -211,6 +597,10 @@ impl<E: CommandExecutor, F: FilesystemReader> ZfsStatsCollector<E, F> {
         let mut l2_misses = 0u64;
         let mut l2_size = 0u64;
         let mut l2_read_bytes_total = 0u64;
+        let mut l2_write_bytes_total = 0u64;
+        let mut l2_cksum_bad = 0u64;
+        let mut l2_io_error = 0u64;
+        let mut c_max = 0u64;
 
         for line in arc_content.lines() {
             let line = line.trim();
@@ -218,21 +608,23 @@ impl<E: CommandExecutor, F: FilesystemReader> ZfsStatsCollector<E, F> {
                 continue; // Skip header lines
             }
 
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 3 {
-                let value_str = parts[2];
+            let mut fields = line.split_whitespace();
+            if let (Some(name), Some(_), Some(value_str)) =
+                (fields.next(), fields.next(), fields.next())
+            {
                 let value = value_str.parse::<u64>().map_err(|_| {
-                    ZfsError::parse_error(
-                        "L2ARC kstat",
-                        &format!("Invalid number: {}", value_str),
-                    )
+                    ZfsError::parse_error("L2ARC kstat", &format!("Invalid number: {}", value_str))
                 })?;
 
-                match parts[0] {
+                match name {
                     "l2_hits" => l2_hits = value,
                     "l2_misses" => l2_misses = value,
                     "l2_size" => l2_size = value,
                     "l2_read_bytes" => l2_read_bytes_total = value,
+                    "l2_write_bytes" => l2_write_bytes_total = value,
+                    "l2_cksum_bad" => l2_cksum_bad = value,
+                    "l2_io_error" => l2_io_error = value,
+                    "c_max" => c_max = value,
                     _ => {}
                 }
             }
@@ -245,203 +637,770 @@ impl<E: CommandExecutor, F: FilesystemReader> ZfsStatsCollector<E, F> {
             0.0
         };
 
-        // Calculate rates for operations and read bandwidth
-        let l2_ops_rate = self
-            .rate_calculator
-            .calculate_and_update("l2_total_ops", total_l2_ops, now)
-            .unwrap_or(0.0);
-        let l2_read_bytes_rate = self
+        // Calculate rates for operations, read bandwidth and fill (write) bandwidth
+        let l2_ops_rate = self.counter_rate("l2_total_ops", total_l2_ops, now);
+        let l2_read_bytes_rate = self.counter_rate("l2_read_bytes", l2_read_bytes_total, now);
+        let l2_write_bytes_rate = self.counter_rate("l2_write_bytes", l2_write_bytes_total, now);
+
+        let l2_read_bytes_peak = self
+            .peak_tracker
+            .observe("l2_read_bytes", l2_read_bytes_rate as u64);
+
+        // A cache device that is empty after being added or a reboot hits rarely
+        // until it fills. Call it warming while it is still growing and holds less
+        // than the ARC's maximum size, i.e. it can't have caught much of what the
+        // ARC evicts yet.
+        // A size, not a counter: compared between samples whatever the rate mode
+        let growing = self
             .rate_calculator
-            .calculate_and_update("l2_read_bytes", l2_read_bytes_total, now)
-            .unwrap_or(0.0);
+            .trend_and_update("l2_size", l2_size, now)
+            == Some(Trend::Rising);
+        let warming = growing && l2_size < c_max;
+        self.check_cache_vdevs(l2_size > 0);
 
         Ok(Some(L2ArcStats {
             hit_rate: l2_hit_rate,
             size: l2_size,
             read_bytes: l2_read_bytes_rate as u64,
+            read_bytes_peak: l2_read_bytes_peak,
+            write_bytes: l2_write_bytes_rate as u64,
             total_ops: l2_ops_rate as u64,
+            read_bytes_total: l2_read_bytes_total,
+            write_bytes_total: l2_write_bytes_total,
+            cksum_bad: l2_cksum_bad,
+            io_errors: l2_io_error,
+            hits_total: l2_hits,
+            misses_total: l2_misses,
+            warming,
+            collected_at: CollectedAt::now(),
         }))
     }
 
-    /// Collect SLOG statistics
-    pub async fn collect_slog_stats(&mut self) -> ZfsResult<Option<SlogStats>> {
-        let now = Instant::now();
+    /// Collect L2ARC statistics from arcstat command (fallback)
+    async fn collect_l2arc_stats_from_arcstat(&mut self) -> ZfsResult<Option<L2ArcStats>> {
+        let args = ["-f", "l2hit%,l2miss%,l2size,l2read", "1", "1"];
+        let output = self.run_command("arcstat", &args).await.map_err(|_| {
+            ZfsError::subsystem_unavailable(
+                "L2ARC",
+                "Failed to collect statistics from all sources (/proc and arcstat command)",
+            )
+        })?;
 
-        // Get zpool status to find SLOG devices (cached for performance)
-        let status_output = if let Some(cached) = self.cache.get("zpool_status") {
-            cached.clone()
-        } else {
-            let output = self
-                .command_executor
-                .execute("zpool", &["status"])
-                .await
-                .map_err(|e| ZfsError::command_error("zpool", &["status"], &e.to_string()))?;
-            self.cache
-                .insert("zpool_status".to_string(), output.clone());
-            output
-        };
+        self.parse_arcstat_l2_output(&output)
+    }
+
+    /// Parse `arcstat -f l2hit%,l2miss%,l2size,l2read` output, None without a cache device
+    fn parse_arcstat_l2_output(&self, output: &str) -> ZfsResult<Option<L2ArcStats>> {
+        // Parse the output format: "73 26 553G 12", skipping the header line
+        let data_line = output
+            .lines()
+            .find(|line| {
+                line.split_whitespace()
+                    .next()
+                    .is_some_and(|token| parse_percent(token).is_ok())
+            })
+            .unwrap_or("");
+        let parts: Vec<&str> = data_line.split_whitespace().collect();
+        if parts.len() < 4 {
+            return Err(ZfsError::invalid_format(
+                "4 space-separated values",
+                &format!("{} parts", parts.len()),
+                "arcstat L2ARC output",
+            ));
+        }
 
-        let slog_device = self.parse_slog_device_from_status(&status_output)?;
+        let hit_rate = parse_percent(parts[0])
+            .map_err(|_| ZfsError::parse_error("arcstat l2hit%", "Invalid hit rate percentage"))?;
+        // arcstat scales sizes and counts like zpool does ("553G", "1.2K")
+        let size = self.parse_ops(parts[2], "arcstat l2size")?;
+        let read_ops = self.parse_ops(parts[3], "arcstat l2read")?;
 
-        if slog_device.is_none() {
+        if size == 0 {
             return Ok(None);
         }
 
-        let device_name = slog_device.unwrap();
+        // arcstat only reports per-interval figures: no byte rates or cumulative counters
+        Ok(Some(L2ArcStats {
+            hit_rate,
+            size,
+            read_bytes: 0,
+            read_bytes_peak: 0,
+            write_bytes: 0,
+            total_ops: read_ops,
+            read_bytes_total: 0,
+            write_bytes_total: 0,
+            cksum_bad: 0,
+            io_errors: 0,
+            hits_total: 0,
+            misses_total: 0,
+            warming: false,
+            collected_at: CollectedAt::now(),
+        }))
+    }
 
-        // Get I/O statistics for the SLOG device (cached for performance)
-        let iostat_output = if let Some(cached) = self.cache.get("zpool_iostat") {
-            cached.clone()
-        } else {
-            let output = self
-                .command_executor
-                .execute("zpool", &["iostat", "-v"])
-                .await
-                .map_err(|e| ZfsError::command_error("zpool", &["iostat", "-v"], &e.to_string()))?;
-            self.cache
-                .insert("zpool_iostat".to_string(), output.clone());
-            output
+    /// Collect SLOG statistics
+    pub async fn collect_slog_stats(&mut self) -> ZfsResult<Option<SlogStats>> {
+        // Refreshes the uptime, which decides between sampled and since-boot rates
+        self.rate_sample_time().await;
+
+        // Get zpool status to find SLOG devices (cached for performance),
+        // scoped to the monitored pool so other pools' logs can't be picked up
+        let pool = self.pool.clone();
+        let mut status_args = vec!["status"];
+        if let Some(pool) = &pool {
+            status_args.push(pool);
+        }
+        let status_output = self
+            .cached_zpool_command("SLOG", "zpool_status", &status_args)
+            .await?;
+
+        let vdevs = self.parse_pool_vdevs(&status_output);
+        let slog_device = vdevs.log.first().cloned();
+        // A degraded mirror still takes writes; anything worse leaves the
+        // pool writing its intent log to the data vdevs
+        let fault = slog_device
+            .as_deref()
+            .and_then(|device| vdevs.state_of(device))
+            .filter(|state| *state != "DEGRADED")
+            .map(str::to_string);
+        self.vdevs = Some(vdevs);
+
+        let Some(device_name) = slog_device else {
+            return Ok(None);
         };
 
-        let (write_ops_total, write_bw_total) =
-            self.parse_slog_stats_from_iostat(&iostat_output, &device_name)?;
+        // A faulted device has no I/O worth asking `zpool iostat` about
+        if fault.is_some() {
+            let display_name = self.short_device_name(&device_name).await;
+            return Ok(Some(SlogStats {
+                device: device_name,
+                display_name,
+                write_ops: 0,
+                write_bw: 0,
+                write_bw_peak: 0,
+                write_ops_avg: 0,
+                write_bw_avg: 0,
+                utilization: 0.0,
+                latency: 0.0,
+                cache_age: self.cache.age("zpool_status"),
+                collected_at: CollectedAt::now(),
+                latency_percentiles: None,
+                fault,
+            }));
+        }
 
-        // Calculate rates
-        let write_ops_rate = self
-            .rate_calculator
-            .calculate_and_update(
-                &format!("slog_{}_write_ops", device_name),
-                write_ops_total,
-                now,
-            )
-            .unwrap_or(0.0);
-        let write_bw_rate = self
-            .rate_calculator
-            .calculate_and_update(
-                &format!("slog_{}_write_bw", device_name),
-                write_bw_total,
-                now,
-            )
-            .unwrap_or(0.0);
+        // Get I/O statistics for the SLOG device, preferring the parseable format.
+        // `zpool iostat` only reports averages since import, so the current rates
+        // come from a one-second interval sample, its second report; since-boot
+        // rates take the (cached) averages as they are.
+        let sampled = self.rate_uptime().is_none();
+        let mut cache_age = if sampled {
+            None
+        } else {
+            self.cache.age("zpool_iostat_parseable")
+        };
+        let ((write_ops_avg, write_bw_avg), (write_ops, write_bw)) = match self
+            .collect_slog_iostat_parseable(&device_name, sampled)
+            .await
+        {
+            Ok(stats) => stats,
+            Err(_) => {
+                // Fall back to the human-readable table
+                let iostat_output = if sampled {
+                    let pool = self.pool.clone();
+                    let mut args = vec!["iostat", "-v"];
+                    args.extend(pool.as_deref());
+                    args.extend(SLOG_SAMPLE);
+                    self.zpool_command("SLOG", &args).await?
+                } else {
+                    cache_age = self.cache.age("zpool_iostat");
+                    self.iostat_output("SLOG").await?
+                };
+                let average = self.parse_slog_stats_from_iostat(&iostat_output, &device_name)?;
+                let current = self.parse_slog_stats_from_iostat(
+                    last_report(&iostat_output, &device_name),
+                    &device_name,
+                )?;
+                (average, current)
+            }
+        };
+
+        let latency_percentiles = self.collect_slog_latency_percentiles(&device_name).await;
+        let display_name = self.short_device_name(&device_name).await;
+        let write_bw_peak = self
+            .peak_tracker
+            .observe(&format!("slog_{}_write_bw", device_name), write_bw);
 
         Ok(Some(SlogStats {
             device: device_name,
-            write_ops: write_ops_rate as u64,
-            write_bw: write_bw_rate as u64,
+            display_name,
+            write_ops,
+            write_bw,
+            write_bw_peak,
+            write_ops_avg,
+            write_bw_avg,
             utilization: 0.0, // TODO: Calculate utilization
             latency: 0.0,     // TODO: Calculate latency
+            cache_age,
+            collected_at: CollectedAt::now(),
+            latency_percentiles,
+            fault: None,
         }))
     }
 
-    /// Parse SLOG device from zpool status output
-    fn parse_slog_device_from_status(&self, status_output: &str) -> ZfsResult<Option<String>> {
-        let mut in_logs_section = false;
-
-        for line in status_output.lines() {
-            let line = line.trim();
+    /// Write latency percentiles of the SLOG device via `zpool iostat -w POOL DEVICE`
+    /// (cached for performance). The device can only be named within a pool, and
+    /// older `zpool` lacks `-w`, so either just leaves the percentiles out.
+    async fn collect_slog_latency_percentiles(
+        &mut self,
+        device_name: &str,
+    ) -> Option<LatencyPercentiles> {
+        let pool = self.pool.clone()?;
+        let output = self
+            .cached_output(
+                "zpool_iostat_latency",
+                "zpool",
+                &["iostat", "-w", &pool, device_name],
+            )
+            .await
+            .ok()?;
+        self.parse_latency_histogram(&output)
+    }
 
-            if line.starts_with("logs") {
-                in_logs_section = true;
-                continue;
-            }
+    /// Unparsed inputs of the current refresh for `--dump-raw`, named by source:
+    /// arcstats as read for this refresh, and the (cached) `zpool status` and
+    /// `zpool iostat -v` output of the pool. Sources that can't be read are left out.
+    pub async fn raw_inputs(&mut self) -> Vec<(&'static str, String)> {
+        let mut inputs = Vec::new();
+        if let Ok(arcstats) = self.read_arcstats().await {
+            inputs.push(("arcstats", arcstats));
+        }
 
-            if in_logs_section {
-                if line.is_empty() {
-                    continue;
-                }
-                // Look for mirror or single device lines
-                if line.starts_with("mirror-") || line.contains("ONLINE") {
-                    // Extract device name from mirror-X pattern
-                    if let Some(mirror_match) = line.split_whitespace().next() {
-                        if mirror_match.starts_with("mirror-") {
-                            return Ok(Some(mirror_match.to_string()));
-                        }
-                    }
-                }
-                // Exit logs section when we hit another section
-                if line.starts_with(char::is_alphabetic)
-                    && !line.contains("ONLINE")
-                    && !line.starts_with("mirror-")
-                {
-                    break;
-                }
-            }
+        let pool = self.pool.clone();
+        let mut status_args = vec!["status"];
+        if let Some(pool) = &pool {
+            status_args.push(pool);
+        }
+        if let Ok(status) = self
+            .cached_output("zpool_status", "zpool", &status_args)
+            .await
+        {
+            inputs.push(("zpool-status", status));
+        }
+        let mut iostat_args = vec!["iostat", "-v"];
+        iostat_args.extend(pool.as_deref());
+        if let Ok(iostat) = self
+            .cached_output("zpool_iostat", "zpool", &iostat_args)
+            .await
+        {
+            inputs.push(("zpool-iostat-v", iostat));
         }
+        inputs
+    }
 
-        Ok(None)
+    /// Run a command, reusing its output from the cache while it is fresh
+    async fn cached_command(
+        &mut self,
+        key: &str,
+        command: &str,
+        args: &[&str],
+    ) -> ZfsResult<String> {
+        self.cached_output(key, command, args)
+            .await
+            .map_err(|e| ZfsError::command_failed(command, args, e.as_ref()))
     }
 
-    /// Parse SLOG statistics from zpool iostat output
-    fn parse_slog_stats_from_iostat(
+    /// Like `cached_command` for `zpool` calls that may need root (SLOG, pool I/O):
+    /// a permission failure is reported as `subsystem` being unavailable
+    async fn cached_zpool_command(
+        &mut self,
+        subsystem: &str,
+        key: &str,
+        args: &[&str],
+    ) -> ZfsResult<String> {
+        self.cached_output(key, "zpool", args)
+            .await
+            .map_err(|e| zpool_error(subsystem, args, e.as_ref()))
+    }
+
+    /// `cached_zpool_command` for output that mustn't be reused, e.g. an interval sample
+    async fn zpool_command(&mut self, subsystem: &str, args: &[&str]) -> ZfsResult<String> {
+        self.run_command("zpool", args)
+            .await
+            .map_err(|e| zpool_error(subsystem, args, e.as_ref()))
+    }
+
+    /// Run a command, giving up after `command_timeout`
+    async fn run_command(
         &self,
-        iostat_output: &str,
+        command: &str,
+        args: &[&str],
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        self.command_executor
+            .execute_with_timeout(command, args, self.command_timeout)
+            .await
+    }
+
+    async fn cached_output(
+        &mut self,
+        key: &str,
+        command: &str,
+        args: &[&str],
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        if let Some(cached) = self.cache.get(key) {
+            return Ok(cached.clone());
+        }
+
+        // How much of the cache is still serving, e.g. to see how often `zpool` runs
+        if self.cache.is_effectively_empty() {
+            log::debug!(
+                "Running `{} {}`, no cached output is fresh",
+                command,
+                args.join(" ")
+            );
+        } else {
+            log::debug!(
+                "Running `{} {}`, {} of {} cached outputs still fresh",
+                command,
+                args.join(" "),
+                self.cache.valid_len(),
+                self.cache.len()
+            );
+        }
+        let output = self.run_command(command, args).await?;
+        self.cache.insert(key.to_string(), output.clone());
+        Ok(output)
+    }
+
+    /// Collect SLOG write ops and bandwidth via `zpool iostat -v -H -p [POOL]`: the
+    /// averages since import, and the current rates of a `SLOG_SAMPLE` interval
+    /// sample when `sampled` (else the averages again)
+    async fn collect_slog_iostat_parseable(
+        &mut self,
         device_name: &str,
-    ) -> ZfsResult<(u64, u64)> {
-        let mut in_device_section = false;
-        let mut write_ops = 0u64;
-        let mut write_bw = 0u64;
+        sampled: bool,
+    ) -> ZfsResult<((u64, u64), (u64, u64))> {
+        let output = if sampled {
+            let pool = self.pool.clone();
+            let mut args = vec!["iostat", "-v", "-H", "-p"];
+            args.extend(pool.as_deref());
+            args.extend(SLOG_SAMPLE);
+            self.run_command("zpool", &args)
+                .await
+                .map_err(|e| ZfsError::command_failed("zpool", &args, e.as_ref()))?
+        } else {
+            self.iostat_parseable_output().await?
+        };
+        let average = self.parse_slog_stats_from_iostat_parseable(&output, device_name)?;
+        let current = self.parse_slog_stats_from_iostat_parseable(
+            last_report(&output, device_name),
+            device_name,
+        )?;
+        Ok((average, current))
+    }
+
+    /// Run `zpool iostat -v -H -p [POOL]` (cached for performance)
+    async fn iostat_parseable_output(&mut self) -> ZfsResult<String> {
+        let pool = self.pool.clone();
+        let mut args = vec!["iostat", "-v", "-H", "-p"];
+        if let Some(pool) = &pool {
+            args.push(pool);
+        }
+
+        self.cached_command("zpool_iostat_parseable", "zpool", &args)
+            .await
+    }
+
+    /// Run `zpool iostat -v [POOL]` (cached for performance), the human-readable
+    /// table for a `zpool` without `-H -p`. Scoped like the scripted output, so a
+    /// same-named vdev of another pool (e.g. its own "mirror-1") can't be picked up.
+    async fn iostat_output(&mut self, subsystem: &str) -> ZfsResult<String> {
+        let pool = self.pool.clone();
+        let mut args = vec!["iostat", "-v"];
+        args.extend(pool.as_deref());
+        self.cached_zpool_command(subsystem, "zpool_iostat", &args)
+            .await
+    }
+
+    /// Collect aggregate pool I/O from the top-level `zpool iostat` row.
+    /// Without an interval `zpool iostat` already reports per-second averages,
+    /// so no rate calculation is needed.
+    pub async fn collect_pool_io_stats(&mut self) -> ZfsResult<Option<PoolIoStats>> {
+        let output = match self.iostat_parseable_output().await {
+            Ok(output) => output,
+            // Fall back to the human-readable table
+            Err(_) => self.iostat_output("Pool I/O").await?,
+        };
+
+        let pool = self.pool.clone();
+        self.parse_pool_io_from_iostat(&output, pool.as_deref())
+    }
 
+    /// Parse the pool row of `zpool iostat -v` output, either human-readable or
+    /// scripted (`-H -p`). Without a pool name the first pool row is used.
+    fn parse_pool_io_from_iostat(
+        &self,
+        iostat_output: &str,
+        pool: Option<&str>,
+    ) -> ZfsResult<Option<PoolIoStats>> {
         for line in iostat_output.lines() {
-            let line = line.trim();
+            let mut fields = line.split_whitespace();
+            let Some(name) = fields.next() else {
+                continue;
+            };
 
-            // Look for the device section
-            if line.contains(device_name) {
-                in_device_section = true;
-                // If this line contains the device name and has enough parts, parse it directly
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 7 {
-                    write_ops = parts[4].parse::<u64>().map_err(|_| {
-                        ZfsError::parse_error(
-                            "iostat write_ops",
-                            "Invalid write operations count",
-                        )
-                    })?;
-                    // Parse bandwidth (e.g., "12.0M" -> bytes)
-                    write_bw = self.parse_bandwidth(parts[6])?;
-                    break;
-                }
+            // Skip the table headers and separators
+            if matches!(name, "capacity" | "pool") || name.starts_with('-') {
+                continue;
+            }
+            if pool.is_some_and(|pool| pool != name) {
                 continue;
             }
 
-            if in_device_section && !line.is_empty() && !line.starts_with('-') {
-                // Parse the I/O stats line: "mirror-1  -  -  0  23  0  12.0M"
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 7 {
-                    write_ops = parts[4].parse::<u64>().map_err(|_| {
-                        ZfsError::parse_error(
-                            "iostat write_ops",
-                            "Invalid write operations count",
-                        )
-                    })?;
-                    // Parse bandwidth (e.g., "12.0M" -> bytes)
-                    write_bw = self.parse_bandwidth(parts[6])?;
-                }
-                break;
+            // The six value columns, without collecting the row into a Vec
+            let columns: [&str; 6] = std::array::from_fn(|_| fields.next().unwrap_or(""));
+            if columns[5].is_empty() {
+                return Err(ZfsError::invalid_format(
+                    "7 columns",
+                    &format!("{} columns", line.split_whitespace().count()),
+                    "iostat pool row",
+                ));
             }
+            let [alloc, free, read_ops, write_ops, read_bw, write_bw] = columns;
+
+            return Ok(Some(PoolIoStats {
+                pool: name.to_string(),
+                read_ops: self.parse_ops(read_ops, "iostat read_ops")?,
+                write_ops: self.parse_ops(write_ops, "iostat write_ops")?,
+                read_bw: self.parse_bandwidth(read_bw)?,
+                write_bw: self.parse_bandwidth(write_bw)?,
+                // Sizes use the same suffixes (or raw bytes with `-p`) as bandwidth
+                alloc: self.parse_bandwidth(alloc)?,
+                free: self.parse_bandwidth(free)?,
+            }));
         }
 
-        Ok((write_ops, write_bw))
+        Ok(None)
     }
 
+    /// Collect read/write stats of one dataset from its objset kstat,
+    /// `/proc/spl/kstat/zfs/POOL/objset-0xID`. The ID comes from
+    /// `zfs get objsetid` (in decimal) and doesn't change, so it is cached.
+    pub async fn collect_dataset_stats(&mut self, dataset: &str) -> ZfsResult<DatasetStats> {
+        // objset counters start at import, not boot, so they are always compared
+        // between samples
+        let now = self.sample_time();
+
+        let args = ["get", "-H", "-o", "value", "objsetid", dataset];
+        let output = self
+            .cached_command(&format!("zfs_objsetid_{}", dataset), "zfs", &args)
+            .await?;
+        let objset_id = output.trim().parse::<u64>().map_err(|_| {
+            ZfsError::parse_error(
+                "zfs objsetid",
+                &format!("Invalid objset ID: {}", output.trim()),
+            )
+        })?;
 
-
-    /// Parse bandwidth string (e.g., "12.0M" -> bytes)
-    fn parse_bandwidth(&self, bw_str: &str) -> ZfsResult<u64> {
-        if bw_str.is_empty() || bw_str == "-" {
-            return Ok(0);
+        let pool = dataset.split('/').next().unwrap_or(dataset);
+        let path = format!("/proc/spl/kstat/zfs/{}/objset-0x{:x}", pool, objset_id);
+        let content = self
+            .filesystem_reader
+            .read_to_string(&path)
+            .await
+            .with_fs_context(&path, "read")?;
+        let mut stats = parse_objset_kstat(&content)?;
+        if stats.name.is_empty() {
+            stats.name = dataset.to_string();
         }
 
-        // Handle formats like "12.0M", "234M", "1.82T"
-        let bw_str = bw_str.trim();
-        let last_char = bw_str.chars().last().ok_or_else(|| {
-            ZfsError::invalid_format("non-empty string", "empty string", "bandwidth parsing")
-        })?;
-
-        let num_str = if "BKMGTbkmgt".contains(last_char) {
-            &bw_str[..bw_str.len().saturating_sub(1)]
-        } else {
-            // No unit suffix, treat whole string as number
-            bw_str
+        let mut rate = |counter: &str, total: u64| {
+            self.rate_calculator
+                .calculate_and_update(&format!("dataset_{}_{}", dataset, counter), total, now)
+                .unwrap_or(0.0) as u64
+        };
+        stats.reads = rate("reads", stats.reads_total);
+        stats.writes = rate("writes", stats.writes_total);
+        stats.read_bytes = rate("nread", stats.read_bytes_total);
+        stats.write_bytes = rate("nwritten", stats.write_bytes_total);
+        Ok(stats)
+    }
+
+    /// Resolve a by-id device identifier to its kernel name via the
+    /// `/dev/disk/by-id` symlink, falling back to the identifier itself
+    async fn short_device_name(&self, device: &str) -> String {
+        if !self.resolve_device_names {
+            return device.to_string();
+        }
+
+        let link = format!("/dev/disk/by-id/{}", device);
+        match self.filesystem_reader.read_link(&link).await {
+            Ok(target) => std::path::Path::new(&target)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| device.to_string()),
+            Err(_) => device.to_string(),
+        }
+    }
+
+    /// Parse SLOG device from zpool status output
+    /// When several pools are listed, only the monitored pool's logs are considered.
+    #[cfg(test)]
+    fn parse_slog_device_from_status(&self, status_output: &str) -> ZfsResult<Option<String>> {
+        Ok(self.parse_pool_vdevs(status_output).log.into_iter().next())
+    }
+
+    /// Sort the top-level vdevs in the config of `zpool status` into their sections.
+    /// Each section header ("logs", "cache", ...) stands alone on its line, and the
+    /// pool's own line heads the data vdevs; whatever is indented one level below a
+    /// header is a top-level vdev. Headers are matched on the trimmed line, so the
+    /// config may be indented with tabs, spaces or a mix of both. When several pools
+    /// are listed, only the monitored pool's vdevs are collected.
+    fn parse_pool_vdevs(&self, status_output: &str) -> PoolVdevs {
+        let mut vdevs = PoolVdevs::default();
+        let mut unhealthy = Vec::new();
+        let mut in_monitored_pool = true;
+        // Set on the "NAME STATE ..." header, so the next line is the pool's own
+        let mut expect_pool_line = false;
+        // Section being read, with the indentation of its header and of its vdevs
+        let mut section: Option<(&mut Vec<String>, usize, Option<usize>)> = None;
+
+        for line in status_output.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let indent = indent_width(line);
+
+            // Each pool's status starts with "pool: NAME"
+            if let Some(name) = trimmed.strip_prefix("pool:") {
+                section = None;
+                in_monitored_pool = self
+                    .pool
+                    .as_deref()
+                    .map_or(true, |pool| pool == name.trim());
+                continue;
+            }
+            if !in_monitored_pool {
+                continue;
+            }
+            if trimmed.starts_with("errors:") {
+                section = None;
+                continue;
+            }
+            if trimmed.starts_with("NAME ") {
+                expect_pool_line = true;
+                continue;
+            }
+
+            let mut fields = trimmed.split_whitespace();
+            let name = fields.next().unwrap_or_default();
+            if expect_pool_line {
+                expect_pool_line = false;
+                vdevs.state = fields.next().map(str::to_string);
+                section = Some((&mut vdevs.data, indent, None));
+                continue;
+            }
+            if fields.next().is_none() {
+                // A header without state columns starts a new section; spares and
+                // anything unknown are skipped until the next one
+                let category = match name {
+                    "logs" => Some(&mut vdevs.log),
+                    "cache" => Some(&mut vdevs.cache),
+                    "special" => Some(&mut vdevs.special),
+                    "dedup" => Some(&mut vdevs.dedup),
+                    _ => None,
+                };
+                section = category.map(|vdevs| (vdevs, indent, None));
+                continue;
+            }
+
+            if let Some((vdevs, header_indent, vdev_indent)) = &mut section {
+                if indent <= *header_indent {
+                    continue;
+                }
+                if *vdev_indent.get_or_insert(indent) == indent {
+                    vdevs.push(name.to_string());
+                    if let Some(state) = trimmed.split_whitespace().nth(1) {
+                        if state != "ONLINE" {
+                            unhealthy.push((name.to_string(), state.to_string()));
+                        }
+                    }
+                }
+            }
+        }
+
+        vdevs.unhealthy = unhealthy;
+        vdevs
+    }
+
+    /// Note when the last `zpool status` disagrees with arcstats about an L2ARC.
+    /// arcstats are system-wide, so a cache vdev in another pool also explains it.
+    fn check_cache_vdevs(&self, has_l2arc: bool) {
+        if let Some(vdevs) = &self.vdevs {
+            if vdevs.cache.is_empty() == has_l2arc {
+                log::debug!(
+                    "L2ARC {} in arcstats but `zpool status` lists cache vdevs {:?}",
+                    if has_l2arc { "present" } else { "absent" },
+                    vdevs.cache
+                );
+            }
+        }
+    }
+
+    /// Parse SLOG statistics from zpool iostat output
+    fn parse_slog_stats_from_iostat(
+        &self,
+        iostat_output: &str,
+        device_name: &str,
+    ) -> ZfsResult<(u64, u64)> {
+        // Set once the device's name was printed on a line of its own: zpool wraps
+        // long names, putting the six value columns on the following line
+        let mut wrapped = false;
+
+        for line in iostat_output.lines() {
+            // Only the device's own row is split up; every other row is
+            // skipped on its first column
+            let Some(name) = line.split_whitespace().next() else {
+                continue;
+            };
+            if !wrapped && name != device_name {
+                continue;
+            }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+
+            // Values of an idle device are "-" or 0, which parse as 0
+            let values = if wrapped {
+                &parts[..]
+            } else if parts.len() == 1 {
+                wrapped = true;
+                continue;
+            } else {
+                &parts[1..]
+            };
+
+            // Parse the I/O stats: "mirror-1  -  -  0  23  0  12.0M"
+            if values.len() < 6 {
+                return Err(ZfsError::invalid_format(
+                    "6 value columns",
+                    &format!("{} columns", values.len()),
+                    "iostat device row",
+                ));
+            }
+            let write_ops = self.parse_ops(values[3], "iostat write_ops")?;
+            // Parse bandwidth (e.g., "12.0M" -> bytes)
+            let write_bw = self.parse_bandwidth(values[5])?;
+            return Ok((write_ops, write_bw));
+        }
+
+        Ok((0, 0))
+    }
+
+    /// Parse SLOG statistics from scripted `zpool iostat -v -H -p` output.
+    /// Columns are tab-separated: name, alloc, free, read ops, write ops, read bw, write bw,
+    /// with all values as raw integers.
+    fn parse_slog_stats_from_iostat_parseable(
+        &self,
+        iostat_output: &str,
+        device_name: &str,
+    ) -> ZfsResult<(u64, u64)> {
+        let Some(line) = iostat_output
+            .lines()
+            .find(|line| line.split_whitespace().next() == Some(device_name))
+        else {
+            return Ok((0, 0));
+        };
+
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 7 {
+            return Err(ZfsError::invalid_format(
+                "7 tab-separated columns",
+                &format!("{} columns", parts.len()),
+                "parseable iostat output",
+            ));
+        }
+
+        let write_ops = parts[4].parse::<u64>().map_err(|_| {
+            ZfsError::parse_error(
+                "parseable iostat write_ops",
+                "Invalid write operations count",
+            )
+        })?;
+        let write_bw = parts[6].parse::<u64>().map_err(|_| {
+            ZfsError::parse_error("parseable iostat write_bw", "Invalid write bandwidth")
+        })?;
+
+        Ok((write_ops, write_bw))
+    }
+
+    /// Parse the write latency histogram of `zpool iostat -w`. Each row is a bucket's
+    /// upper bound (e.g. "131us") followed by read/write counts for total_wait,
+    /// disk_wait, syncq_wait, ...; the total_wait write count is the third column.
+    /// Only the first histogram is used, and empty buckets may show as 0 or "-".
+    fn parse_latency_histogram(&self, output: &str) -> Option<LatencyPercentiles> {
+        let mut buckets = Vec::new();
+        for line in output.lines() {
+            let mut fields = line.split_whitespace();
+            let Some(bound) = fields.next().and_then(parse_latency_label) else {
+                // The separator below the rows ends the first histogram
+                if !buckets.is_empty() && line.starts_with('-') {
+                    break;
+                }
+                continue;
+            };
+            let count = self
+                .parse_ops(fields.nth(1)?, "zpool iostat -w write latency")
+                .ok()?;
+            buckets.push((bound, count));
+        }
+        latency_percentiles(&buckets)
+    }
+
+    /// Parse an operations count from human-readable `zpool iostat` output
+    /// (e.g., "23", "1.2K"). OpenZFS formats counts with the same 1024-based
+    /// `zfs_nicenum` as sizes, so "1.2K" is 1228 operations, not 1200.
+    fn parse_ops(&self, ops_str: &str, data_source: &str) -> ZfsResult<u64> {
+        let ops_str = ops_str.trim();
+        if ops_str.is_empty() || ops_str == "-" {
+            return Ok(0);
+        }
+        if let Ok(ops) = ops_str.parse::<u64>() {
+            return Ok(ops);
+        }
+
+        let invalid = || ZfsError::parse_error(data_source, "Invalid operations count");
+        let (num_str, suffix) = ops_str.split_at(ops_str.len() - 1);
+        let multiplier: u64 = match suffix.to_ascii_uppercase().as_str() {
+            "K" => 1024,
+            "M" => 1024 * 1024,
+            "G" => 1024 * 1024 * 1024,
+            "T" => 1024u64 * 1024 * 1024 * 1024,
+            _ => return Err(invalid()),
+        };
+        let num = parse_decimal(num_str).map_err(|_| invalid())?;
+        if !num.is_finite() || num < 0.0 {
+            return Err(invalid());
+        }
+        Ok((num * multiplier as f64) as u64)
+    }
+
+    /// Parse bandwidth string (e.g., "12.0M" -> bytes)
+    fn parse_bandwidth(&self, bw_str: &str) -> ZfsResult<u64> {
+        if bw_str.is_empty() || bw_str == "-" {
+            return Ok(0);
+        }
+
+        // Handle formats like "12.0M", "234M", "1.82T", "1.5P"
+        let bw_str = bw_str.trim();
+        let last_char = bw_str.chars().last().ok_or_else(|| {
+            ZfsError::invalid_format("non-empty string", "empty string", "bandwidth parsing")
+        })?;
+
+        let num_str = if "BKMGTPEbkmgtpe".contains(last_char) {
+            &bw_str[..bw_str.len().saturating_sub(1)]
+        } else {
+            // No unit suffix, treat whole string as number
+            bw_str
         };
 
         let multiplier: u64 = match last_char.to_ascii_uppercase() {
@@ -450,28 +1409,407 @@ impl<E: CommandExecutor, F: FilesystemReader> ZfsStatsCollector<E, F> {
             'M' => 1024 * 1024,
             'G' => 1024 * 1024 * 1024,
             'T' => 1024u64 * 1024 * 1024 * 1024,
+            'P' => 1024u64 * 1024 * 1024 * 1024 * 1024,
+            'E' => 1024u64 * 1024 * 1024 * 1024 * 1024 * 1024,
             _ => {
-                // If no unit, assume bytes - parse the whole string
-                return bw_str.parse::<u64>().map_err(|_| {
-                    ZfsError::parse_error("bandwidth", "Invalid number format")
-                });
+                // If no unit, assume bytes - parse the whole string, which may carry
+                // a fractional part (e.g. "12.0"), and truncate to whole bytes
+                return parse_decimal(bw_str)
+                    .ok()
+                    .filter(|num| num.is_finite() && *num >= 0.0)
+                    .map(|num| num as u64)
+                    .ok_or_else(|| ZfsError::parse_error("bandwidth", "Invalid number format"));
             }
         };
 
-        let num: f64 = num_str.parse().map_err(|_| {
-            ZfsError::parse_error("bandwidth number", "Invalid numeric value")
-        })?;
+        let num = parse_decimal(num_str)
+            .map_err(|_| ZfsError::parse_error("bandwidth number", "Invalid numeric value"))?;
         Ok((num * multiplier as f64) as u64)
     }
 }
 
+/// Parse the cumulative counters of an `objset-*` kstat; the rates are left at 0.
+/// Unlike arcstats it has a string row (`dataset_name`, type 7), so rows are
+/// picked by name rather than parsing every value as a number.
+fn parse_objset_kstat(content: &str) -> ZfsResult<DatasetStats> {
+    let mut stats = DatasetStats {
+        name: String::new(),
+        reads: 0,
+        writes: 0,
+        read_bytes: 0,
+        write_bytes: 0,
+        reads_total: 0,
+        writes_total: 0,
+        read_bytes_total: 0,
+        write_bytes_total: 0,
+        collected_at: CollectedAt::now(),
+    };
+    let mut found = false;
+
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(name), Some(_), Some(value)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let total = match name {
+            "dataset_name" => {
+                stats.name = value.to_string();
+                continue;
+            }
+            "reads" => &mut stats.reads_total,
+            "writes" => &mut stats.writes_total,
+            "nread" => &mut stats.read_bytes_total,
+            "nwritten" => &mut stats.write_bytes_total,
+            _ => continue,
+        };
+        *total = value.parse().map_err(|_| {
+            ZfsError::parse_error("objset kstat", &format!("Invalid number: {}", value))
+        })?;
+        found = true;
+    }
+
+    if !found {
+        return Err(ZfsError::parse_error(
+            "objset kstat",
+            "No reads/writes counters found",
+        ));
+    }
+    Ok(stats)
+}
+
+/// Columns `line` is indented by, a tab advancing to the next multiple of 8, so
+/// a tab-indented vdev still nests under a space-indented header
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| c.is_whitespace())
+        .fold(0, |width, c| match c {
+            '\t' => (width / 8 + 1) * 8,
+            _ => width + 1,
+        })
+}
+
+/// Parse an arcstat percentage column, which some builds print as "100%"
+/// rather than "100.0"
+fn parse_percent(token: &str) -> Result<f64, std::num::ParseFloatError> {
+    parse_decimal(token.strip_suffix('%').unwrap_or(token))
+}
+
+/// Parse a decimal number, also with the comma separator a non-C locale gives
+/// `zpool` and `arcstat` output (e.g. "1,5" for 1.5), should one get through
+fn parse_decimal(text: &str) -> Result<f64, std::num::ParseFloatError> {
+    match text.split_once(',') {
+        Some((whole, fraction)) if !text.contains('.') => format!("{}.{}", whole, fraction).parse(),
+        _ => text.parse(),
+    }
+}
+
+/// An error's message followed by its source, e.g. "Filesystem read failed for
+/// path: /proc/...: Permission denied (os error 13)"
+fn describe_error(error: &ZfsError) -> String {
+    match std::error::Error::source(error) {
+        Some(source) => format!("{}: {}", error, source),
+        None => error.to_string(),
+    }
+}
+
+/// Report a failed `zpool` call that may need root, a permission failure as
+/// `subsystem` being unavailable
+fn zpool_error(
+    subsystem: &str,
+    args: &[&str],
+    error: &(dyn std::error::Error + 'static),
+) -> ZfsError {
+    if is_permission_denied(error) {
+        ZfsError::subsystem_unavailable(
+            subsystem,
+            &format!(
+                "`zpool {}` needs elevated privileges, run with sudo for {} stats",
+                args.join(" "),
+                subsystem
+            ),
+        )
+    } else {
+        ZfsError::command_failed("zpool", args, error)
+    }
+}
+
+/// `zpool iostat` output from the last row naming `device` on, so the parsers,
+/// which take the first row, read the last report of an interval sample
+fn last_report<'a>(output: &'a str, device: &str) -> &'a str {
+    let mut start = 0;
+    let mut offset = 0;
+    for line in output.split_inclusive('\n') {
+        if line.split_whitespace().next() == Some(device) {
+            start = offset;
+        }
+        offset += line.len();
+    }
+    &output[start..]
+}
+
+/// A `zpool iostat -w` bucket label (e.g. "511ns", "65us", "2ms", "1s") in milliseconds
+fn parse_latency_label(label: &str) -> Option<f64> {
+    let (value, unit) = label.split_at(label.find(|c: char| !c.is_ascii_digit())?);
+    let value: f64 = value.parse().ok()?;
+    match unit {
+        "ns" => Some(value / 1_000_000.0),
+        "us" => Some(value / 1000.0),
+        "ms" => Some(value),
+        "s" => Some(value * 1000.0),
+        _ => None,
+    }
+}
+
+/// Nearest-rank p50/p95/p99 over `(bucket upper bound, count)` pairs in ascending
+/// order, or None when the histogram holds no samples
+fn latency_percentiles(buckets: &[(f64, u64)]) -> Option<LatencyPercentiles> {
+    let total: u64 = buckets.iter().map(|(_, count)| count).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let percentile = |pct: u64| {
+        let rank = (total * pct + 99) / 100;
+        let mut seen = 0;
+        for &(bound, count) in buckets {
+            seen += count;
+            if seen >= rank {
+                return bound;
+            }
+        }
+        buckets[buckets.len() - 1].0
+    };
+    Some(LatencyPercentiles {
+        p50: percentile(50),
+        p95: percentile(95),
+        p99: percentile(99),
+    })
+}
+
+/// Parse the first field of /proc/uptime (`350735.47 234388.90`), the seconds since boot
+fn parse_uptime(content: &str) -> Option<Duration> {
+    let seconds = content.split_whitespace().next()?.parse::<f64>().ok()?;
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// Parse the `MemTotal:  67437156 kB` line of /proc/meminfo into bytes
+fn parse_mem_total(content: &str) -> Option<u64> {
+    let line = content.lines().find(|line| line.starts_with("MemTotal:"))?;
+    let mut parts = line["MemTotal:".len()..].split_whitespace();
+    let value = parts.next()?.parse::<u64>().ok()?;
+    match parts.next() {
+        Some("kB") => Some(value * 1024),
+        None => Some(value),
+        Some(_) => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::system::commands::DemoCommandExecutor;
+    use crate::demo::{DEMO_SEED, SharedDemoData};
+    use crate::system::commands::{
+        DemoCommandExecutor, DynamicDemoCommandExecutor, RecordingCommandExecutor,
+        UnprivilegedCommandExecutor, decode_output,
+    };
     use crate::system::filesystem::DemoFilesystemReader;
+    use crate::zfs::types::ArcPressure;
+    use async_trait::async_trait;
+    use std::error::Error;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::time::Instant;
 
+    /// Filesystem reader returning the same content for every path
+    struct StaticFilesystemReader(&'static str);
+
+    #[async_trait]
+    impl FilesystemReader for StaticFilesystemReader {
+        async fn read_to_string(&self, _path: &str) -> Result<String, Box<dyn Error>> {
+            Ok(self.0.to_string())
+        }
+    }
+
+    /// Filesystem reader exposing a single `/dev/disk/by-id` symlink
+    struct ByIdFilesystemReader;
+
+    #[async_trait]
+    impl FilesystemReader for ByIdFilesystemReader {
+        async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+            Err(format!("Not found: {}", path).into())
+        }
+
+        async fn read_link(&self, path: &str) -> Result<String, Box<dyn Error>> {
+            match path {
+                "/dev/disk/by-id/ata-Samsung_SSD_860_EVO_250GB_S3YJNX0N1234567" => {
+                    Ok("../../sdc".to_string())
+                }
+                _ => Err(format!("Not a symlink: {}", path).into()),
+            }
+        }
+    }
+
+    /// Command executor returning the same output for every command
+    struct StaticCommandExecutor(&'static str);
+
+    #[async_trait]
+    impl CommandExecutor for StaticCommandExecutor {
+        async fn execute(&self, _command: &str, _args: &[&str]) -> Result<String, Box<dyn Error>> {
+            Ok(self.0.to_string())
+        }
+    }
+
+    /// Command executor whose commands never return, like `zpool` on a hung device
+    struct HangingCommandExecutor;
+
+    #[async_trait]
+    impl CommandExecutor for HangingCommandExecutor {
+        async fn execute(&self, _command: &str, _args: &[&str]) -> Result<String, Box<dyn Error>> {
+            std::future::pending().await
+        }
+    }
+
+    /// Command executor behaving like `arcstat 1 COUNT`: every call adds 2000 ARC
+    /// accesses, and the first sample is diffed against zero (i.e. since boot)
+    struct ArcstatCommandExecutor {
+        calls: std::sync::atomic::AtomicU64,
+    }
+
+    #[async_trait]
+    impl CommandExecutor for ArcstatCommandExecutor {
+        async fn execute(&self, _command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+            let calls = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let since_boot = 1_000_000 + calls * 2000;
+            let mut output = format!(
+                "hit% miss% read arcsz c\n95.2 4.8 {} 5368709120 8589934592\n",
+                since_boot
+            );
+            if args.last() == Some(&"2") {
+                output.push_str("95.0 5.0 1247 5368709120 8589934592\n");
+            }
+            Ok(output)
+        }
+    }
+
+    /// Filesystem reader whose L2ARC grows by `step` bytes on every read, like a
+    /// cache device filling up after a reboot
+    struct FillingL2FilesystemReader {
+        step: u64,
+        c_max: u64,
+        reads: std::sync::atomic::AtomicU64,
+    }
+
+    #[async_trait]
+    impl FilesystemReader for FillingL2FilesystemReader {
+        async fn read_to_string(&self, _path: &str) -> Result<String, Box<dyn Error>> {
+            let reads = self.reads.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(format!(
+                "name type data\nl2_hits 4 10\nl2_misses 4 990\nl2_size 4 {}\nc_max 4 {}\n",
+                (reads + 1) * self.step,
+                self.c_max
+            ))
+        }
+    }
+
+    /// Filesystem reader for a hardened system where nothing is world-readable
+    struct UnreadableFilesystemReader;
+
+    #[async_trait]
+    impl FilesystemReader for UnreadableFilesystemReader {
+        async fn read_to_string(&self, _path: &str) -> Result<String, Box<dyn Error>> {
+            Err(Box::new(std::io::Error::from(
+                std::io::ErrorKind::PermissionDenied,
+            )))
+        }
+    }
+
+    /// Command executor whose `zpool status` carries a byte that isn't valid UTF-8
+    struct NonUtf8CommandExecutor;
+
+    #[async_trait]
+    impl CommandExecutor for NonUtf8CommandExecutor {
+        async fn execute(&self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+            match (command, args) {
+                ("zpool", ["status", ..]) => {
+                    let status = include_str!("../demo/zpool_status.txt")
+                        .replace("9RK3VYJD", "9RK3\u{1}VYJD")
+                        .into_bytes()
+                        .into_iter()
+                        .map(|byte| if byte == 1 { 0xff } else { byte })
+                        .collect::<Vec<u8>>();
+                    Ok(decode_output(command, &status))
+                }
+                _ => DemoCommandExecutor.execute(command, args).await,
+            }
+        }
+    }
+
+    const FREEBSD_SYSCTL_ARCSTATS: &str = "\
+kstat.zfs.misc.arcstats.hits: 9500
+kstat.zfs.misc.arcstats.misses: 500
+kstat.zfs.misc.arcstats.demand_data_hits: 4200
+kstat.zfs.misc.arcstats.c_min: 1073741824
+kstat.zfs.misc.arcstats.c_max: 8589934592
+kstat.zfs.misc.arcstats.size: 5368709120
+kstat.zfs.misc.arcstats.l2_hits: 0
+";
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_collect_arc_stats_reports_proc_permission_error() {
+        // arcstats exists but can't be read, and no command produces usable output
+        let mut collector =
+            ZfsStatsCollector::new(StaticCommandExecutor(""), UnreadableFilesystemReader);
+
+        let error = collector.collect_arc_stats().await.unwrap_err();
+        assert!(matches!(error, ZfsError::SubsystemUnavailable { .. }));
+        let message = error.to_string();
+        assert!(
+            message.contains("/proc/spl/kstat/zfs/arcstats"),
+            "{}",
+            message
+        );
+        assert!(message.contains("permission denied"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_collect_arc_stats_from_freebsd_sysctl() {
+        // No /proc/spl/kstat, so the sysctl block is used
+        let mut collector = ZfsStatsCollector::new(
+            StaticCommandExecutor(FREEBSD_SYSCTL_ARCSTATS),
+            ByIdFilesystemReader,
+        );
+
+        let arc = collector.collect_arc_stats().await.unwrap();
+        assert_eq!(arc.hit_rate, 95.0);
+        assert_eq!(arc.size, 5368709120);
+        // No `c` in this block, so the target falls back to the ceiling
+        assert_eq!(arc.target, 8589934592);
+        assert_eq!(arc.max, 8589934592);
+        assert_eq!(arc.read_ops, 0);
+        assert_eq!(arc.hits_total, 9500);
+        assert_eq!(arc.misses_total, 500);
+    }
+
+    #[test]
+    fn test_parse_sysctl_arcstats_invalid() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        let result = collector.parse_sysctl_arcstats("kstat.zfs.misc.arcstats.hits: lots");
+        if let Err(ZfsError::ParseError { data_source, .. }) = result {
+            assert_eq!(data_source, "sysctl arcstats");
+        } else {
+            panic!("Expected ParseError");
+        }
+
+        // Unrelated sysctl lines are ignored
+        let kstats = collector
+            .parse_sysctl_arcstats("vfs.zfs.arc_max: 0\nkstat.zfs.misc.arcstats.size: 42")
+            .unwrap();
+        assert_eq!(kstats, vec![("size", 42)]);
+    }
+
     #[test]
     fn test_parse_arcstat_output_valid() {
         let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
@@ -506,119 +1844,954 @@ mod tests {
         }
     }
 
-    #[test]
-    fn test_parse_arcstat_output_invalid_hit_rate() {
-        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
-        let output = "invalid 4.8 1234 5368709120 8589934592";
+    #[test]
+    fn test_parse_arcstat_output_invalid_hit_rate() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+        let output = "invalid 4.8 1234 5368709120 8589934592";
+
+        let result = collector.parse_arcstat_output(output);
+        assert!(result.is_err());
+
+        if let Err(ZfsError::ParseError { data_source, .. }) = result {
+            assert_eq!(data_source, "arcstat hit_rate");
+        } else {
+            panic!("Expected ParseError");
+        }
+    }
+
+    #[test]
+    fn test_parse_ops() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        assert_eq!(collector.parse_ops("23", "ops").unwrap(), 23);
+        assert_eq!(collector.parse_ops("0", "ops").unwrap(), 0);
+        assert_eq!(collector.parse_ops("-", "ops").unwrap(), 0);
+        // zfs_nicenum is 1024-based for counts too
+        assert_eq!(collector.parse_ops("1.2K", "ops").unwrap(), 1228);
+        assert_eq!(collector.parse_ops("2K", "ops").unwrap(), 2048);
+        assert_eq!(collector.parse_ops("1.5M", "ops").unwrap(), 1_572_864);
+
+        for invalid in ["lots", "1.2B", "K", "-1K"] {
+            let result = collector.parse_ops(invalid, "iostat write_ops");
+            assert!(
+                matches!(result, Err(ZfsError::ParseError { ref data_source, .. }) if data_source == "iostat write_ops"),
+                "{} should not parse",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_slog_stats_with_suffixed_ops() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+        let iostat_output = "\
+logs            -      -      -      -      -      -
+  mirror-1  1.2G   230G      0   1.2K      0  12.0M
+";
+
+        let (write_ops, write_bw) = collector
+            .parse_slog_stats_from_iostat(iostat_output, "mirror-1")
+            .unwrap();
+        assert_eq!(write_ops, 1228);
+        assert_eq!(write_bw, 12 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_parse_latency_histogram() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+        // total_wait writes: 120 at 131us, 850 at 262us, 2.1K at 524us, 3.4K at 1ms,
+        // 1.2K at 2ms, 310 at 4ms, 64 at 8ms, 9 at 16ms; 8212 in all
+        let output = include_str!("../demo/zpool_iostat_latency.txt");
+
+        let percentiles = collector.parse_latency_histogram(output).unwrap();
+        assert_eq!(percentiles.p50, 1.0);
+        assert_eq!(percentiles.p95, 2.0);
+        assert_eq!(percentiles.p99, 4.0);
+    }
+
+    #[test]
+    fn test_parse_latency_histogram_empty_buckets() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+        let output = "\
+data         total_wait     disk_wait
+latency      read  write   read  write
+----------  -----  -----  -----  -----
+511ns           0      -      0      0
+1us             0      0      0      0
+65us            0      3      0      0
+131us           0      -      0      0
+1s              0      1      0      0
+--------------------------------------
+mirror-1     total_wait     disk_wait
+latency      read  write   read  write
+----------  -----  -----  -----  -----
+1us             0    999      0      0
+--------------------------------------
+";
+
+        // Only the first histogram counts; "-" buckets are empty
+        let percentiles = collector.parse_latency_histogram(output).unwrap();
+        assert_eq!(percentiles.p50, 0.065);
+        assert_eq!(percentiles.p95, 1000.0);
+
+        // No samples at all
+        let idle = "latency      read  write\n1us             -      0\n1ms             0      -\n";
+        assert_eq!(collector.parse_latency_histogram(idle), None);
+        assert_eq!(collector.parse_latency_histogram(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_collect_slog_latency_percentiles() {
+        let mut collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data");
+        let slog = collector.collect_slog_stats().await.unwrap().unwrap();
+        assert_eq!(slog.latency_percentiles.unwrap().p99, 4.0);
+
+        // Without a pool the device can't be named to `zpool iostat -w`
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+        let slog = collector.collect_slog_stats().await.unwrap().unwrap();
+        assert_eq!(slog.latency_percentiles, None);
+    }
+
+    #[test]
+    fn test_parse_bandwidth_bytes() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        assert_eq!(collector.parse_bandwidth("1024").unwrap(), 1024);
+        assert_eq!(collector.parse_bandwidth("0").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_parse_bandwidth_with_units() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        assert_eq!(collector.parse_bandwidth("1K").unwrap(), 1024);
+        assert_eq!(collector.parse_bandwidth("2M").unwrap(), 2 * 1024 * 1024);
+        assert_eq!(
+            collector.parse_bandwidth("1.5G").unwrap(),
+            (1.5 * 1024.0 * 1024.0 * 1024.0) as u64
+        );
+        assert_eq!(
+            collector.parse_bandwidth("1T").unwrap(),
+            1024 * 1024 * 1024 * 1024
+        );
+        assert_eq!(
+            collector.parse_bandwidth("1.5P").unwrap(),
+            3 * 512 * 1024 * 1024 * 1024 * 1024
+        );
+        assert_eq!(collector.parse_bandwidth("2P").unwrap(), 2 << 50);
+        assert_eq!(collector.parse_bandwidth("1E").unwrap(), 1 << 60);
+    }
+
+    #[test]
+    fn test_parse_bandwidth_decimal_without_unit() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        assert_eq!(collector.parse_bandwidth("12.0").unwrap(), 12);
+        assert_eq!(collector.parse_bandwidth("12.9").unwrap(), 12); // Truncated
+        assert!(collector.parse_bandwidth("-12.0").is_err());
+        assert!(collector.parse_bandwidth("inf").is_err());
+    }
+
+    #[test]
+    fn test_parse_bandwidth_zpool_iostat_values() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        assert_eq!(collector.parse_bandwidth("0B").unwrap(), 0);
+        assert_eq!(
+            collector.parse_bandwidth("1.82T").unwrap(),
+            (1.82 * 1024.0 * 1024.0 * 1024.0 * 1024.0) as u64
+        );
+        assert_eq!(collector.parse_bandwidth("23").unwrap(), 23);
+    }
+
+    #[test]
+    fn test_parse_numbers_with_decimal_comma() {
+        // What zpool prints under e.g. LC_ALL=de_DE.UTF-8
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        assert_eq!(
+            collector.parse_bandwidth("1,5M").unwrap(),
+            collector.parse_bandwidth("1.5M").unwrap()
+        );
+        assert_eq!(collector.parse_bandwidth("12,9").unwrap(), 12);
+        assert_eq!(
+            collector.parse_ops("1,2K", "iostat write_ops").unwrap(),
+            1228
+        );
+        assert_eq!(parse_percent("99,5%"), Ok(99.5));
+        assert!(collector.parse_bandwidth("1,5,0M").is_err());
+    }
+
+    #[test]
+    fn test_parse_bandwidth_empty_or_dash() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        assert_eq!(collector.parse_bandwidth("").unwrap(), 0);
+        assert_eq!(collector.parse_bandwidth("-").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_parse_bandwidth_invalid_number() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        let result = collector.parse_bandwidth("invalid");
+        assert!(result.is_err());
+
+        if let Err(ZfsError::ParseError { data_source, .. }) = result {
+            assert_eq!(data_source, "bandwidth");
+        } else {
+            panic!("Expected ParseError");
+        }
+    }
+
+    #[test]
+    fn test_parse_bandwidth_invalid_unit() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        let result = collector.parse_bandwidth("100X"); // Invalid unit
+        assert!(result.is_err());
+
+        if let Err(ZfsError::ParseError { data_source, .. }) = result {
+            assert_eq!(data_source, "bandwidth");
+        } else {
+            panic!("Expected ParseError");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collect_arc_stats_from_proc_success() {
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+        let now = Instant::now();
+
+        // This will use the demo filesystem reader which returns mock data
+        let result = collector.collect_arc_stats_from_proc(now).await;
+        // The demo data may not have the expected format, so we just check it doesn't panic
+        // In a real test, we'd mock the filesystem reader to return known data
+        let _ = result; // Just ensure it doesn't panic
+    }
+
+    #[test]
+    fn test_parse_uptime() {
+        assert_eq!(
+            parse_uptime("350735.47 234388.90\n"),
+            Some(Duration::from_secs_f64(350735.47))
+        );
+        assert_eq!(parse_uptime(""), None);
+        assert_eq!(parse_uptime("-1 0"), None);
+    }
+
+    #[tokio::test]
+    async fn test_since_boot_rates() {
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader)
+            .with_since_boot_rates(true);
+        assert_eq!(
+            collector.read_uptime().await,
+            Some(Duration::from_secs(86_400))
+        );
+        // 245,760,000 bytes read from the cache device over a day of uptime,
+        // already on the first sample
+        let l2arc = collector.collect_l2arc_stats().await.unwrap().unwrap();
+        assert_eq!(l2arc.read_bytes, 245_760_000 / 86_400);
+        // `zpool iostat` already averages the SLOG since import
+        let slog = collector.collect_slog_stats().await.unwrap().unwrap();
+        assert_eq!(slog.write_ops, 23);
+        assert_eq!(slog.write_bw, 12_582_912);
+
+        // Without /proc/uptime the rates stay between samples: none on the first
+        let mut collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, NoUptimeReader).with_since_boot_rates(true);
+        assert_eq!(collector.read_uptime().await, None);
+        let l2arc = collector.collect_l2arc_stats().await.unwrap().unwrap();
+        assert_eq!(l2arc.read_bytes, 0);
+    }
+
+    #[tokio::test]
+    async fn test_since_boot_rates_read_uptime_once_per_refresh() {
+        let reads = Arc::new(AtomicUsize::new(0));
+        let mut collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, UptimeCountingReader(reads.clone()))
+                .with_since_boot_rates(true);
+
+        for refresh in 1..=2 {
+            let now = collector.sample_time();
+            collector.mark_sample(now + Duration::from_secs(refresh));
+            collector.collect_arc_stats().await.unwrap();
+            collector.collect_l2arc_stats().await.unwrap();
+            collector.collect_slog_stats().await.unwrap();
+            assert_eq!(reads.load(Ordering::SeqCst), refresh as usize);
+        }
+    }
+
+    /// Demo files, counting the reads of /proc/uptime
+    struct UptimeCountingReader(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl FilesystemReader for UptimeCountingReader {
+        async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+            if path == "/proc/uptime" {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+            DemoFilesystemReader.read_to_string(path).await
+        }
+    }
+
+    /// Demo files, on a system without /proc/uptime
+    struct NoUptimeReader;
+
+    #[async_trait]
+    impl FilesystemReader for NoUptimeReader {
+        async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+            match path {
+                "/proc/uptime" => Err("No such file or directory".into()),
+                _ => DemoFilesystemReader.read_to_string(path).await,
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_mem_total() {
+        let meminfo = "MemTotal:       16384000 kB\nMemFree:         1024000 kB\n";
+        let total = parse_mem_total(meminfo).unwrap();
+        assert_eq!(total, 16_777_216_000);
+
+        let arc = ArcStats {
+            hit_rate: 90.0,
+            size: 4_194_304_000,
+            target: 8_388_608_000,
+            target_trend: None,
+            max: 8_388_608_000,
+            read_ops: 0,
+            hits_total: 0,
+            misses_total: 0,
+            hit_breakdown: None,
+            eviction: None,
+            metadata: None,
+            no_grow: None,
+            prunes: None,
+            memory_total: Some(total),
+            collected_at: CollectedAt::default(),
+        };
+        assert_eq!(arc.percent_of_memory(), Some(25.0));
+
+        assert_eq!(parse_mem_total("MemFree: 1024 kB\n"), None);
+        assert_eq!(parse_mem_total("MemTotal: lots kB\n"), None);
+    }
+
+    #[tokio::test]
+    async fn test_collect_arc_memory_total() {
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+        let arc = collector.collect_arc_stats().await.unwrap();
+        assert_eq!(arc.memory_total, Some(67_437_156 * 1024));
+        assert_eq!(arc.percent_of_memory().unwrap().round(), 72.0);
+
+        // No /proc/meminfo (e.g. FreeBSD): the percentage is omitted
+        let mut collector = ZfsStatsCollector::new(
+            StaticCommandExecutor(FREEBSD_SYSCTL_ARCSTATS),
+            ByIdFilesystemReader,
+        );
+        let arc = collector.collect_arc_stats().await.unwrap();
+        assert_eq!(arc.memory_total, None);
+        assert_eq!(arc.percent_of_memory(), None);
+    }
+
+    #[tokio::test]
+    async fn test_collect_arc_metadata() {
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        let arc = collector
+            .collect_arc_stats_from_proc(Instant::now())
+            .await
+            .unwrap();
+        let metadata = arc.metadata.unwrap();
+        assert_eq!(metadata.size, 1_700_000_000);
+        assert_eq!(metadata.limit, Some(37_432_921_856));
+        assert!((metadata.percent_of_arc(arc.size) - 3.42).abs() < 0.01);
+        assert!((metadata.percent_of_limit().unwrap() - 4.54).abs() < 0.01);
+
+        // OpenZFS 2.2+ only has `metadata_size`, and no limit
+        let arc = collector.arc_stats_from_kstats(
+            &[("size", 4_000_000_000), ("metadata_size", 1_000_000_000)],
+            Instant::now(),
+        );
+        let metadata = arc.metadata.unwrap();
+        assert_eq!(metadata.size, 1_000_000_000);
+        assert_eq!(metadata.percent_of_arc(arc.size), 25.0);
+        assert_eq!(metadata.percent_of_limit(), None);
+    }
+
+    #[tokio::test]
+    async fn test_collect_arc_target_and_max() {
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        let arc = collector
+            .collect_arc_stats_from_proc(Instant::now())
+            .await
+            .unwrap();
+        // Demo fixture: c = 49720066048, c_max = 49910562816
+        assert_eq!(arc.target, 49720066048);
+        assert_eq!(arc.max, 49910562816);
+    }
+
+    #[tokio::test]
+    async fn test_collect_arc_stats_from_arcstat_fallback() {
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+        let now = Instant::now();
+
+        // This will try various arcstat commands, some may succeed with demo data
+        let result = collector.collect_arc_stats_from_arcstat(now).await;
+        // We don't assert success since demo data may not match expected formats
+        let _ = result; // Just ensure it doesn't panic
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_hung_command_times_out() {
+        let mut collector = ZfsStatsCollector::new(HangingCommandExecutor, DemoFilesystemReader)
+            .with_pool("data")
+            .with_command_timeout(Duration::from_secs(2));
+
+        let start = tokio::time::Instant::now();
+        let error = collector.collect_pool_io_stats().await.unwrap_err();
+
+        // Both `zpool iostat` variants are given up on rather than waited for
+        assert_eq!(start.elapsed(), Duration::from_secs(4));
+        match error {
+            ZfsError::TimeoutError {
+                command, timeout, ..
+            } => {
+                assert_eq!(command, "zpool");
+                assert_eq!(timeout, Duration::from_secs(2));
+            }
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_arcstat_cumulative_reads_become_a_rate() {
+        let executor = ArcstatCommandExecutor {
+            calls: std::sync::atomic::AtomicU64::new(0),
+        };
+        let mut collector = ZfsStatsCollector::new(executor, UnreadableFilesystemReader);
+        let now = Instant::now();
+
+        // Accesses since boot aren't a rate until there is a previous sample
+        let arc = collector.collect_arc_stats_from_arcstat(now).await.unwrap();
+        assert_eq!(arc.read_ops, 0);
+        let arc = collector
+            .collect_arc_stats_from_arcstat(now + Duration::from_secs(2))
+            .await
+            .unwrap();
+        assert_eq!(arc.read_ops, 1000);
+    }
+
+    #[tokio::test]
+    async fn test_arcstat_per_second_reads_are_used_as_is() {
+        let executor = ArcstatCommandExecutor {
+            calls: std::sync::atomic::AtomicU64::new(0),
+        };
+        let mut collector = ZfsStatsCollector::new(executor, UnreadableFilesystemReader)
+            .with_arcstat_reads(ArcstatReads::PerSecond);
+
+        let arc = collector
+            .collect_arc_stats_from_arcstat(Instant::now())
+            .await
+            .unwrap();
+        assert_eq!(arc.read_ops, 1247);
+        assert_eq!(arc.hit_rate, 95.0);
+    }
+
+    #[test]
+    fn test_parse_arcstat_l2_output() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        let l2arc = collector
+            .parse_arcstat_l2_output(
+                "l2hit% l2miss% l2size l2read\n    73      26    553G     12\n",
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(l2arc.hit_rate, 73.0);
+        assert_eq!(l2arc.size, 553 * 1024 * 1024 * 1024);
+        assert_eq!(l2arc.total_ops, 12);
+        assert_eq!(l2arc.read_bytes_total, 0);
+
+        // No cache device
+        assert!(
+            collector
+                .parse_arcstat_l2_output("0 0 0 0")
+                .unwrap()
+                .is_none()
+        );
+
+        assert!(matches!(
+            collector.parse_arcstat_l2_output("l2hit% l2miss% l2size l2read\n"),
+            Err(ZfsError::InvalidFormat { .. })
+        ));
+        assert!(matches!(
+            collector.parse_arcstat_l2_output("73 26 553X 12"),
+            Err(ZfsError::ParseError { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_collect_l2arc_from_arcstat_fallback() {
+        // No kstats file: arcstat's L2 columns are used instead
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, ByIdFilesystemReader);
+
+        let l2arc = collector.collect_l2arc_stats().await.unwrap().unwrap();
+        assert_eq!(l2arc.hit_rate, 73.0);
+        assert_eq!(l2arc.size, 553 * 1024 * 1024 * 1024);
+    }
+
+    #[tokio::test]
+    async fn test_collect_l2arc_warming() {
+        const GIB: u64 = 1024 * 1024 * 1024;
+        let filling = |step, c_max| FillingL2FilesystemReader {
+            step,
+            c_max,
+            reads: Default::default(),
+        };
+
+        // Small and growing: warming, even though 1% is a poor hit rate
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, filling(GIB, 8 * GIB));
+        let l2arc = collector.collect_l2arc_stats().await.unwrap().unwrap();
+        assert!(!l2arc.warming, "no growth is known after a single sample");
+        collector.rewind_rate_samples(Duration::from_secs(2));
+        let l2arc = collector.collect_l2arc_stats().await.unwrap().unwrap();
+        assert_eq!(l2arc.hit_rate, 1.0);
+        assert!(l2arc.warming);
+
+        // Already larger than the ARC can grow: a low hit rate is real
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, filling(16 * GIB, 8 * GIB));
+        collector.collect_l2arc_stats().await.unwrap();
+        collector.rewind_rate_samples(Duration::from_secs(2));
+        let l2arc = collector.collect_l2arc_stats().await.unwrap().unwrap();
+        assert!(!l2arc.warming);
+
+        // Not growing
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, filling(0, 8 * GIB));
+        collector.collect_l2arc_stats().await.unwrap();
+        collector.rewind_rate_samples(Duration::from_secs(2));
+        let l2arc = collector.collect_l2arc_stats().await.unwrap().unwrap();
+        assert!(!l2arc.warming);
+    }
+
+    #[tokio::test]
+    async fn test_collect_l2arc_fill_rate() {
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        // First sample only establishes the baseline
+        let first = collector.collect_l2arc_stats().await.unwrap().unwrap();
+        assert_eq!(first.write_bytes, 0);
+
+        // Pretend l2_write_bytes (10000000 in the demo data) was 1MB lower 10 seconds ago
+        collector.rate_calculator.update(
+            "l2_write_bytes",
+            9_000_000,
+            Instant::now() - Duration::from_secs(10),
+        );
+        let second = collector.collect_l2arc_stats().await.unwrap().unwrap();
+        assert!(second.write_bytes > 90_000 && second.write_bytes <= 100_000);
+    }
+
+    #[tokio::test]
+    async fn test_collect_l2arc_errors() {
+        let arcstats = "name                            type data\n\
+                        l2_hits                         4    655000\n\
+                        l2_misses                       4    237000\n\
+                        l2_cksum_bad                    4    0\n\
+                        l2_io_error                     4    7\n\
+                        l2_size                         4    594542387200\n";
+        let mut collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, StaticFilesystemReader(arcstats));
+
+        let l2arc = collector.collect_l2arc_stats().await.unwrap().unwrap();
+        assert_eq!(l2arc.io_errors, 7);
+        assert_eq!(l2arc.cksum_bad, 0);
+        assert!(l2arc.has_errors());
+    }
+
+    /// Filesystem reader serving the given arcstats contents one refresh at a time
+    struct SequenceFilesystemReader(std::sync::Mutex<Vec<&'static str>>);
+
+    #[async_trait]
+    impl FilesystemReader for SequenceFilesystemReader {
+        async fn read_to_string(&self, path: &str) -> Result<String, Box<dyn Error>> {
+            match path {
+                "/proc/spl/kstat/zfs/arcstats" => Ok(self.0.lock().unwrap().remove(0).to_string()),
+                _ => Err(format!("Not mocked: {}", path).into()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collect_arc_eviction_pressure() {
+        let mut collector = ZfsStatsCollector::new(
+            DemoCommandExecutor,
+            SequenceFilesystemReader(std::sync::Mutex::new(vec![
+                "hits 4 900\nmisses 4 100\nsize 4 1024\nc_max 4 2048\n\
+                 mutex_miss 4 5\nevict_skip 4 1000\nevict_not_enough 4 10\n",
+                "hits 4 950\nmisses 4 150\nsize 4 1024\nc_max 4 2048\n\
+                 mutex_miss 4 5\nevict_skip 4 5500\nevict_not_enough 4 40\n",
+            ])),
+        );
+
+        // Only a baseline so far, so nothing is failing yet
+        let arc = collector.collect_arc_stats().await.unwrap();
+        assert_eq!(arc.eviction.unwrap().pressure(), ArcPressure::None);
+
+        // 4500 skips over the (rewound) 10s since then is ~450/s
+        collector.rewind_rate_samples(Duration::from_secs(10));
+        let arc = collector.collect_arc_stats().await.unwrap();
+        let eviction = arc.eviction.unwrap();
+        assert!((440..=450).contains(&eviction.evict_skip));
+        assert!((2..=3).contains(&eviction.evict_not_enough));
+        assert_eq!(eviction.mutex_miss, 0);
+        assert_eq!(eviction.pressure(), ArcPressure::Medium);
+
+        // arcstat doesn't report the eviction counters
+        let stats = collector
+            .parse_arcstat_output("95.2 1234 5368709120 8589934592")
+            .unwrap();
+        assert_eq!(stats.eviction, None);
+    }
+
+    #[tokio::test]
+    async fn test_collect_arc_no_grow() {
+        let mut collector = ZfsStatsCollector::new(
+            DemoCommandExecutor,
+            SequenceFilesystemReader(std::sync::Mutex::new(vec![
+                "hits 4 900\nmisses 4 100\nsize 4 1024\nc 4 1536\nc_max 4 2048\n\
+                 arc_no_grow 4 1\narc_prune 4 100\n",
+                "hits 4 950\nmisses 4 150\nsize 4 1024\nc 4 1536\nc_max 4 2048\n\
+                 arc_no_grow 4 1\narc_prune 4 600\n",
+            ])),
+        );
+
+        let arc = collector.collect_arc_stats().await.unwrap();
+        assert_eq!(arc.no_grow, Some(true));
+        assert_eq!(arc.prunes, Some(0));
+
+        // 500 prunes over the (rewound) 10s since then is 50/s
+        collector.rewind_rate_samples(Duration::from_secs(10));
+        let arc = collector.collect_arc_stats().await.unwrap();
+        assert_eq!(arc.no_grow, Some(true));
+        assert!((49..=50).contains(&arc.prunes.unwrap()));
+
+        let arc = collector.arc_stats_from_kstats(&[("arc_no_grow", 0)], Instant::now());
+        assert_eq!(arc.no_grow, Some(false));
+        assert_eq!(arc.prunes, None);
+    }
+
+    #[tokio::test]
+    async fn test_collect_arc_without_no_grow() {
+        // The demo arcstats, like some ZFS versions, export neither flag
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+        let arc = collector.collect_arc_stats().await.unwrap();
+        assert_eq!(arc.no_grow, None);
+        assert_eq!(arc.prunes, None);
+    }
+
+    #[test]
+    fn test_arc_pressure_levels() {
+        let pressure = |evict_skip, evict_not_enough| {
+            ArcEviction {
+                evict_skip,
+                evict_not_enough,
+                mutex_miss: 0,
+            }
+            .pressure()
+        };
+        assert_eq!(pressure(0, 0), ArcPressure::None);
+        assert_eq!(pressure(0, 4), ArcPressure::Low);
+        assert_eq!(pressure(99, 0), ArcPressure::Low);
+        assert_eq!(pressure(100, 0), ArcPressure::Medium);
+        assert_eq!(pressure(1000, 0), ArcPressure::High);
+    }
+
+    #[tokio::test]
+    async fn test_collect_arc_hit_breakdown() {
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        let arc = collector.collect_arc_stats().await.unwrap();
+        let breakdown = arc.hit_breakdown.unwrap();
+        assert_eq!(breakdown.demand_data, 7220000);
+        assert_eq!(breakdown.demand_metadata, 2090000);
+        assert_eq!(breakdown.prefetch, 145000 + 45000);
+        assert_eq!(breakdown.total(), arc.hits_total);
+
+        // The arcstat fallback has no breakdown
+        let stats = collector
+            .parse_arcstat_output("95.2 1234 5368709120 8589934592")
+            .unwrap();
+        assert_eq!(stats.hit_breakdown, None);
+    }
+
+    #[tokio::test]
+    async fn test_collect_totals_carried_through() {
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
 
-        let result = collector.parse_arcstat_output(output);
-        assert!(result.is_err());
+        let arc = collector.collect_arc_stats().await.unwrap();
+        assert_eq!(arc.hits_total, 9500000);
+        assert_eq!(arc.misses_total, 300000);
 
-        if let Err(ZfsError::ParseError { data_source, .. }) = result {
-            assert_eq!(data_source, "arcstat hit_rate");
-        } else {
-            panic!("Expected ParseError");
-        }
+        let l2arc = collector.collect_l2arc_stats().await.unwrap().unwrap();
+        assert_eq!(l2arc.read_bytes_total, 245760000);
+        assert_eq!(l2arc.write_bytes_total, 10000000);
+
+        let slog = collector.collect_slog_stats().await.unwrap().unwrap();
+        assert_eq!(slog.write_ops_avg, 23);
+        assert_eq!(slog.write_bw_avg, 12582912);
     }
 
-    #[test]
-    fn test_parse_bandwidth_bytes() {
-        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+    #[tokio::test]
+    async fn test_collect_l2arc_no_errors() {
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
 
-        assert_eq!(collector.parse_bandwidth("1024").unwrap(), 1024);
-        assert_eq!(collector.parse_bandwidth("0").unwrap(), 0);
+        let l2arc = collector.collect_l2arc_stats().await.unwrap().unwrap();
+        assert_eq!(l2arc.io_errors, 0);
+        assert_eq!(l2arc.cksum_bad, 0);
+        assert!(!l2arc.has_errors());
     }
 
     #[test]
-    fn test_parse_bandwidth_with_units() {
+    fn test_parse_pool_io_from_iostat() {
         let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
 
-        assert_eq!(collector.parse_bandwidth("1K").unwrap(), 1024);
-        assert_eq!(collector.parse_bandwidth("2M").unwrap(), 2 * 1024 * 1024);
-        assert_eq!(
-            collector.parse_bandwidth("1.5G").unwrap(),
-            (1.5 * 1024.0 * 1024.0 * 1024.0) as u64
-        );
-        assert_eq!(
-            collector.parse_bandwidth("1T").unwrap(),
-            1024 * 1024 * 1024 * 1024
-        );
+        let output = include_str!("../demo/zpool_iostat.txt");
+        let pool_io = collector
+            .parse_pool_io_from_iostat(output, Some("data"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(pool_io.pool, "data");
+        assert_eq!(pool_io.read_ops, 47);
+        assert_eq!(pool_io.write_ops, 23);
+        assert_eq!(pool_io.read_bw, 234 * 1024 * 1024);
+        assert_eq!(pool_io.write_bw, 12 * 1024 * 1024);
+        assert!((pool_io.capacity_percent().unwrap() - 57.5).abs() < 0.1);
+
+        // The scripted format yields the same row, and the first pool is the default
+        let output = include_str!("../demo/zpool_iostat_parseable.txt");
+        let pool_io = collector
+            .parse_pool_io_from_iostat(output, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(pool_io.pool, "data");
+        assert_eq!(pool_io.read_bw, 245366784);
+        assert_eq!(pool_io.write_ops, 23);
+        assert_eq!(pool_io.alloc, 3793315160064);
+        assert_eq!(pool_io.free, 2803752878080);
+
+        // Unknown pool
+        let result = collector.parse_pool_io_from_iostat(output, Some("tank"));
+        assert!(result.unwrap().is_none());
     }
 
-    #[test]
-    fn test_parse_bandwidth_empty_or_dash() {
-        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+    #[tokio::test]
+    async fn test_collect_pool_io_stats_demo() {
+        let mut collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data");
 
-        assert_eq!(collector.parse_bandwidth("").unwrap(), 0);
-        assert_eq!(collector.parse_bandwidth("-").unwrap(), 0);
+        let pool_io = collector.collect_pool_io_stats().await.unwrap().unwrap();
+        assert_eq!(pool_io.read_ops, 47);
+        assert_eq!(pool_io.write_bw, 12582912);
     }
 
     #[test]
-    fn test_parse_bandwidth_invalid_number() {
-        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+    fn test_parse_objset_kstat() {
+        let content = "\
+25 1 0x01 7 2160 6165208925 5231496820934
+name                            type data
+dataset_name                    7    tank/vm/disk0
+writes                          4    1832954
+nwritten                        4    61462937600
+reads                           4    5190347
+nread                           4    214748364800
+nunlinks                        4    27041
+nunlinked                       4    27041
+";
+        let stats = parse_objset_kstat(content).unwrap();
+        assert_eq!(stats.name, "tank/vm/disk0");
+        assert_eq!(stats.reads_total, 5190347);
+        assert_eq!(stats.writes_total, 1832954);
+        assert_eq!(stats.read_bytes_total, 214748364800);
+        assert_eq!(stats.write_bytes_total, 61462937600);
+        assert_eq!((stats.reads, stats.writes), (0, 0));
+
+        assert!(parse_objset_kstat("name type data\n").is_err());
+        assert!(parse_objset_kstat("reads 4 many\n").is_err());
+    }
 
-        let result = collector.parse_bandwidth("invalid");
-        assert!(result.is_err());
+    #[tokio::test]
+    async fn test_collect_dataset_stats() {
+        let executor = RecordingCommandExecutor::new(DemoCommandExecutor);
+        let log = executor.log();
+        let mut collector = ZfsStatsCollector::new(executor, DemoFilesystemReader);
+
+        // The demo's data/home has objset ID 54, i.e. /proc/spl/kstat/zfs/data/objset-0x36
+        let stats = collector.collect_dataset_stats("data/home").await.unwrap();
+        assert_eq!(stats.name, "data/home");
+        assert_eq!(stats.reads_total, 5190347);
+        assert_eq!(stats.write_bytes_total, 61462937600);
+
+        // Rates come from the second sample on; the objset ID is only looked up once
+        collector.rewind_rate_samples(Duration::from_secs(1));
+        let stats = collector.collect_dataset_stats("data/home").await.unwrap();
+        assert_eq!(stats.reads, 0);
+        assert_eq!(
+            log.calls()
+                .iter()
+                .filter(|(command, _)| command == "zfs")
+                .count(),
+            1
+        );
 
-        if let Err(ZfsError::ParseError { data_source, .. }) = result {
-            assert_eq!(data_source, "bandwidth");
-        } else {
-            panic!("Expected ParseError");
-        }
+        assert!(
+            collector
+                .collect_dataset_stats("data/missing")
+                .await
+                .is_err()
+        );
     }
 
-    #[test]
-    fn test_parse_bandwidth_invalid_unit() {
-        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+    #[tokio::test]
+    async fn test_collect_slog_stats_cached() {
+        let executor = RecordingCommandExecutor::new(DemoCommandExecutor);
+        let log = executor.log();
+        let mut collector = ZfsStatsCollector::new(executor, DemoFilesystemReader);
 
-        let result = collector.parse_bandwidth("100X"); // Invalid unit
-        assert!(result.is_err());
+        // First call should populate cache
+        collector.collect_slog_stats().await.unwrap();
+        let first_calls = log.count("zpool");
+        assert!(first_calls > 0);
+
+        // Second call should use cache, only sampling `zpool iostat` again
+        collector.collect_slog_stats().await.unwrap();
+        assert_eq!(log.count("zpool"), first_calls + 1);
+        let (_, args) = log.calls().pop().unwrap();
+        assert_eq!(args, ["iostat", "-v", "-H", "-p", "1", "2"]);
+    }
 
-        if let Err(ZfsError::ParseError { data_source, .. }) = result {
-            assert_eq!(data_source, "bandwidth");
-        } else {
-            panic!("Expected ParseError");
+    #[tokio::test]
+    async fn test_collect_slog_stats_sampled_rates() {
+        let data = SharedDemoData::new(DEMO_SEED);
+        let mut collector =
+            ZfsStatsCollector::new(DynamicDemoCommandExecutor::new(data), DemoFilesystemReader)
+                .with_pool("data");
+
+        // Every tick shows the interval sample, not a change in the averages
+        for tick in 1..=2 {
+            let now = collector.sample_time();
+            collector.mark_sample(now + Duration::from_secs(tick));
+            let slog = collector.collect_slog_stats().await.unwrap().unwrap();
+            assert!((93..=219).contains(&slog.write_ops), "{}", slog.write_ops);
+            assert!(slog.write_bw > 7_000_000, "{}", slog.write_bw);
+            assert_eq!(slog.write_ops_avg, 23);
         }
     }
 
-    #[tokio::test]
-    async fn test_collect_arc_stats_from_proc_success() {
-        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
-        let now = Instant::now();
+    /// Demo commands whose `zpool iostat` interval samples report the SLOG writing
+    /// each of the given bandwidths in turn, at a 12 MiB/s average since import
+    struct SlogBurstCommandExecutor {
+        bandwidths: &'static [u64],
+        samples: AtomicUsize,
+    }
 
-        // This will use the demo filesystem reader which returns mock data
-        let result = collector.collect_arc_stats_from_proc(now).await;
-        // The demo data may not have the expected format, so we just check it doesn't panic
-        // In a real test, we'd mock the filesystem reader to return known data
-        let _ = result; // Just ensure it doesn't panic
+    #[async_trait]
+    impl CommandExecutor for SlogBurstCommandExecutor {
+        async fn execute(&self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+            if !args.ends_with(&SLOG_SAMPLE) {
+                return DemoCommandExecutor.execute(command, args).await;
+            }
+            let sample = self.samples.fetch_add(1, Ordering::SeqCst);
+            Ok(format!(
+                "mirror-1\t0\t2001111162880\t0\t23\t0\t12582912\n\
+                 mirror-1\t0\t2001111162880\t0\t100\t0\t{}\n",
+                self.bandwidths[sample]
+            ))
+        }
     }
 
     #[tokio::test]
-    async fn test_collect_arc_stats_from_arcstat_fallback() {
-        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
-        let now = Instant::now();
+    async fn test_collect_slog_stats_peak_of_sampled_rates() {
+        let executor = SlogBurstCommandExecutor {
+            bandwidths: &[50_000_000, 1_000_000],
+            samples: AtomicUsize::new(0),
+        };
+        let mut collector = ZfsStatsCollector::new(executor, DemoFilesystemReader);
 
-        // This will try various arcstat commands, some may succeed with demo data
-        let result = collector.collect_arc_stats_from_arcstat(now).await;
-        // We don't assert success since demo data may not match expected formats
-        let _ = result; // Just ensure it doesn't panic
+        let slog = collector.collect_slog_stats().await.unwrap().unwrap();
+        assert_eq!(slog.write_bw_peak, 50_000_000);
+
+        // The burst stays the peak once writes calm down
+        let slog = collector.collect_slog_stats().await.unwrap().unwrap();
+        assert_eq!(slog.write_bw, 1_000_000);
+        assert_eq!(slog.write_bw_peak, 50_000_000);
     }
 
     #[tokio::test]
-    async fn test_collect_slog_stats_cached() {
-        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+    async fn test_collect_slog_stats_cache_age() {
+        // Only the since-boot averages are served from cache
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader)
+            .with_since_boot_rates(true);
+
+        // Fresh fetch carries no age
+        let slog = collector.collect_slog_stats().await.unwrap().unwrap();
+        assert_eq!(slog.cache_age, None);
+
+        // Served from cache on the next refresh
+        let slog = collector.collect_slog_stats().await.unwrap().unwrap();
+        assert!(slog.cache_age.is_some());
+    }
 
-        // First call should populate cache
-        let result1 = collector.collect_slog_stats().await;
-        // Second call should use cache
-        let result2 = collector.collect_slog_stats().await;
+    #[test]
+    fn test_parse_arcstat_output_header_line() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+        let without_header = collector
+            .parse_arcstat_output("95.2 1234 5368709120 8589934592\n")
+            .unwrap();
+        let with_header = collector
+            .parse_arcstat_output("hit% miss% read arcsz c\n95.2 1234 5368709120 8589934592\n")
+            .unwrap();
+
+        for stats in [&without_header, &with_header] {
+            assert_eq!(stats.hit_rate, 95.2);
+            assert_eq!(stats.read_ops, 1234);
+            assert_eq!(stats.size, 5368709120);
+            assert_eq!(stats.target, 8589934592);
+        }
 
-        // Both should complete without panicking
-        let _ = result1;
-        let _ = result2;
+        // A header without a data row is still an error
+        assert!(
+            collector
+                .parse_arcstat_output("hit% miss% read arcsz c\n")
+                .is_err()
+        );
     }
 
+    #[test]
+    fn test_parse_arcstat_output_percent_signs() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        for output in [
+            "100% 0% 1247 49720066048 49910562816",
+            "hit% miss% read arcsz c\n100% 0% 1247 49720066048 49910562816\n",
+            "100.0 0.0 1247 49720066048 49910562816",
+        ] {
+            let stats = collector.parse_arcstat_output(output).unwrap();
+            assert_eq!(stats.hit_rate, 100.0);
+            assert_eq!(stats.read_ops, 1247);
+            assert_eq!(stats.size, 49720066048);
+            assert_eq!(stats.target, 49910562816);
+        }
 
+        let l2 = collector
+            .parse_arcstat_l2_output("l2hit% l2miss% l2size l2read\n73% 26% 553G 12\n")
+            .unwrap()
+            .unwrap();
+        assert_eq!(l2.hit_rate, 73.0);
+        assert!(collector.parse_arcstat_output("%% 0% 1247 1 2").is_err());
+    }
 
     #[test]
     fn test_parse_arcstat_output_edge_cases() {
@@ -682,8 +2855,6 @@ mod tests {
         }
     }
 
-
-
     #[test]
     fn test_parse_bandwidth_edge_cases() {
         let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
@@ -744,6 +2915,400 @@ config:
         let result = collector.parse_slog_device_from_status(status_output_no_slog);
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), None);
+
+        // Test with a single (non-mirrored) log device
+        let status_output_single = r#"
+logs
+  ata-Samsung_SSD_860_EVO_250GB_S3YJNX0N1234567  ONLINE       0     0     0
+"#;
+
+        let result = collector.parse_slog_device_from_status(status_output_single);
+        assert_eq!(
+            result.unwrap(),
+            Some("ata-Samsung_SSD_860_EVO_250GB_S3YJNX0N1234567".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_slog_device_from_tab_and_space_indented_status() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+        let status = |indent: &str, nested: &str| {
+            format!(
+                "  pool: tank\n config:\n\n\
+                 {i}NAME        STATE     READ WRITE CKSUM\n\
+                 {i}tank        ONLINE       0     0     0\n\
+                 {n}  sda       ONLINE       0     0     0\n\
+                 {i}logs\n\
+                 {n}  nvme0n1   ONLINE       0     0     0\n\
+                 {i}cache\n\
+                 {n}  nvme1n1   ONLINE       0     0     0\n",
+                i = indent,
+                n = nested,
+            )
+        };
+
+        for (indent, nested) in [
+            ("\t", "\t"),
+            ("        ", "        "),
+            // Header re-indented with spaces, vdevs still on a tab
+            ("        ", "\t"),
+            ("\t", "        "),
+        ] {
+            let vdevs = collector.parse_pool_vdevs(&status(indent, nested));
+            assert_eq!(vdevs.log, vec!["nvme0n1"], "{:?} / {:?}", indent, nested);
+            assert_eq!(vdevs.cache, vec!["nvme1n1"], "{:?} / {:?}", indent, nested);
+        }
+
+        // The demo fixture is tab-indented
+        let demo = include_str!("../demo/zpool_status.txt");
+        assert!(demo.contains("\n\tlogs\n"));
+        assert_eq!(
+            collector.parse_slog_device_from_status(demo).unwrap(),
+            Some("mirror-1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_indent_width() {
+        assert_eq!(indent_width("logs"), 0);
+        assert_eq!(indent_width("    logs"), 4);
+        assert_eq!(indent_width("\tlogs"), 8);
+        assert_eq!(indent_width("\t  mirror-1"), 10);
+        assert_eq!(indent_width("  \tlogs"), 8);
+    }
+
+    #[test]
+    fn test_parse_slog_device_from_multi_pool_status() {
+        let status_output = r#"
+  pool: backup
+ state: ONLINE
+config:
+
+	NAME        STATE     READ WRITE CKSUM
+	backup      ONLINE       0     0     0
+	  sda       ONLINE       0     0     0
+	logs
+	  nvme0n1   ONLINE       0     0     0
+
+errors: No known data errors
+
+  pool: tank
+ state: ONLINE
+config:
+
+	NAME        STATE     READ WRITE CKSUM
+	tank        ONLINE       0     0     0
+	  mirror-0  ONLINE       0     0     0
+	    sdb     ONLINE       0     0     0
+	    sdc     ONLINE       0     0     0
+	logs
+	  mirror-1  ONLINE       0     0     0
+	    sdd     ONLINE       0     0     0
+	    sde     ONLINE       0     0     0
+
+errors: No known data errors
+"#;
+
+        let backup =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("backup");
+        assert_eq!(
+            backup.parse_slog_device_from_status(status_output).unwrap(),
+            Some("nvme0n1".to_string())
+        );
+
+        let tank =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("tank");
+        assert_eq!(
+            tank.parse_slog_device_from_status(status_output).unwrap(),
+            Some("mirror-1".to_string())
+        );
+
+        let missing =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("usb");
+        assert_eq!(
+            missing
+                .parse_slog_device_from_status(status_output)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_pool_vdevs() {
+        let status_output = "  pool: tank
+ state: ONLINE
+config:
+
+\tNAME          STATE     READ WRITE CKSUM
+\ttank          ONLINE       0     0     0
+\t  raidz2-0    ONLINE       0     0     0
+\t    sda       ONLINE       0     0     0
+\t    sdb       ONLINE       0     0     0
+\t    sdc       ONLINE       0     0     0
+\tspecial
+\t  mirror-1    ONLINE       0     0     0
+\t    nvme0n1   ONLINE       0     0     0
+\t    nvme1n1   ONLINE       0     0     0
+\tdedup
+\t  mirror-2    ONLINE       0     0     0
+\t    nvme2n1   ONLINE       0     0     0
+\t    nvme3n1   ONLINE       0     0     0
+\tlogs
+\t  nvme4n1p1   ONLINE       0     0     0
+\tcache
+\t  nvme5n1     ONLINE       0     0     0
+\t  nvme6n1     ONLINE       0     0     0
+\tspares
+\t  sdz         AVAIL
+
+errors: No known data errors
+";
+        let collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("tank");
+
+        let vdevs = collector.parse_pool_vdevs(status_output);
+        assert_eq!(
+            vdevs,
+            PoolVdevs {
+                data: vec!["raidz2-0".to_string()],
+                log: vec!["nvme4n1p1".to_string()],
+                cache: vec!["nvme5n1".to_string(), "nvme6n1".to_string()],
+                special: vec!["mirror-1".to_string()],
+                dedup: vec!["mirror-2".to_string()],
+                unhealthy: Vec::new(),
+                state: Some("ONLINE".to_string()),
+            }
+        );
+        assert_eq!(
+            collector
+                .parse_slog_device_from_status(status_output)
+                .unwrap(),
+            Some("nvme4n1p1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_pool_vdevs_without_logs() {
+        // A special vdev must not be mistaken for a SLOG, nor a cache device
+        let status_output = "  pool: tank
+config:
+
+\tNAME        STATE     READ WRITE CKSUM
+\ttank        ONLINE       0     0     0
+\t  sda       ONLINE       0     0     0
+\t  sdb       ONLINE       0     0     0
+\tspecial
+\t  nvme0n1   ONLINE       0     0     0
+\tcache
+\t  nvme1n1   ONLINE       0     0     0
+";
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        let vdevs = collector.parse_pool_vdevs(status_output);
+        assert_eq!(vdevs.data, vec!["sda".to_string(), "sdb".to_string()]);
+        assert_eq!(vdevs.special, vec!["nvme0n1".to_string()]);
+        assert_eq!(vdevs.cache, vec!["nvme1n1".to_string()]);
+        assert!(vdevs.log.is_empty());
+        assert_eq!(
+            collector
+                .parse_slog_device_from_status(status_output)
+                .unwrap(),
+            None
+        );
+    }
+
+    const FAULTED_SLOG_STATUS: &str = "  pool: tank
+ state: DEGRADED
+config:
+
+\tNAME          STATE     READ WRITE CKSUM
+\ttank          DEGRADED     0     0     0
+\t  mirror-0    ONLINE       0     0     0
+\t    sda       ONLINE       0     0     0
+\t    sdb       ONLINE       0     0     0
+\tlogs
+\t  nvme0n1p1   FAULTED      3    12     0  too many errors
+";
+
+    const UNAVAIL_SLOG_STATUS: &str = "  pool: tank
+ state: DEGRADED
+config:
+
+\tNAME                     STATE     READ WRITE CKSUM
+\ttank                     DEGRADED     0     0     0
+\t  sda                    ONLINE       0     0     0
+\tlogs
+\t  7396032830195427186    UNAVAIL      0     0     0  was /dev/nvme0n1p1
+";
+
+    #[test]
+    fn test_parse_pool_vdevs_faulted_log() {
+        let collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("tank");
+
+        let vdevs = collector.parse_pool_vdevs(FAULTED_SLOG_STATUS);
+        assert_eq!(vdevs.log, vec!["nvme0n1p1".to_string()]);
+        assert_eq!(vdevs.state_of("nvme0n1p1"), Some("FAULTED"));
+        assert_eq!(vdevs.state_of("mirror-0"), None);
+        assert_eq!(vdevs.state.as_deref(), Some("DEGRADED"));
+
+        let vdevs = collector.parse_pool_vdevs(UNAVAIL_SLOG_STATUS);
+        assert_eq!(vdevs.log, vec!["7396032830195427186".to_string()]);
+        assert_eq!(vdevs.state_of("7396032830195427186"), Some("UNAVAIL"));
+    }
+
+    #[tokio::test]
+    async fn test_collect_slog_stats_faulted() {
+        for (status, device, state) in [
+            (FAULTED_SLOG_STATUS, "nvme0n1p1", "FAULTED"),
+            (UNAVAIL_SLOG_STATUS, "7396032830195427186", "UNAVAIL"),
+        ] {
+            // `zpool iostat` would get the status output back and fail to parse it
+            let mut collector =
+                ZfsStatsCollector::new(StaticCommandExecutor(status), DemoFilesystemReader)
+                    .with_pool("tank");
+
+            let slog = collector.collect_slog_stats().await.unwrap().unwrap();
+            assert_eq!(slog.device, device);
+            assert_eq!(slog.fault.as_deref(), Some(state));
+            assert_eq!(slog.write_bw, 0);
+            assert_eq!(collector.pool_state(), Some("DEGRADED"));
+        }
+    }
+
+    /// Demo commands of a `zpool` without scripted output (`-H -p`), whose unscoped
+    /// `zpool iostat -v` lists another pool with a "mirror-1" log of its own first
+    struct TwoPoolIostatExecutor;
+
+    #[async_trait]
+    impl CommandExecutor for TwoPoolIostatExecutor {
+        async fn execute(&self, command: &str, args: &[&str]) -> Result<String, Box<dyn Error>> {
+            match args {
+                ["iostat", "-v", "-H", "-p", ..] => Err("invalid option 'H'".into()),
+                ["iostat", "-v", "data", ..] => DemoCommandExecutor.execute(command, args).await,
+                ["iostat", "-v", ..] => Ok(concat!(
+                    "backup      1.00T  1.00T      5    999    10M   999M\n",
+                    "logs            -      -      -      -      -      -\n",
+                    "  mirror-1     0B  1.82T      0    999      0   999M\n",
+                )
+                .to_string()),
+                _ => DemoCommandExecutor.execute(command, args).await,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_iostat_fallback_scoped_to_pool() {
+        let mut collector =
+            ZfsStatsCollector::new(TwoPoolIostatExecutor, DemoFilesystemReader).with_pool("data");
+
+        let slog = collector.collect_slog_stats().await.unwrap().unwrap();
+        assert_eq!((slog.write_ops, slog.write_ops_avg), (23, 23));
+        let pool_io = collector.collect_pool_io_stats().await.unwrap().unwrap();
+        assert_eq!(pool_io.pool, "data");
+        assert_eq!(pool_io.write_ops, 23);
+    }
+
+    #[tokio::test]
+    async fn test_raw_inputs_scoped_to_pool() {
+        let mut collector =
+            ZfsStatsCollector::new(TwoPoolIostatExecutor, DemoFilesystemReader).with_pool("data");
+
+        let inputs = collector.raw_inputs().await;
+        let (_, iostat) = inputs
+            .iter()
+            .find(|(source, _)| *source == "zpool-iostat-v")
+            .unwrap();
+        assert_eq!(iostat, include_str!("../demo/zpool_iostat.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_collect_slog_stats_scoped_to_pool() {
+        let executor = RecordingCommandExecutor::new(DemoCommandExecutor);
+        let log = executor.log();
+        let mut collector =
+            ZfsStatsCollector::new(executor, DemoFilesystemReader).with_pool("data");
+
+        let slog = collector.collect_slog_stats().await.unwrap().unwrap();
+        assert_eq!(slog.device, "mirror-1");
+        assert!(log.calls().contains(&(
+            "zpool".to_string(),
+            vec!["status".to_string(), "data".to_string()]
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_collect_slog_stats_with_non_utf8_output() {
+        let mut collector =
+            ZfsStatsCollector::new(NonUtf8CommandExecutor, DemoFilesystemReader).with_pool("data");
+
+        let slog = collector.collect_slog_stats().await.unwrap().unwrap();
+        assert_eq!(slog.device, "mirror-1");
+    }
+
+    #[tokio::test]
+    async fn test_collect_slog_stats_permission_denied() {
+        let mut collector =
+            ZfsStatsCollector::new(UnprivilegedCommandExecutor, DemoFilesystemReader)
+                .with_pool("data");
+
+        let error = collector.collect_slog_stats().await.unwrap_err();
+        assert!(matches!(
+            &error,
+            ZfsError::SubsystemUnavailable { subsystem, .. } if subsystem == "SLOG"
+        ));
+        assert_eq!(
+            error.to_string(),
+            "SLOG subsystem unavailable: `zpool status data` needs elevated privileges, \
+             run with sudo for SLOG stats"
+        );
+
+        // ARC comes from kstats and is unaffected
+        assert!(collector.collect_arc_stats().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_collected_at_increases() {
+        let mut collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data");
+
+        let first = collector.collect_arc_stats().await.unwrap();
+        let first_slog = collector.collect_slog_stats().await.unwrap().unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let second = collector.collect_arc_stats().await.unwrap();
+        let second_slog = collector.collect_slog_stats().await.unwrap().unwrap();
+
+        assert!(second.collected_at.0 > first.collected_at.0);
+        assert!(second_slog.collected_at.0 > first_slog.collected_at.0);
+        // Timing alone doesn't make stats differ
+        assert_eq!(first.collected_at, second.collected_at);
+    }
+
+    #[test]
+    fn test_mark_sample() {
+        let mut collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+        let t0 = Instant::now();
+
+        assert_eq!(collector.mark_sample(t0), None);
+        assert_eq!(
+            collector.mark_sample(t0 + Duration::from_millis(2300)),
+            Some(Duration::from_millis(2300))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_short_device_name() {
+        let device = "ata-Samsung_SSD_860_EVO_250GB_S3YJNX0N1234567";
+
+        // Disabled by default: identifiers are shown as-is
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, ByIdFilesystemReader);
+        assert_eq!(collector.short_device_name(device).await, device);
+
+        let collector = collector.with_device_name_resolution(true);
+        assert_eq!(collector.short_device_name(device).await, "sdc");
+
+        // Unresolvable names fall back to the raw identifier
+        assert_eq!(collector.short_device_name("mirror-1").await, "mirror-1");
     }
 
     #[test]
@@ -767,6 +3332,60 @@ testpool                   1.23T  2.34T      0     23      0  12.0M
         assert_eq!(write_bw, 12 * 1024 * 1024); // 12.0M in bytes
     }
 
+    #[test]
+    fn test_parse_slog_stats_from_iostat_parseable() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        let iostat_output = "testpool\t1352399302656\t2572850757632\t0\t23\t0\t12582912\n\
+                             raidz1-0\t1352399302656\t2572850757632\t0\t0\t0\t0\n\
+                             logs\t-\t-\t-\t-\t-\t-\n\
+                             mirror-1\t0\t2001111162880\t0\t23\t0\t12582912\n";
+
+        let result = collector.parse_slog_stats_from_iostat_parseable(iostat_output, "mirror-1");
+        assert_eq!(result.unwrap(), (23, 12_582_912));
+
+        // Devices missing from the output report no activity
+        let result = collector.parse_slog_stats_from_iostat_parseable(iostat_output, "mirror-9");
+        assert_eq!(result.unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn test_parse_slog_stats_from_iostat_parseable_invalid() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        // Human-formatted suffixes are not valid in parseable mode
+        let result = collector.parse_slog_stats_from_iostat_parseable(
+            "mirror-1\t0\t1.82T\t0\t23\t0\t12.0M",
+            "mirror-1",
+        );
+        if let Err(ZfsError::ParseError { data_source, .. }) = result {
+            assert_eq!(data_source, "parseable iostat write_bw");
+        } else {
+            panic!("Expected ParseError");
+        }
+
+        let result =
+            collector.parse_slog_stats_from_iostat_parseable("mirror-1\t0\t23", "mirror-1");
+        assert!(matches!(result, Err(ZfsError::InvalidFormat { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_collect_slog_stats_parseable_demo() {
+        let mut collector =
+            ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader).with_pool("data");
+
+        let (average, current) = collector
+            .collect_slog_iostat_parseable("mirror-1", true)
+            .await
+            .unwrap();
+        assert_eq!(average, (23, 12_582_912));
+        // The fixture has no second report, so the averages are all there is
+        assert_eq!(current, average);
+
+        let slog = collector.collect_slog_stats().await.unwrap().unwrap();
+        assert_eq!(slog.device, "mirror-1");
+    }
+
     #[test]
     fn test_parse_slog_stats_from_iostat_no_device() {
         let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
@@ -783,6 +3402,54 @@ testpool                   1.23T  2.34T      0     23      0  12.0M
         assert_eq!(write_bw, 0);
     }
 
+    #[test]
+    fn test_parse_slog_stats_from_iostat_idle_pool() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        // Every row before the device is all "-"; a busier device with a name that
+        // contains "mirror-1" comes first, so only an exact name match finds (0, 0)
+        let iostat_output = r#"
+                 capacity     operations     bandwidth
+pool           alloc   free   read  write   read  write
+-------------  -----  -----  -----  -----  -----  -----
+data               -      -      -      -      -      -
+logs               -      -      -      -      -      -
+  mirror-10        -      -      0     99      0   1.0M
+  mirror-1         -      -      0      0      0      0
+-------------  -----  -----  -----  -----  -----  -----
+"#;
+
+        let result = collector.parse_slog_stats_from_iostat(iostat_output, "mirror-1");
+        assert_eq!(result.unwrap(), (0, 0));
+        let result = collector.parse_slog_stats_from_iostat(iostat_output, "mirror-10");
+        assert_eq!(result.unwrap(), (99, 1024 * 1024));
+    }
+
+    #[test]
+    fn test_parse_slog_stats_from_iostat_wrapped_name() {
+        let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
+
+        // Long device names get a line of their own, even when idle
+        let iostat_output = r#"
+logs                                                -      -      -      -      -      -
+  nvme-Samsung_SSD_970_EVO_Plus_1TB_S4EWNX0R123456
+                                                    -      -      -      -      -      -
+  ata-Samsung_SSD_860_EVO_250GB_S3YJNX0N1234567
+                                                    0B  1.82T      0     23      0  12.0M
+"#;
+
+        let result = collector.parse_slog_stats_from_iostat(
+            iostat_output,
+            "nvme-Samsung_SSD_970_EVO_Plus_1TB_S4EWNX0R123456",
+        );
+        assert_eq!(result.unwrap(), (0, 0));
+        let result = collector.parse_slog_stats_from_iostat(
+            iostat_output,
+            "ata-Samsung_SSD_860_EVO_250GB_S3YJNX0N1234567",
+        );
+        assert_eq!(result.unwrap(), (23, 12 * 1024 * 1024));
+    }
+
     #[test]
     fn test_parse_slog_stats_from_iostat_invalid_data() {
         let collector = ZfsStatsCollector::new(DemoCommandExecutor, DemoFilesystemReader);
@@ -1,33 +1,420 @@
+use serde::{Deserialize, Deserializer, de::Error};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+/// Wall-clock time a stats struct was collected. Any two compare equal, so
+/// otherwise identical stats stay equal however far apart they were taken
+#[derive(Debug, Clone, Copy)]
+#[allow(dead_code)]
+pub struct CollectedAt(pub SystemTime);
+
+impl CollectedAt {
+    pub fn now() -> Self {
+        Self(SystemTime::now())
+    }
+}
+
+impl Default for CollectedAt {
+    fn default() -> Self {
+        Self(SystemTime::UNIX_EPOCH)
+    }
+}
+
+impl PartialEq for CollectedAt {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// Read a float that was written as null because it wasn't finite
+fn nan_if_null<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::NAN))
+}
+
+/// Read a duration written in (fractional) seconds
+fn optional_seconds<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    Option::<f64>::deserialize(deserializer)?
+        .map(|seconds| Duration::try_from_secs_f64(seconds).map_err(D::Error::custom))
+        .transpose()
+}
+
 /// ARC (Adaptive Replacement Cache) statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct ArcStats {
+    #[serde(deserialize_with = "nan_if_null")]
     pub hit_rate: f64,
-    pub size: u64,     // Current cache size in bytes
-    pub target: u64,   // Target cache size in bytes
-    pub read_ops: u64, // Read operations per second
+    pub size: u64,                              // Current cache size in bytes
+    pub target: u64,                            // Current target size (`c`) in bytes
+    pub target_trend: Option<Trend>,            // Where `c` went since the previous sample
+    pub max: u64,                               // Hard ceiling (`c_max`) in bytes
+    pub read_ops: u64,                          // Read operations per second
+    pub hits_total: u64,                        // Cumulative ARC hits since boot
+    pub misses_total: u64,                      // Cumulative ARC misses since boot
+    pub hit_breakdown: Option<ArcHitBreakdown>, // Hits by access type, kstat sources only
+    pub eviction: Option<ArcEviction>,          // Eviction trouble per second, kstat sources only
+    pub metadata: Option<ArcMetadata>,          // Metadata held in the ARC, kstat sources only
+    pub no_grow: Option<bool>,                  // `arc_no_grow` is set, kstat sources only
+    pub prunes: Option<u64>,                    // `arc_prune` calls per second, kstat sources only
+    pub memory_total: Option<u64>,              // Physical RAM in bytes, if known
+    #[serde(skip)]
+    pub collected_at: CollectedAt, // When these stats were collected
+}
+
+impl ArcStats {
+    /// ARC size as a percentage of physical RAM
+    pub fn percent_of_memory(&self) -> Option<f64> {
+        self.memory_total
+            .filter(|&total| total > 0)
+            .map(|total| self.size as f64 / total as f64 * 100.0)
+    }
+
+    /// Share of all reads served from either cache since boot, in percent: ARC hits
+    /// plus L2ARC hits over ARC hits plus misses, as every L2ARC lookup is an ARC miss.
+    /// None without cumulative counters (arcstat fallback)
+    pub fn effective_hit_rate(&self, l2arc: &L2ArcStats) -> Option<f64> {
+        let reads = self.hits_total + self.misses_total;
+        (reads > 0).then(|| {
+            let l2_hits = l2arc.hits_total.min(self.misses_total);
+            (self.hits_total + l2_hits) as f64 / reads as f64 * 100.0
+        })
+    }
+}
+
+/// Aggregate I/O of the whole pool (top-level `zpool iostat` row)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PoolIoStats {
+    pub pool: String,
+    pub read_ops: u64,  // Read operations per second
+    pub write_ops: u64, // Write operations per second
+    pub read_bw: u64,   // Read bandwidth in bytes per second
+    pub write_bw: u64,  // Write bandwidth in bytes per second
+    // Allocated and free space in bytes; absent from captures made before
+    // capacity tracking, leaving it unknown
+    #[serde(default)]
+    pub alloc: u64,
+    #[serde(default)]
+    pub free: u64,
+}
+
+impl PoolIoStats {
+    /// Allocated space as a percentage of the pool's size, None if it's unknown
+    pub fn capacity_percent(&self) -> Option<f64> {
+        let size = self.alloc + self.free;
+        (size > 0).then(|| self.alloc as f64 / size as f64 * 100.0)
+    }
+}
+
+/// Reads and writes of a single dataset, from its `objset-*` kstat
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatasetStats {
+    pub name: String,
+    pub reads: u64,                // Read operations per second
+    pub writes: u64,               // Write operations per second
+    pub read_bytes: u64,           // Bytes read per second
+    pub write_bytes: u64,          // Bytes written per second
+    pub reads_total: u64,          // Cumulative read operations since import
+    pub writes_total: u64,         // Cumulative write operations since import
+    pub read_bytes_total: u64,     // Cumulative bytes read since import
+    pub write_bytes_total: u64,    // Cumulative bytes written since import
+    pub collected_at: CollectedAt, // When these stats were collected
+}
+
+/// Cumulative ARC hits split by access type
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ArcHitBreakdown {
+    pub demand_data: u64,
+    pub demand_metadata: u64,
+    pub prefetch: u64, // Prefetched data and metadata
+}
+
+impl ArcHitBreakdown {
+    pub fn total(&self) -> u64 {
+        self.demand_data + self.demand_metadata + self.prefetch
+    }
+
+    /// Share of each category in percent: (demand data, demand metadata, prefetch)
+    pub fn percentages(&self) -> (f64, f64, f64) {
+        let total = self.total();
+        if total == 0 {
+            return (0.0, 0.0, 0.0);
+        }
+        let percent = |hits: u64| hits as f64 / total as f64 * 100.0;
+        (
+            percent(self.demand_data),
+            percent(self.demand_metadata),
+            percent(self.prefetch),
+        )
+    }
+}
+
+/// Per-second rates of the ARC eviction counters that signal memory pressure
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ArcEviction {
+    pub evict_skip: u64,       // Buffers skipped because they were in use
+    pub evict_not_enough: u64, // Evictions that could not free as much as asked
+    pub mutex_miss: u64,       // Buffers skipped because their hash lock was held
+}
+
+impl ArcEviction {
+    /// Pressure judged by the eviction-skip rate, `None` while nothing is failing
+    pub fn pressure(&self) -> ArcPressure {
+        if self.evict_skip + self.evict_not_enough + self.mutex_miss == 0 {
+            return ArcPressure::None;
+        }
+        match self.evict_skip {
+            0..100 => ArcPressure::Low,
+            100..1000 => ArcPressure::Medium,
+            _ => ArcPressure::High,
+        }
+    }
+}
+
+/// Metadata held in the ARC, to tell whether `zfs_arc_meta_limit` holds back a
+/// metadata-heavy workload
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ArcMetadata {
+    pub size: u64,          // `arc_meta_used`, or `metadata_size` where that is gone
+    pub limit: Option<u64>, // `arc_meta_limit`; OpenZFS 2.2 dropped the hard limit
+}
+
+impl ArcMetadata {
+    /// Metadata as a percentage of the whole ARC
+    pub fn percent_of_arc(&self, arc_size: u64) -> f64 {
+        if arc_size == 0 {
+            return 0.0;
+        }
+        self.size as f64 / arc_size as f64 * 100.0
+    }
+
+    /// Metadata as a percentage of `arc_meta_limit`, if there is one
+    pub fn percent_of_limit(&self) -> Option<f64> {
+        self.limit
+            .filter(|&limit| limit > 0)
+            .map(|limit| self.size as f64 / limit as f64 * 100.0)
+    }
+}
+
+/// How hard the ARC struggles to evict; sustained high pressure suggests
+/// raising `zfs_arc_max`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArcPressure {
+    None,
+    Low,
+    Medium,
+    High,
+}
+
+impl std::fmt::Display for ArcPressure {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ArcPressure::None => write!(f, "none"),
+            ArcPressure::Low => write!(f, "low"),
+            ArcPressure::Medium => write!(f, "medium"),
+            ArcPressure::High => write!(f, "high"),
+        }
+    }
+}
+
+/// Direction a value moved in between two samples, e.g. the ARC target `c`
+/// growing while ZFS has memory to spare or shrinking under memory pressure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Trend {
+    Rising,
+    Falling,
+    Steady,
+}
+
+impl Trend {
+    /// Compare a value against its previous sample
+    pub fn between(previous: u64, current: u64) -> Self {
+        match current.cmp(&previous) {
+            std::cmp::Ordering::Greater => Trend::Rising,
+            std::cmp::Ordering::Less => Trend::Falling,
+            std::cmp::Ordering::Equal => Trend::Steady,
+        }
+    }
+
+    /// Arrow shown next to the value on the dashboard
+    pub fn arrow(&self) -> &'static str {
+        match self {
+            Trend::Rising => "↑",
+            Trend::Falling => "↓",
+            Trend::Steady => "→",
+        }
+    }
+}
+
+impl FromStr for Trend {
+    type Err = String;
+
+    /// Parse the `Display` form, e.g. "rising"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "rising" => Ok(Trend::Rising),
+            "falling" => Ok(Trend::Falling),
+            "steady" => Ok(Trend::Steady),
+            _ => Err(format!("Invalid trend '{}'", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for Trend {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl std::fmt::Display for Trend {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Trend::Rising => write!(f, "rising"),
+            Trend::Falling => write!(f, "falling"),
+            Trend::Steady => write!(f, "steady"),
+        }
+    }
 }
 
 /// L2ARC (Level 2 ARC) statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct L2ArcStats {
+    #[serde(deserialize_with = "nan_if_null")]
     pub hit_rate: f64,
-    pub size: u64,       // Cache size in bytes
-    pub read_bytes: u64, // Bytes read per second
-    pub total_ops: u64,  // Total operations per second
+    pub size: u64,              // Cache size in bytes
+    pub read_bytes: u64,        // Bytes read per second
+    pub write_bytes: u64,       // Bytes written (filled) per second
+    pub read_bytes_peak: u64,   // Highest read rate seen this session
+    pub total_ops: u64,         // Total operations per second
+    pub read_bytes_total: u64,  // Cumulative bytes read from the cache device
+    pub write_bytes_total: u64, // Cumulative bytes written (filled) to the cache device
+    pub cksum_bad: u64,         // Checksum failures reading from the cache device (cumulative)
+    pub io_errors: u64,         // I/O errors on the cache device (cumulative)
+    // Cumulative L2ARC hits and misses since boot, kstat sources only; absent
+    // from captures made before the effective hit rate
+    #[serde(default)]
+    pub hits_total: u64,
+    #[serde(default)]
+    pub misses_total: u64,
+    // Still filling up, so a low hit rate says little yet; absent from captures
+    // made before warm-up detection
+    #[serde(default)]
+    pub warming: bool,
+    #[serde(skip)]
+    pub collected_at: CollectedAt, // When these stats were collected
+}
+
+impl L2ArcStats {
+    /// Whether the cache device has reported checksum or I/O errors
+    pub fn has_errors(&self) -> bool {
+        self.cksum_bad > 0 || self.io_errors > 0
+    }
 }
 
 /// SLOG (Separate Intent Log) statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct SlogStats {
-    pub device: String,   // Device identifier (e.g., "mirror-1")
-    pub write_ops: u64,   // Write operations per second
-    pub write_bw: u64,    // Write bandwidth in bytes per second
+    pub device: String,       // Device identifier (e.g., "mirror-1")
+    pub display_name: String, // Short name for display (e.g., "sdc"), else the identifier
+    pub write_ops: u64,       // Write operations per second
+    pub write_bw: u64,        // Write bandwidth in bytes per second
+    pub write_bw_peak: u64,   // Highest write bandwidth seen this session
+    // Write operations and bytes per second, averaged since import; named
+    // `*_total` in schema version 1
+    #[serde(alias = "write_ops_total")]
+    pub write_ops_avg: u64,
+    #[serde(alias = "write_bw_total")]
+    pub write_bw_avg: u64,
+    #[serde(deserialize_with = "nan_if_null")]
     pub utilization: f64, // Device utilization percentage
-    pub latency: f64,     // Average latency in milliseconds
+    #[serde(deserialize_with = "nan_if_null")]
+    pub latency: f64, // Average latency in milliseconds
+    // Age of the iostat output when served from cache
+    #[serde(
+        default,
+        rename = "cache_age_seconds",
+        deserialize_with = "optional_seconds"
+    )]
+    pub cache_age: Option<Duration>,
+    #[serde(skip)]
+    pub collected_at: CollectedAt, // When these stats were collected
+    // Write latency percentiles from `zpool iostat -w`, if it is supported
+    pub latency_percentiles: Option<LatencyPercentiles>,
+    // State from `zpool status` when the device can't take writes (e.g. "FAULTED")
+    pub fault: Option<String>,
 }
 
-/// Overall cache performance status
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Write latency percentiles in milliseconds, read off a `zpool iostat -w`
+/// histogram, so each is the upper bound of the bucket it falls in
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct LatencyPercentiles {
+    #[serde(deserialize_with = "nan_if_null")]
+    pub p50: f64,
+    #[serde(deserialize_with = "nan_if_null")]
+    pub p95: f64,
+    #[serde(deserialize_with = "nan_if_null")]
+    pub p99: f64,
+}
+
+/// Top-level vdevs of a pool by the section of `zpool status` they are listed in;
+/// a mirror or raidz group is named once (e.g. "mirror-1"), not by its disks
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PoolVdevs {
+    pub data: Vec<String>,
+    pub log: Vec<String>,
+    pub cache: Vec<String>,
+    pub special: Vec<String>,
+    pub dedup: Vec<String>,
+    // Top-level vdevs whose state isn't ONLINE, e.g. ("nvme4n1p1", "FAULTED")
+    pub unhealthy: Vec<(String, String)>,
+    // State of the pool itself, e.g. "DEGRADED" while one of its vdevs is
+    pub state: Option<String>,
+}
+
+impl PoolVdevs {
+    /// State of a top-level vdev that isn't ONLINE
+    pub fn state_of(&self, vdev: &str) -> Option<&str> {
+        self.unhealthy
+            .iter()
+            .find(|(name, _)| name == vdev)
+            .map(|(_, state)| state.as_str())
+    }
+}
+
+/// What the `read` column of the arcstat fallback holds. arcstat diffs each
+/// sample against the previous one, and the first against zero:
+/// - `arcstat 1 1` prints only that first sample, so `read` is every ARC access
+///   since boot and has to be turned into a rate between refreshes
+/// - `arcstat 1 2` ends with a sample diffed against the first, so `read` is
+///   already accesses per second, at the cost of a second per refresh
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArcstatReads {
+    #[default]
+    Cumulative,
+    PerSecond,
+}
+
+impl FromStr for ArcstatReads {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cumulative" => Ok(ArcstatReads::Cumulative),
+            "rate" => Ok(ArcstatReads::PerSecond),
+            _ => Err(format!(
+                "Invalid arcstat reads '{}': expected cumulative or rate",
+                s
+            )),
+        }
+    }
+}
+
+/// Overall cache performance status, ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "String")]
 pub enum CacheStatus {
     Excellent,
     Good,
@@ -35,19 +422,121 @@ pub enum CacheStatus {
     Poor,
 }
 
+/// Hit rate cutoffs (in percent) used to classify a `CacheStatus`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CacheThresholds {
+    pub excellent: f64,
+    pub good: f64,
+    pub fair: f64,
+}
+
+impl Default for CacheThresholds {
+    fn default() -> Self {
+        Self {
+            excellent: 85.0,
+            good: 70.0,
+            fair: 50.0,
+        }
+    }
+}
+
+impl FromStr for CacheThresholds {
+    type Err = String;
+
+    /// Parse thresholds in the form "EXCELLENT,GOOD,FAIR" (e.g., "90,75,60")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s
+            .split(',')
+            .map(|part| part.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("Invalid thresholds '{}': expected numbers", s))?;
+
+        let [excellent, good, fair] = values[..] else {
+            return Err(format!(
+                "Invalid thresholds '{}': expected EXCELLENT,GOOD,FAIR",
+                s
+            ));
+        };
+
+        let ordered = 0.0 <= fair && fair <= good && good <= excellent && excellent <= 100.0;
+        if !ordered {
+            return Err(format!(
+                "Invalid thresholds '{}': expected 100 >= EXCELLENT >= GOOD >= FAIR >= 0",
+                s
+            ));
+        }
+
+        Ok(Self {
+            excellent,
+            good,
+            fair,
+        })
+    }
+}
+
 impl CacheStatus {
-    /// Determine status based on hit rate percentage
+    /// Determine status based on hit rate percentage, by the default thresholds
+    #[cfg(test)]
     pub fn from_hit_rate(hit_rate: f64) -> Self {
-        if hit_rate >= 85.0 {
+        Self::from_hit_rate_with_thresholds(hit_rate, &CacheThresholds::default())
+    }
+
+    /// Determine status based on hit rate percentage and custom cutoffs
+    pub fn from_hit_rate_with_thresholds(hit_rate: f64, thresholds: &CacheThresholds) -> Self {
+        if hit_rate >= thresholds.excellent {
             CacheStatus::Excellent
-        } else if hit_rate >= 70.0 {
+        } else if hit_rate >= thresholds.good {
             CacheStatus::Good
-        } else if hit_rate >= 50.0 {
+        } else if hit_rate >= thresholds.fair {
             CacheStatus::Fair
         } else {
             CacheStatus::Poor
         }
     }
+
+    /// Color the status is shown in; callers decide whether to style at all
+    pub fn color(&self) -> console::Style {
+        let style = console::Style::new();
+        match self {
+            CacheStatus::Excellent => style.green(),
+            CacheStatus::Good => style.cyan(),
+            CacheStatus::Fair => style.yellow(),
+            CacheStatus::Poor => style.red(),
+        }
+    }
+
+    /// One-character marker for the status, readable without color
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            CacheStatus::Excellent => "✓",
+            CacheStatus::Good => "~",
+            CacheStatus::Fair => "!",
+            CacheStatus::Poor => "✗",
+        }
+    }
+}
+
+impl FromStr for CacheStatus {
+    type Err = String;
+
+    /// Parse the `Display` form, e.g. "Excellent"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Excellent" => Ok(CacheStatus::Excellent),
+            "Good" => Ok(CacheStatus::Good),
+            "Fair" => Ok(CacheStatus::Fair),
+            "Poor" => Ok(CacheStatus::Poor),
+            _ => Err(format!("Invalid cache status '{}'", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for CacheStatus {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
 }
 
 impl std::fmt::Display for CacheStatus {
@@ -60,3 +549,156 @@ impl std::fmt::Display for CacheStatus {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_status_color_and_symbol() {
+        let cases = [
+            (CacheStatus::Excellent, console::Style::new().green(), "✓"),
+            (CacheStatus::Good, console::Style::new().cyan(), "~"),
+            (CacheStatus::Fair, console::Style::new().yellow(), "!"),
+            (CacheStatus::Poor, console::Style::new().red(), "✗"),
+        ];
+        for (status, color, symbol) in cases {
+            assert_eq!(status.color(), color, "{}", status);
+            assert_eq!(status.symbol(), symbol, "{}", status);
+        }
+    }
+
+    #[test]
+    fn test_arc_hit_breakdown_percentages() {
+        let breakdown = ArcHitBreakdown {
+            demand_data: 750,
+            demand_metadata: 200,
+            prefetch: 50,
+        };
+        assert_eq!(breakdown.total(), 1000);
+        assert_eq!(breakdown.percentages(), (75.0, 20.0, 5.0));
+
+        let empty = ArcHitBreakdown {
+            demand_data: 0,
+            demand_metadata: 0,
+            prefetch: 0,
+        };
+        assert_eq!(empty.percentages(), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_effective_hit_rate_counts_l2arc_hits() {
+        let arc = |hits_total, misses_total| ArcStats {
+            hit_rate: 0.0,
+            size: 0,
+            target: 0,
+            target_trend: None,
+            max: 0,
+            read_ops: 0,
+            hits_total,
+            misses_total,
+            hit_breakdown: None,
+            eviction: None,
+            metadata: None,
+            no_grow: None,
+            prunes: None,
+            memory_total: None,
+            collected_at: CollectedAt::default(),
+        };
+        let l2arc = |hits_total, misses_total| L2ArcStats {
+            hit_rate: 0.0,
+            size: 0,
+            read_bytes: 0,
+            write_bytes: 0,
+            read_bytes_peak: 0,
+            total_ops: 0,
+            read_bytes_total: 0,
+            write_bytes_total: 0,
+            cksum_bad: 0,
+            io_errors: 0,
+            hits_total,
+            misses_total,
+            warming: false,
+            collected_at: CollectedAt::default(),
+        };
+
+        // 900 of 1000 reads hit the ARC, 60 of the 100 misses hit the L2ARC
+        assert_eq!(arc(900, 100).effective_hit_rate(&l2arc(60, 40)), Some(96.0));
+        assert_eq!(arc(900, 100).effective_hit_rate(&l2arc(0, 100)), Some(90.0));
+        // Counters sampled a moment apart can't push it past every miss
+        assert_eq!(
+            arc(900, 100).effective_hit_rate(&l2arc(150, 0)),
+            Some(100.0)
+        );
+        // arcstat fallback has no counters
+        assert_eq!(arc(0, 0).effective_hit_rate(&l2arc(60, 40)), None);
+    }
+
+    #[test]
+    fn test_from_hit_rate_default_thresholds() {
+        assert_eq!(CacheStatus::from_hit_rate(85.0), CacheStatus::Excellent);
+        assert_eq!(CacheStatus::from_hit_rate(70.0), CacheStatus::Good);
+        assert_eq!(CacheStatus::from_hit_rate(50.0), CacheStatus::Fair);
+        assert_eq!(CacheStatus::from_hit_rate(49.9), CacheStatus::Poor);
+    }
+
+    #[test]
+    fn test_from_hit_rate_custom_thresholds() {
+        let strict = CacheThresholds {
+            excellent: 95.0,
+            good: 90.0,
+            fair: 80.0,
+        };
+
+        // Same hit rate, different classification than the defaults
+        assert_eq!(CacheStatus::from_hit_rate(88.0), CacheStatus::Excellent);
+        assert_eq!(
+            CacheStatus::from_hit_rate_with_thresholds(88.0, &strict),
+            CacheStatus::Fair
+        );
+
+        assert_eq!(CacheStatus::from_hit_rate(75.0), CacheStatus::Good);
+        assert_eq!(
+            CacheStatus::from_hit_rate_with_thresholds(75.0, &strict),
+            CacheStatus::Poor
+        );
+
+        let lenient = CacheThresholds {
+            excellent: 60.0,
+            good: 40.0,
+            fair: 20.0,
+        };
+        assert_eq!(CacheStatus::from_hit_rate(45.0), CacheStatus::Poor);
+        assert_eq!(
+            CacheStatus::from_hit_rate_with_thresholds(45.0, &lenient),
+            CacheStatus::Good
+        );
+    }
+
+    #[test]
+    fn test_thresholds_from_str() {
+        let thresholds: CacheThresholds = "90,75,60".parse().unwrap();
+        assert_eq!(
+            thresholds,
+            CacheThresholds {
+                excellent: 90.0,
+                good: 75.0,
+                fair: 60.0,
+            }
+        );
+
+        let thresholds: CacheThresholds = " 99.5 , 80 , 0 ".parse().unwrap();
+        assert_eq!(thresholds.excellent, 99.5);
+        assert_eq!(thresholds.fair, 0.0);
+    }
+
+    #[test]
+    fn test_thresholds_from_str_invalid() {
+        assert!("90,75".parse::<CacheThresholds>().is_err());
+        assert!("90,75,60,50".parse::<CacheThresholds>().is_err());
+        assert!("90,abc,60".parse::<CacheThresholds>().is_err());
+        assert!("60,75,90".parse::<CacheThresholds>().is_err()); // Not descending
+        assert!("150,75,60".parse::<CacheThresholds>().is_err()); // Out of range
+        assert!("90,75,-1".parse::<CacheThresholds>().is_err());
+    }
+}